@@ -44,12 +44,122 @@ METADATA EXAMPLE:
 // smoelius: Please keep the last four fields `args`, `operation`, `lib_sel`, and `output`, in that
 // order. Please keep all other fields sorted.
 struct Dylint {
+    #[clap(
+        long,
+        help = "Activate all available features of the packages being checked"
+    )]
+    all_features: bool,
+
+    #[clap(
+        long,
+        requires("fix_noconflict"),
+        help = "With `--fix-noconflict`, run `cargo check` after applying suggestions. If it \
+                fails, warn about the lints whose suggestions cause the failure, and keep only \
+                the other lints' suggestions. Fail if no single lint's suggestions are to blame."
+    )]
+    allow_downgrade: bool,
+
+    #[clap(
+        long,
+        help = "Like `--keep-going`, but report build and compilation failures as warnings, and \
+                exit successfully"
+    )]
+    collect_only: bool,
+
+    #[clap(
+        long,
+        conflicts_with("pipe_stdout"),
+        help = "Write the diagnostics for dependencies before those for the packages being \
+                checked (implies `--message-format=json` for `cargo check`). With \
+                `--message-format=json-lines`, a `dylint_primary` field is added to each object, \
+                indicating whether the diagnostic is for a package being checked."
+    )]
+    deps_first: bool,
+
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        long = "env",
+        value_name = "KEY=VALUE[@TOOLCHAIN]",
+        help = "Set environment variable <KEY> to <VALUE> when checking with <TOOLCHAIN>, or with \
+                every toolchain if `@TOOLCHAIN` is omitted (may be repeated). The text after the \
+                last `@`, if any, is the toolchain; end <VALUE> with `@` if it contains `@` and \
+                should apply to every toolchain."
+    )]
+    envs: Vec<String>,
+
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        long = "exclude-lint",
+        value_name = "NAME",
+        help = "Do not report lint <NAME> (may be repeated)"
+    )]
+    exclude_lints: Vec<String>,
+
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        short = 'F',
+        long,
+        value_name = "FEATURES",
+        help = "Space or comma separated list of features of the packages being checked to \
+                activate"
+    )]
+    features: Vec<String>,
+
     #[clap(long, help = "Automatically apply lint suggestions")]
     fix: bool,
 
-    #[clap(long, help = "Continue if `cargo check` fails")]
+    #[clap(
+        long,
+        requires("fix"),
+        help = "With `--fix`, apply only machine-applicable suggestions that do not overlap one \
+                another, and leave the rest for a subsequent run. A summary of the fixes applied, \
+                grouped by lint, is printed afterward."
+    )]
+    fix_noconflict: bool,
+
+    #[clap(
+        long,
+        requires("fix_noconflict"),
+        help = "With `--fix-noconflict`, run `rustfmt` on the files to which suggestions were \
+                applied. If `rustfmt` is not installed, a warning is printed instead."
+    )]
+    fmt_after_fix: bool,
+
+    #[clap(
+        long,
+        help = "Run each library in its own `cargo check` invocation (slower, but a library that \
+                crashes does not prevent other libraries from running)"
+    )]
+    isolate_libraries: bool,
+
+    #[clap(
+        long,
+        visible_alias = "no-fail-fast",
+        help = "Continue if a library fails to build or `cargo check` fails, and report the \
+                failures at the end"
+    )]
     keep_going: bool,
 
+    #[clap(
+        long,
+        value_name = "FMT",
+        value_parser = ["json-lines"],
+        help = "Write each diagnostic produced by a Dylint lint to stdout as it arrives, as one \
+                JSON object per line (`json-lines` is the only supported format). Each object is \
+                a Cargo `compiler-message` with an added `dylint_toolchain` field. Rendered \
+                diagnostics are still written to stderr."
+    )]
+    message_format: Option<String>,
+
+    #[clap(
+        long,
+        help = "Do not activate the `default` feature of the packages being checked"
+    )]
+    no_default_features: bool,
+
     #[clap(long, help = "Do not check other packages within the workspace")]
     no_deps: bool,
 
@@ -63,9 +173,81 @@ struct Dylint {
     )]
     packages: Vec<String>,
 
+    #[clap(
+        long,
+        value_name = "PATH",
+        requires("fix"),
+        help = "With `--fix`, write the machine-applicable suggestions that do not overlap one \
+                another to <PATH> as a unified diff, rather than modifying any files"
+    )]
+    patch: Option<String>,
+
+    #[clap(
+        long,
+        help = "Print the path of the driver for each toolchain required by the named libraries \
+                and exit. If no libraries are named, print the path of the driver for the active \
+                toolchain."
+    )]
+    print_driver_path: bool,
+
+    #[clap(
+        long,
+        help = "Print the libraries that would be loaded, as a JSON object mapping each toolchain \
+                to an array of library paths, and exit without running `cargo check`"
+    )]
+    print_libs_json: bool,
+
+    #[clap(
+        long,
+        help = "Print the sysroot the driver uses for each toolchain required by the named \
+                libraries and exit. If no libraries are named, print the sysroot for the active \
+                toolchain."
+    )]
+    print_sysroot: bool,
+
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with("pipe_stdout"),
+        help = "Write a JSON summary of the run (libraries, toolchains, diagnostic counts, and \
+                wall time) to <PATH>"
+    )]
+    stats: Option<String>,
+
+    #[clap(
+        long,
+        conflicts_with("pipe_stdout"),
+        help = "Print a one-line summary of the diagnostics produced by Dylint lints to stderr \
+                once the run completes"
+    )]
+    summary: bool,
+
+    #[clap(
+        long,
+        help = "Have the driver log each library as it is loaded, and the lints each library \
+                registers, in registration order. The messages are logged at the `info` level \
+                with target `dylint_driver`. If `RUST_LOG` is not set, it is set to \
+                `dylint_driver=info`; otherwise, `RUST_LOG` must enable those messages. Unlike \
+                Cargo's `--verbose`, this flag does not affect Cargo's output."
+    )]
+    verbose: bool,
+
     #[clap(long, help = "Check all packages in the workspace")]
     workspace: bool,
 
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        long = "workspace-dir",
+        value_name = "DIR",
+        conflicts_with_all(["patch", "stats"]),
+        help = "Check the workspace in <DIR> rather than the current one (may be repeated). The \
+                libraries are resolved once and used to check each workspace in turn, with that \
+                workspace's `dylint.toml` file. The results are reported per workspace, and \
+                Dylint fails if checking any workspace fails."
+    )]
+    workspace_dirs: Vec<String>,
+
     #[clap(last = true, help = "Arguments for `cargo check`")]
     args: Vec<String>,
 
@@ -81,6 +263,33 @@ struct Dylint {
 
 #[derive(Debug, Parser)]
 enum Operation {
+    #[clap(
+        about = "Check `dylint.toml` against the named libraries",
+        long_about = "Load each named library and have it deserialize its section of the \
+workspace's `dylint.toml` file, without running any lints. Libraries opt in by invoking \
+`dylint_linting::dylint_validate_config!`; other libraries are not checked."
+    )]
+    CheckConfig {
+        #[clap(flatten)]
+        lib_sel: LibrarySelection,
+    },
+
+    #[clap(
+        about = "Check a single file",
+        long_about = "Run the driver directly on the file at <PATH>, without invoking Cargo, and \
+with the named libraries loaded"
+    )]
+    CheckFile {
+        #[clap(help = "Path to the file to check")]
+        path: String,
+
+        #[clap(last = true, help = "Arguments for `rustc`, e.g., `--edition=2021`")]
+        args: Vec<String>,
+
+        #[clap(flatten)]
+        lib_sel: LibrarySelection,
+    },
+
     #[clap(
         about = "List libraries or lints",
         long_about = "If no libraries are named, list the name, toolchain, and location of all \
@@ -89,9 +298,51 @@ discovered libraries.
 If at least one library is named, list the name, level, and description of all lints in all named \
 libraries.
 
-Combine with `--all` to list all lints in all discovered libraries."
+Combine with `--all` to list all lints in all discovered libraries.
+
+With `--toolchains`, list the distinct toolchains required by all discovered libraries."
     )]
     List {
+        #[clap(
+            long,
+            value_name = "N",
+            help = "Fail if any named library does not register exactly <N> lints"
+        )]
+        assert_lint_count: Option<usize>,
+
+        #[clap(
+            long,
+            conflicts_with("assert_lint_count"),
+            help = "List the configuration keys accepted by each named library, instead of its \
+                    lints"
+        )]
+        configs: bool,
+
+        #[clap(
+            long,
+            value_name = "FMT",
+            value_parser = ["human", "json"],
+            requires("toolchains"),
+            help = "With `--toolchains`, print the toolchains in the given format (`human` or \
+                    `json`)"
+        )]
+        message_format: Option<String>,
+
+        #[clap(
+            long,
+            conflicts_with_all(["assert_lint_count", "configs", "with_docs"]),
+            help = "List the distinct toolchains required by the discovered libraries, one per \
+                    line, instead of the libraries or lints"
+        )]
+        toolchains: bool,
+
+        #[clap(
+            long,
+            help = "Print each lint's description on the lines following its name and level, \
+                    preserving line breaks"
+        )]
+        with_docs: bool,
+
         #[clap(flatten)]
         lib_sel: LibrarySelection,
     },
@@ -138,6 +389,7 @@ Combine with `--all` to list all lints in all discovered libraries."
     },
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 #[cfg_attr(feature = "__clap_headings", clap(next_help_heading = Some("Library Selection")))]
 struct LibrarySelection {
@@ -151,6 +403,13 @@ struct LibrarySelection {
     )]
     branch: Option<String>,
 
+    #[clap(
+        long,
+        help = "Fail rather than build a driver that is missing or outdated (e.g., to detect CI \
+                cache misses)"
+    )]
+    frozen_driver: bool,
+
     #[clap(
         long,
         value_name = "URL",
@@ -159,6 +418,25 @@ struct LibrarySelection {
     )]
     git: Option<String>,
 
+    #[clap(
+        long,
+        value_name = "N",
+        help = "Number of times to retry fetching a git library source after a network error \
+                (default: 0)"
+    )]
+    git_retries: Option<usize>,
+
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        long = "lib-dir",
+        value_name = "PATH",
+        help = "Directory to load lints from. The directory is searched recursively for files with \
+                names of the form \"DLL_PREFIX <NAME> '@' TOOLCHAIN DLL_SUFFIX\"; other files are \
+                skipped."
+    )]
+    lib_dirs: Vec<String>,
+
     #[clap(
         action = ArgAction::Append,
         number_of_values = 1,
@@ -176,7 +454,8 @@ struct LibrarySelection {
         help = "Library name to load lints from. A file with a name of the form \"DLL_PREFIX \
         <NAME> '@' TOOLCHAIN DLL_SUFFIX\" is searched for in the directories listed in \
         DYLINT_LIBRARY_PATH, and in the `target/release` directories produced by building the \
-        current workspace's metadata entries (see example below)."
+        current workspace's metadata entries (see example below). If libraries with the same \
+        name were built with multiple toolchains, use <NAME>@TOOLCHAIN to select one."
     )]
     libs: Vec<String>,
 
@@ -210,6 +489,12 @@ struct LibrarySelection {
     )]
     pattern: Option<String>,
 
+    #[clap(
+        long,
+        help = "Fail if the named libraries require more than one toolchain"
+    )]
+    require_single_toolchain: bool,
+
     #[clap(
         long,
         requires("git"),
@@ -217,6 +502,20 @@ struct LibrarySelection {
     )]
     rev: Option<String>,
 
+    #[clap(
+        long,
+        help = "With `--all`, warn about and skip libraries that fail to build, rather than \
+                failing"
+    )]
+    skip_unbuildable: bool,
+
+    #[clap(
+        long,
+        help = "Strip debug info from the libraries Dylint builds (i.e., workspace metadata \
+                entries and `--git` or `--path` library packages)"
+    )]
+    strip: bool,
+
     #[clap(
         long,
         requires("git"),
@@ -247,11 +546,31 @@ struct OutputOptions {
 impl From<Dylint> for dylint::opts::Dylint {
     fn from(opts: Dylint) -> Self {
         let Dylint {
+            all_features,
+            allow_downgrade,
+            collect_only,
+            deps_first,
+            envs,
+            exclude_lints,
+            features,
             fix,
+            fix_noconflict,
+            fmt_after_fix,
+            isolate_libraries,
             keep_going,
+            message_format,
+            no_default_features,
             no_deps,
             packages,
+            patch,
+            print_driver_path,
+            print_libs_json,
+            print_sysroot,
+            stats,
+            summary,
+            verbose,
             workspace,
+            workspace_dirs,
             args,
             operation,
             mut lib_sel,
@@ -266,18 +585,68 @@ impl From<Dylint> for dylint::opts::Dylint {
             None => dylint::opts::Operation::Check({
                 dylint::opts::Check {
                     lib_sel: lib_sel.into(),
+                    all_features,
+                    allow_downgrade,
+                    collect_only,
+                    deps_first,
+                    envs,
+                    exclude_lints,
+                    features,
                     fix,
+                    fix_noconflict,
+                    fmt_after_fix,
+                    isolate_libraries,
                     keep_going,
+                    message_format,
+                    no_default_features,
                     no_deps,
                     packages,
+                    patch,
+                    print_driver_path,
+                    print_libs_json,
+                    print_sysroot,
+                    stats,
+                    summary,
+                    verbose,
                     workspace,
+                    workspace_dirs,
                     args,
                 }
             }),
-            Some(Operation::List { lib_sel: other }) => {
+            Some(Operation::CheckConfig { lib_sel: other }) => {
+                lib_sel.absorb(other);
+                dylint::opts::Operation::CheckConfig(dylint::opts::CheckConfig {
+                    lib_sel: lib_sel.into(),
+                })
+            }
+            Some(Operation::CheckFile {
+                path,
+                args,
+                lib_sel: other,
+            }) => {
+                lib_sel.absorb(other);
+                dylint::opts::Operation::CheckFile(dylint::opts::CheckFile {
+                    lib_sel: lib_sel.into(),
+                    path,
+                    args,
+                })
+            }
+            Some(Operation::List {
+                assert_lint_count,
+                configs,
+                message_format,
+                toolchains,
+                with_docs,
+                lib_sel: other,
+            }) => {
                 lib_sel.absorb(other);
                 dylint::opts::Operation::List(dylint::opts::List {
                     lib_sel: lib_sel.into(),
+                    assert_lint_count,
+                    configs,
+                    message_format,
+                    toolchains,
+                    with_docs,
                 })
             }
             Some(Operation::New { isolate, path }) => {
@@ -322,7 +691,10 @@ impl LibrarySelection {
         let Self {
             all,
             branch,
+            frozen_driver,
             git,
+            git_retries,
+            lib_dirs,
             lib_paths,
             libs,
             manifest_path,
@@ -330,12 +702,18 @@ impl LibrarySelection {
             no_metadata,
             paths,
             pattern,
+            require_single_toolchain,
             rev,
+            skip_unbuildable,
+            strip,
             tag,
         } = other;
         self.all |= all;
         option_absorb!(&mut self.branch, branch);
+        self.frozen_driver |= frozen_driver;
         option_absorb!(&mut self.git, git);
+        option_absorb!(&mut self.git_retries, git_retries);
+        self.lib_dirs.extend(lib_dirs);
         self.lib_paths.extend(lib_paths);
         self.libs.extend(libs);
         option_absorb!(&mut self.manifest_path, manifest_path);
@@ -343,7 +721,10 @@ impl LibrarySelection {
         self.no_metadata |= no_metadata;
         self.paths.extend(paths);
         option_absorb!(&mut self.pattern, pattern);
+        self.require_single_toolchain |= require_single_toolchain;
         option_absorb!(&mut self.rev, rev);
+        self.skip_unbuildable |= skip_unbuildable;
+        self.strip |= strip;
         option_absorb!(&mut self.tag, tag);
     }
 }
@@ -353,7 +734,10 @@ impl From<LibrarySelection> for dylint::opts::LibrarySelection {
         let LibrarySelection {
             all,
             branch,
+            frozen_driver,
             git,
+            git_retries,
+            lib_dirs,
             lib_paths,
             libs,
             manifest_path,
@@ -361,13 +745,19 @@ impl From<LibrarySelection> for dylint::opts::LibrarySelection {
             no_metadata,
             paths,
             pattern,
+            require_single_toolchain,
             rev,
+            skip_unbuildable,
+            strip,
             tag,
         } = lib_sel;
         Self {
             all,
             branch,
+            frozen_driver,
             git,
+            git_retries: git_retries.unwrap_or_default(),
+            lib_dirs,
             lib_paths,
             libs,
             manifest_path,
@@ -375,7 +765,10 @@ impl From<LibrarySelection> for dylint::opts::LibrarySelection {
             no_metadata,
             paths,
             pattern,
+            require_single_toolchain,
             rev,
+            skip_unbuildable,
+            strip,
             tag,
         }
     }
@@ -0,0 +1,68 @@
+use assert_cmd::prelude::*;
+use dylint_internal::{env, testing::new_template};
+use predicates::prelude::*;
+use std::fs::{read_to_string, write};
+use tempfile::tempdir;
+
+/// Verify that, with `DYLINT_CATCH_PANICS` enabled, a panicking lint pass is reported as an error
+/// and other libraries' lint passes still run.
+#[test]
+fn catch_panics() {
+    let panicking_dir = tempdir().unwrap();
+    let tempdir = tempdir().unwrap();
+
+    new_template(panicking_dir.path()).unwrap();
+
+    let path = panicking_dir.path().join("src/lib.rs");
+    let contents = read_to_string(&path).unwrap();
+    write(
+        &path,
+        contents.replace(
+            "impl<'tcx> LateLintPass<'tcx> for FillMeIn {",
+            r#"impl<'tcx> LateLintPass<'tcx> for FillMeIn {
+    fn check_crate(&mut self, _: &rustc_lint::LateContext<'tcx>) {
+        panic!("`fill_me_in` is broken");
+    }
+"#,
+        ),
+    )
+    .unwrap();
+
+    std::process::Command::new("cargo")
+        .current_dir(&tempdir)
+        .args(["init", "--name", "catch_panics_test"])
+        .assert()
+        .success();
+
+    write(
+        tempdir.path().join("src/main.rs"),
+        "#![allow(dead_code)]\n\nfn main() {}\n",
+    )
+    .unwrap();
+
+    let mut command = std::process::Command::cargo_bin("cargo-dylint").unwrap();
+    command.args([
+        "dylint",
+        "--manifest-path",
+        &tempdir.path().join("Cargo.toml").to_string_lossy(),
+        "--path",
+        &panicking_dir.path().to_string_lossy(),
+        "--path",
+        "../examples/general/crate_wide_allow",
+    ]);
+
+    command.assert().failure();
+
+    command
+        .env(env::DYLINT_CATCH_PANICS, "1")
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains(
+                "lint pass `FillMeIn` from `fill_me_in` panicked: `fill_me_in` is broken",
+            )
+            .and(predicate::str::contains(
+                "silently overrides `--warn dead-code` and `--deny dead-code`",
+            )),
+        );
+}
@@ -0,0 +1,26 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn check_config() {
+    for (fixture, valid) in [("valid", true), ("invalid", false)] {
+        let assert = std::process::Command::cargo_bin("cargo-dylint")
+            .unwrap()
+            .current_dir(format!("../fixtures/check_config/{fixture}"))
+            .args([
+                "dylint",
+                "check-config",
+                "--path",
+                "../../../examples/general/non_local_effect_before_error_return",
+            ])
+            .assert();
+
+        if valid {
+            assert.success().stdout(predicate::str::contains("    ok"));
+        } else {
+            assert
+                .failure()
+                .stderr(predicate::str::contains("work_limit"));
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs::write;
+use tempfile::tempdir;
+
+#[test]
+fn check_file() {
+    let tempdir = tempdir().unwrap();
+
+    let path = tempdir.path().join("main.rs");
+
+    write(&path, "#![allow(dead_code)]\n\nfn main() {}\n").unwrap();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "check-file",
+            &path.to_string_lossy(),
+            "--path",
+            "../examples/general/crate_wide_allow",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "silently overrides `--warn dead-code` and `--deny dead-code`",
+        ));
+}
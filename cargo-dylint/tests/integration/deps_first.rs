@@ -0,0 +1,51 @@
+use assert_cmd::prelude::*;
+
+// `b` is a dependency of `c`, so Cargo checks `b` first, and `b`'s diagnostics precede `c`'s
+// regardless of `--deps-first`. What `--deps-first` adds is the `dylint_primary` field, which tells
+// the two apart.
+#[test]
+fn deps_first() {
+    let assert = std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/no_deps")
+        .args([
+            "dylint",
+            "--path",
+            "../../examples/restriction/question_mark_in_expression",
+            "--package",
+            "c",
+            "--deps-first",
+            "--message-format=json-lines",
+        ])
+        .assert()
+        .success();
+
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    let stderr = std::str::from_utf8(&assert.get_output().stderr).unwrap();
+
+    let tagged_files = stdout
+        .lines()
+        .map(|line| {
+            let message = serde_json::from_str::<serde_json::Value>(line).unwrap();
+            let file_name = message["message"]["spans"][0]["file_name"]
+                .as_str()
+                .unwrap()
+                .replace('\\', "/");
+            let primary = message["dylint_primary"].as_bool().unwrap();
+            (file_name, primary)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (String::from("b/src/lib.rs"), false),
+            (String::from("c/src/lib.rs"), true)
+        ],
+        tagged_files,
+        "{stdout}"
+    );
+
+    let b_position = stderr.find("b/src/lib.rs").unwrap();
+    let c_position = stderr.find("c/src/lib.rs").unwrap();
+    assert!(b_position < c_position, "{stderr}");
+}
@@ -0,0 +1,41 @@
+use assert_cmd::prelude::*;
+use dylint_internal::{rustup::SanitizeEnvironment, CommandExt};
+use predicates::prelude::*;
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    path::Path,
+};
+
+#[test]
+fn diagnostic_handler() {
+    let handler_dir = Path::new("../fixtures/diagnostic_handler/handler");
+
+    dylint_internal::cargo::build("diagnostic handler")
+        .build()
+        .sanitize_environment()
+        .current_dir(handler_dir)
+        .success()
+        .unwrap();
+
+    let handler_path = handler_dir
+        .canonicalize()
+        .unwrap()
+        .join("target/debug")
+        .join(format!("{DLL_PREFIX}diagnostic_handler{DLL_SUFFIX}"));
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/diagnostic_handler/package")
+        .env("DYLINT_DIAGNOSTIC_HANDLER", handler_path)
+        .args([
+            "dylint",
+            "--path",
+            "../../../examples/restriction/question_mark_in_expression",
+        ])
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("warning: handled")
+                .and(predicate::str::contains("using the `?` operator").not()),
+        );
+}
@@ -0,0 +1,23 @@
+use assert_cmd::prelude::*;
+use dylint_internal::env;
+use predicates::prelude::*;
+
+// `dylint_testing`'s `timeout` option sets `DYLINT_DRIVER_TIMEOUT`. A one-millisecond timeout
+// expires before the driver can finish checking even an empty crate.
+#[test]
+fn driver_timeout() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/driver_timeout")
+        .env(env::DYLINT_DRIVER_TIMEOUT, "1")
+        .args([
+            "dylint",
+            "--path",
+            "../../examples/general/crate_wide_allow",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "dylint-driver timed out after 1ms",
+        ));
+}
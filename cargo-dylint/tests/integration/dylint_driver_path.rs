@@ -1,3 +1,4 @@
+use assert_cmd::prelude::*;
 use dylint_internal::{
     driver as dylint_driver, env,
     rustup::{toolchain_path, SanitizeEnvironment},
@@ -37,3 +38,85 @@ fn dylint_driver_path() {
     .unwrap();
     command.success().unwrap();
 }
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn print_driver_path() {
+    let tempdir = tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+
+    new_template(tempdir.path()).unwrap();
+
+    let dylint_driver_path = tempdir.path().join("target/dylint_drivers");
+
+    create_dir_all(&dylint_driver_path).unwrap();
+
+    let toolchain_path = toolchain_path(tempdir.path()).unwrap();
+    let toolchain = toolchain_path.iter().last().unwrap();
+
+    // No library is named, so the driver for the template's toolchain should be printed.
+    let assert = std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .envs([(env::DYLINT_DRIVER_PATH, &*dylint_driver_path)])
+        .args(["dylint", "--print-driver-path"])
+        .assert()
+        .success();
+
+    let driver = dylint_driver_path
+        .join(toolchain)
+        .join("dylint-driver")
+        .canonicalize()
+        .unwrap();
+
+    assert.stdout(format!("{}\n", driver.to_string_lossy()));
+}
+
+#[test]
+fn print_sysroot() {
+    let tempdir = tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+
+    new_template(tempdir.path()).unwrap();
+
+    let toolchain_path = toolchain_path(tempdir.path()).unwrap();
+
+    // No library is named, so the sysroot for the template's toolchain should be printed.
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--print-sysroot"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", toolchain_path.to_string_lossy()));
+}
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn frozen_driver() {
+    let tempdir = tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+
+    new_template(tempdir.path()).unwrap();
+
+    let dylint_driver_path = tempdir.path().join("target/dylint_drivers");
+
+    create_dir_all(&dylint_driver_path).unwrap();
+
+    let toolchain_path = toolchain_path(tempdir.path()).unwrap();
+    let toolchain = toolchain_path.iter().last().unwrap();
+
+    // `dylint_driver_path` is empty, so the driver would have to be built.
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .envs([(env::DYLINT_DRIVER_PATH, &*dylint_driver_path)])
+        .args(["dylint", "--print-driver-path", "--frozen-driver"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "`--frozen-driver` was used, but the driver at `{}` for toolchain `{}` is missing",
+            dylint_driver_path
+                .join(toolchain)
+                .join("dylint-driver")
+                .to_string_lossy(),
+            toolchain.to_string_lossy()
+        )));
+}
@@ -0,0 +1,27 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn features() {
+    for (args, expected) in [
+        (&[][..], true),
+        (&["--no-default-features"][..], false),
+        (&["--no-default-features", "--features", "greet"][..], true),
+        (&["--no-default-features", "--all-features"][..], true),
+    ] {
+        std::process::Command::cargo_bin("cargo-dylint")
+            .unwrap()
+            .current_dir("../fixtures/features")
+            .args([
+                "dylint",
+                "--path",
+                "../../examples/restriction/question_mark_in_expression",
+            ])
+            .args(args)
+            .assert()
+            .success()
+            .stderr(predicate::function(|stderr: &str| {
+                stderr.contains("src/lib.rs") == expected
+            }));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::util::init_package;
 use anyhow::{anyhow, Context, Result};
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 use std::{
     fs::{read_to_string, write, OpenOptions},
     io::Write,
@@ -35,35 +37,122 @@ fn main() {
 }
 "#;
 
+const UNTOUCHED_RS: &str = "pub fn untouched( ) { }\n";
+
 #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
 #[test]
 fn fix() {
     let tempdir = tempdir().unwrap();
 
-    std::process::Command::new("cargo")
+    init_fix_package(tempdir.path());
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--lib", LIB_NAME, "--fix", "--", "--allow-dirty"])
+        .assert()
+        .success();
+
+    let main_actual = read_to_string(tempdir.path().join("src/main.rs")).unwrap();
+
+    assert_eq!(MAIN_FIXED, main_actual);
+}
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn patch() {
+    let tempdir = tempdir().unwrap();
+
+    init_fix_package(tempdir.path());
+
+    let patch_path = tempdir.path().join("fix.diff");
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
         .current_dir(&tempdir)
         .args([
-            "init",
-            "--edition=2018",
-            "--name",
-            tempdir
-                .path()
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .trim_start_matches('.'),
+            "dylint",
+            "--lib",
+            LIB_NAME,
+            "--fix",
+            "--patch",
+            &patch_path.to_string_lossy(),
         ])
         .assert()
         .success();
 
-    append_workspace_metadata(tempdir.path()).unwrap();
+    let main_actual = read_to_string(tempdir.path().join("src/main.rs")).unwrap();
 
-    write(tempdir.path().join("src/main.rs"), MAIN_RS).unwrap();
+    assert_eq!(MAIN_RS, main_actual);
+
+    let patch = read_to_string(patch_path).unwrap();
+
+    assert!(patch.starts_with("--- a/src/main.rs\n+++ b/src/main.rs\n"));
+    assert!(patch.contains("\n-    let _ = std::path::Path::new(\"..\").join(\"target\");\n"));
+    assert!(patch.contains("\n+    let _ = std::path::PathBuf::from(\"../target\");\n"));
+}
+
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn fmt_after_fix() {
+    let tempdir = tempdir().unwrap();
+
+    init_fix_package(tempdir.path());
+
+    // `untouched.rs` is unformatted, but Dylint does not modify it, so it should not be formatted.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("src/main.rs"))
+        .unwrap();
+    writeln!(file, "\nmod untouched;").unwrap();
+    write(tempdir.path().join("src/untouched.rs"), UNTOUCHED_RS).unwrap();
 
     std::process::Command::cargo_bin("cargo-dylint")
         .unwrap()
         .current_dir(&tempdir)
-        .args(["dylint", "--lib", LIB_NAME, "--fix", "--", "--allow-dirty"])
+        .args([
+            "dylint",
+            "--lib",
+            LIB_NAME,
+            "--fix",
+            "--fix-noconflict",
+            "--fmt-after-fix",
+        ])
+        .assert()
+        .success();
+
+    let main_actual = read_to_string(tempdir.path().join("src/main.rs")).unwrap();
+
+    // `rustfmt` removes the leading blank line.
+    assert_eq!(
+        format!("{}\nmod untouched;\n", MAIN_FIXED.trim_start()),
+        main_actual
+    );
+
+    let untouched_actual = read_to_string(tempdir.path().join("src/untouched.rs")).unwrap();
+
+    assert_eq!(UNTOUCHED_RS, untouched_actual);
+}
+
+// The suggestions compile, so `--allow-downgrade` should leave them in place.
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn allow_downgrade() {
+    let tempdir = tempdir().unwrap();
+
+    init_fix_package(tempdir.path());
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args([
+            "dylint",
+            "--lib",
+            LIB_NAME,
+            "--fix",
+            "--fix-noconflict",
+            "--allow-downgrade",
+        ])
         .assert()
         .success();
 
@@ -72,6 +161,53 @@ fn fix() {
     assert_eq!(MAIN_FIXED, main_actual);
 }
 
+// The local `std` module makes the suggestion's `std::path::PathBuf` ambiguous, so the suggestion
+// does not compile. `--allow-downgrade` should report the lint and leave the file unchanged.
+#[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+#[test]
+fn allow_downgrade_culprit() {
+    const MAIN_RS: &str = r#"mod std {}
+
+fn main() {
+    let _ = ::std::path::Path::new("..").join("target");
+}
+"#;
+
+    let tempdir = tempdir().unwrap();
+
+    init_fix_package(tempdir.path());
+
+    write(tempdir.path().join("src/main.rs"), MAIN_RS).unwrap();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args([
+            "dylint",
+            "--lib",
+            LIB_NAME,
+            "--fix",
+            "--fix-noconflict",
+            "--allow-downgrade",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "The suggestions for the following lints cause `cargo check` to fail, so they were \
+             not applied: `const_path_join`",
+        ));
+
+    let main_actual = read_to_string(tempdir.path().join("src/main.rs")).unwrap();
+
+    assert_eq!(MAIN_RS, main_actual);
+}
+
+fn init_fix_package(path: &Path) {
+    init_package(path, &["--edition=2018"], MAIN_RS);
+
+    append_workspace_metadata(path).unwrap();
+}
+
 fn append_workspace_metadata(path: &Path) -> Result<()> {
     let manifest = path.join("Cargo.toml");
     let mut file = OpenOptions::new()
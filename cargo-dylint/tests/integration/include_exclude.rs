@@ -0,0 +1,22 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+// `not_included.rs` is matched by neither table, and `excluded.rs` is matched by both.
+#[test]
+fn include_exclude() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/include_exclude")
+        .args([
+            "dylint",
+            "--path",
+            "../../examples/restriction/question_mark_in_expression",
+        ])
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("src/included.rs")
+                .and(predicate::str::contains("src/not_included.rs").not())
+                .and(predicate::str::contains("src/excluded.rs").not()),
+        );
+}
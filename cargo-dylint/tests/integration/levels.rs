@@ -0,0 +1,72 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn levels() {
+    let args = [
+        "dylint",
+        "--path",
+        "../../examples/restriction/question_mark_in_expression",
+    ];
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/levels")
+        .args(args)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("error: using the `?` operator"));
+
+    // Flags in `DYLINT_RUSTFLAGS` take precedence over the `levels` table.
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/levels")
+        .env("DYLINT_RUSTFLAGS", "--warn question_mark_in_expression")
+        .args(args)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: using the `?` operator"));
+}
+
+#[test]
+fn exclude_lint() {
+    let args = [
+        "dylint",
+        "--path",
+        "../../examples/restriction/question_mark_in_expression",
+    ];
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/exclude_lint")
+        .args(args)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: using the `?` operator"));
+
+    // An excluded lint takes precedence over flags in `DYLINT_RUSTFLAGS`.
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/exclude_lint")
+        .env("DYLINT_RUSTFLAGS", "--deny question_mark_in_expression")
+        .args(args)
+        .args(["--exclude-lint", "question-mark-in-expression"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("using the `?` operator").not());
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/exclude_lint")
+        .args(args)
+        .args(["--exclude-lint", "nonexistent_lint"])
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains(
+                "Warning: Excluded lint `nonexistent_lint` is not registered by any selected \
+                 library",
+            )
+            .and(predicate::str::contains("warning: using the `?` operator")),
+        );
+}
@@ -1,8 +1,12 @@
 use assert_cmd::prelude::*;
-use dylint_internal::{env, packaging::isolate, CommandExt};
+use dylint_internal::{env, packaging::isolate, testing::new_template, CommandExt};
 use predicates::prelude::*;
-use std::{env::set_var, fs::OpenOptions, io::Write};
-use tempfile::tempdir;
+use std::{
+    env::set_var,
+    fs::{write, OpenOptions},
+    io::Write,
+};
+use tempfile::{tempdir, TempDir};
 
 // smoelius: "Separate lints into categories" commit
 const REV: &str = "402fc24351c60a3c474e786fd76aa66aa8638d55";
@@ -27,6 +31,76 @@ fn array_pattern() {
     assert!(lines[1].starts_with("question_mark_in_expression "));
 }
 
+#[test]
+fn dylint_lock_unpinned() {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `dylint_lock_unpinned_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "dylint_lock_unpinned_test"])
+        .success()
+        .unwrap();
+
+    write(
+        tempdir.path().join("dylint.lock"),
+        r#"
+[[libraries]]
+git = "https://github.com/trailofbits/dylint"
+branch = "master"
+pattern = "examples/general/crate_wide_allow"
+version = "0.1.0"
+"#,
+    )
+    .unwrap();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "must be pinned with `rev`, not `branch` or `tag`",
+        ));
+}
+
+#[test]
+fn dylint_lock_version_mismatch() {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `dylint_lock_version_mismatch_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "dylint_lock_version_mismatch_test"])
+        .success()
+        .unwrap();
+
+    write(
+        tempdir.path().join("dylint.lock"),
+        format!(
+            r#"
+[[libraries]]
+git = "https://github.com/trailofbits/dylint"
+rev = "{REV}"
+pattern = "examples/general/crate_wide_allow"
+version = "0.0.0"
+"#
+        ),
+    )
+    .unwrap();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "but `dylint.lock` pins version 0.0.0",
+        ));
+}
+
 #[test]
 fn invalid_pattern() {
     for pattern in ["/*", "../*"] {
@@ -187,6 +261,79 @@ fn metadata_change() {
         .stderr(predicate::str::contains("Checking metadata_change_test"));
 }
 
+/// Verify that `--skip-unbuildable` skips a library that fails to build and uses the others.
+#[test]
+fn skip_unbuildable() {
+    let (_broken_dir, tempdir) = package_with_broken_library("skip_unbuildable_test");
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--all"])
+        .assert()
+        .failure();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--all", "--skip-unbuildable"])
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("Warning: Could not build `fill_me_in`")
+                .and(predicate::str::contains("Checking skip_unbuildable_test")),
+        );
+}
+
+/// Verify that `--keep-going` uses the libraries that build, and then reports those that do not.
+/// Also verify that `--no-fail-fast` behaves the same, and that `--collect-only` reports the
+/// failures as a warning.
+#[test]
+fn keep_going_build_failure() {
+    let (_broken_dir, tempdir) = package_with_broken_library("keep_going_test");
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--all"])
+        .assert()
+        .failure();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--all", "--keep-going"])
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("Warning: Could not build `fill_me_in`")
+                .and(predicate::str::contains("Checking keep_going_test"))
+                .and(predicate::str::contains(
+                    "Could not build the following libraries: [\"fill_me_in@",
+                )),
+        );
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--all", "--no-fail-fast"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Could not build the following libraries: [\"fill_me_in@",
+        ));
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args(["dylint", "--all", "--collect-only"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: Could not build the following libraries: [\"fill_me_in@",
+        ));
+}
+
 #[test]
 fn nonexistent_git_library() {
     let tempdir = tempdir().unwrap();
@@ -340,6 +487,23 @@ path = "{}/../examples/general/crate_wide_allow"
         .stderr(predicate::str::contains("Compiling").not());
 }
 
+/// Verify that a library built with `--strip` can still be loaded.
+#[test]
+fn strip() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--strip",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("crate_wide_allow"));
+}
+
 #[test]
 fn unknown_keys() {
     let tempdir = tempdir().unwrap();
@@ -383,3 +547,48 @@ pattern = "examples/general/crate_wide_allow"
         .failure()
         .stderr(predicate::str::is_match(r"Unknown library keys:\r?\n\s*revision\r?\n").unwrap());
 }
+
+/// Creates a package named `name` whose workspace metadata lists `crate_wide_allow` and a library
+/// that fails to build. Returns the broken library's directory and the package's directory.
+fn package_with_broken_library(name: &str) -> (TempDir, TempDir) {
+    let broken_dir = tempdir().unwrap();
+    let tempdir = tempdir().unwrap();
+
+    new_template(broken_dir.path()).unwrap();
+
+    OpenOptions::new()
+        .append(true)
+        .open(broken_dir.path().join("src/lib.rs"))
+        .and_then(|mut file| writeln!(file, r#"compile_error!("broken");"#))
+        .unwrap();
+
+    dylint_internal::cargo::init(&format!("package `{name}`"))
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", name])
+        .success()
+        .unwrap();
+
+    isolate(tempdir.path()).unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+    [[workspace.metadata.dylint.libraries]]
+    path = "{}/../examples/general/crate_wide_allow"
+
+    [[workspace.metadata.dylint.libraries]]
+    path = "{}"
+    "#,
+        env!("CARGO_MANIFEST_DIR").replace('\\', "\\\\"),
+        broken_dir.path().to_string_lossy().replace('\\', "\\\\")
+    )
+    .unwrap();
+
+    (broken_dir, tempdir)
+}
@@ -0,0 +1,69 @@
+use assert_cmd::prelude::*;
+use dylint_internal::env;
+use std::fs::{read_to_string, write};
+use tempfile::{tempdir, TempDir};
+
+/// Verify that `DYLINT_LINT_COVERAGE` records the Dylint lints that fired, and only those.
+#[test]
+fn lint_coverage() {
+    let tempdir = package();
+    let coverage = tempdir.path().join("coverage.txt");
+
+    cargo_dylint(&tempdir)
+        .env(env::DYLINT_LINT_COVERAGE, &coverage)
+        .assert()
+        .success();
+
+    let lint_names = read_to_string(coverage).unwrap();
+    assert_eq!("crate_wide_allow\n", lint_names);
+}
+
+/// Verify that setting `DYLINT_LINT_COVERAGE` causes a package that was already checked to be
+/// rechecked, so that its lints are recorded.
+#[test]
+fn lint_coverage_after_check() {
+    let tempdir = package();
+    let coverage = tempdir.path().join("coverage.txt");
+
+    cargo_dylint(&tempdir).assert().success();
+
+    cargo_dylint(&tempdir)
+        .env(env::DYLINT_LINT_COVERAGE, &coverage)
+        .assert()
+        .success();
+
+    let lint_names = read_to_string(coverage).unwrap();
+    assert_eq!("crate_wide_allow\n", lint_names);
+}
+
+fn package() -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    std::process::Command::new("cargo")
+        .current_dir(&tempdir)
+        .args(["init", "--name", "lint_coverage_test"])
+        .assert()
+        .success();
+
+    write(
+        tempdir.path().join("src/main.rs"),
+        "#![allow(dead_code)]\n\nfn main() {\n    let x = 0;\n}\n",
+    )
+    .unwrap();
+
+    tempdir
+}
+
+fn cargo_dylint(tempdir: &TempDir) -> std::process::Command {
+    let mut command = std::process::Command::cargo_bin("cargo-dylint").unwrap();
+    command.args([
+        "dylint",
+        "--manifest-path",
+        &tempdir.path().join("Cargo.toml").to_string_lossy(),
+        "--path",
+        "../examples/general/crate_wide_allow",
+        "--path",
+        "../examples/restriction/question_mark_in_expression",
+    ]);
+    command
+}
@@ -15,6 +15,7 @@ use dylint_internal::{
 use glob::glob;
 use predicates::prelude::*;
 use std::{
+    collections::BTreeMap,
     env::join_paths,
     path::{Path, PathBuf},
 };
@@ -61,6 +62,39 @@ fn one_name_multiple_toolchains() {
             predicate::str::contains(format!("fill_me_in@{CHANNEL_A}"))
                 .and(predicate::str::contains(format!("fill_me_in@{CHANNEL_B}"))),
         );
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .envs([(
+            env::DYLINT_LIBRARY_PATH,
+            target_debug(tempdir.path()).unwrap(),
+        )])
+        .args(["dylint", "list", "--toolchains", "--no-metadata"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(format!("^{CHANNEL_A}[^\n]*\n{CHANNEL_B}[^\n]*\n$")).unwrap(),
+        );
+
+    let assert = std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .envs([(
+            env::DYLINT_LIBRARY_PATH,
+            target_debug(tempdir.path()).unwrap(),
+        )])
+        .args([
+            "dylint",
+            "list",
+            "--toolchains",
+            "--message-format=json",
+            "--no-metadata",
+        ])
+        .assert()
+        .success();
+    let toolchains = serde_json::from_slice::<Vec<String>>(&assert.get_output().stdout).unwrap();
+    assert_eq!(2, toolchains.len());
+    assert!(toolchains[0].starts_with(CHANNEL_A));
+    assert!(toolchains[1].starts_with(CHANNEL_B));
 }
 
 fn patch_dylint_template(path: &Path, channel: &str, clippy_utils_rev: &str) -> Result<()> {
@@ -233,6 +267,173 @@ fn list_by_path() {
         ));
 }
 
+#[test]
+fn lib_dir() {
+    let tempdir = tempdir().unwrap();
+
+    new_template(tempdir.path()).unwrap();
+
+    dylint_internal::cargo::build(&format!("dylint-template in {:?}", tempdir.path()))
+        .build()
+        .sanitize_environment()
+        .current_dir(&tempdir)
+        .success()
+        .unwrap();
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--lib-dir",
+            &tempdir.path().join("target").to_string_lossy(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("fill_me_in"))
+        .stderr(predicate::str::is_match(r"Skipped \d+ file\(s\) in `[^`]*`").unwrap());
+}
+
+#[test]
+fn print_libs_json() {
+    let tempdir = tempdir().unwrap();
+
+    new_template(tempdir.path()).unwrap();
+
+    dylint_internal::cargo::build(&format!("dylint-template in {:?}", tempdir.path()))
+        .build()
+        .sanitize_environment()
+        .current_dir(&tempdir)
+        .success()
+        .unwrap();
+
+    let path = glob(
+        &tempdir
+            .path()
+            .join("target/debug")
+            .join(library_filename("fill_me_in", "*"))
+            .to_string_lossy(),
+    )
+    .ok()
+    .as_mut()
+    .and_then(Iterator::next)
+    .unwrap()
+    .unwrap();
+
+    let assert = std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir(&tempdir)
+        .args([
+            "dylint",
+            "--lib-path",
+            &path.to_string_lossy(),
+            "--print-libs-json",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Checking").not());
+
+    let resolved =
+        serde_json::from_slice::<BTreeMap<String, Vec<PathBuf>>>(&assert.get_output().stdout)
+            .unwrap();
+    assert_eq!(1, resolved.len());
+    let (toolchain, paths) = resolved.into_iter().next().unwrap();
+    assert_eq!(1, paths.len());
+    assert_eq!(
+        library_filename("fill_me_in", &toolchain),
+        paths[0].file_name().unwrap().to_string_lossy()
+    );
+}
+
+#[test]
+fn assert_lint_count() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--assert-lint-count",
+            "1",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("crate_wide_allow"));
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--assert-lint-count",
+            "2",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`crate_wide_allow` registers 1 lint(s), but `--assert-lint-count 2` was used",
+        ));
+}
+
+#[test]
+fn with_docs() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--with-docs",
+            "--assert-lint-count",
+            "1",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\n        use of `#![allow(...)]` at the crate level\n",
+        ));
+}
+
+#[test]
+fn configs() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--configs",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "crate_wide_allow\n    no configuration\n",
+        ));
+}
+
+#[test]
+fn configs_keys() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/restriction/suboptimal_pattern",
+            "--configs",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "suboptimal_pattern\n    copy_only\n    explicit_deref_check\n",
+        ));
+}
+
 // smoelius: For the tests to pass on OSX, the paths have to be canonicalized, because `/var` is
 // symlinked to `/private/var`.
 fn target_debug(path: &Path) -> Result<PathBuf> {
@@ -1,9 +1,26 @@
+mod catch_panics;
+mod check_config;
+mod check_file;
 mod depinfo_dylint_libs;
+mod deps_first;
+mod diagnostic_handler;
+mod driver_timeout;
 mod dylint_driver_path;
+mod features;
 mod fix;
+mod include_exclude;
+mod levels;
 mod library_packages;
+mod lint_coverage;
 mod list;
+mod message_format;
 mod nightly_toolchain;
 mod no_deps;
 mod package_options;
+mod rustflags;
+mod skip_generated;
+mod stats;
+mod util;
+mod verbose;
 mod warn;
+mod workspace_dir;
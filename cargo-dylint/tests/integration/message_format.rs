@@ -0,0 +1,44 @@
+use crate::util::init_package;
+use assert_cmd::prelude::*;
+use tempfile::tempdir;
+
+// `unused_variables` is a `rustc` lint, so its diagnostic should be rendered to stderr but
+// should not appear in the JSON lines.
+#[test]
+fn json_lines() {
+    let tempdir = tempdir().unwrap();
+
+    init_package(
+        tempdir.path(),
+        &[],
+        "#![allow(dead_code)]\n\nfn main() {\n    let x = 0;\n}\n",
+    );
+
+    let assert = std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "--manifest-path",
+            &tempdir.path().join("Cargo.toml").to_string_lossy(),
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--message-format=json-lines",
+        ])
+        .assert()
+        .success();
+
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    let stderr = std::str::from_utf8(&assert.get_output().stderr).unwrap();
+
+    let messages = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(1, messages.len(), "{stdout}");
+    assert_eq!("compiler-message", messages[0]["reason"]);
+    assert_eq!("crate_wide_allow", messages[0]["message"]["code"]["code"]);
+    assert!(messages[0]["dylint_toolchain"].is_string());
+
+    assert!(stderr.contains("unused variable: `x`"), "{stderr}");
+}
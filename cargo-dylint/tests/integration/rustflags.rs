@@ -0,0 +1,42 @@
+use crate::util::init_package;
+use assert_cmd::prelude::*;
+use dylint_internal::env;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+/// Verify that a user-set `RUSTFLAGS` value reaches the checked crate alongside Dylint's settings.
+#[test]
+fn rustflags_are_preserved() {
+    let tempdir = tempdir().unwrap();
+
+    // If `--cfg=user_flag` does not reach the compiler, compilation fails. If Dylint's
+    // settings are clobbered, the `crate_wide_allow` warning is not emitted.
+    init_package(
+        tempdir.path(),
+        &[],
+        r#"#![allow(dead_code)]
+#![allow(unexpected_cfgs)]
+
+#[cfg(not(user_flag))]
+compile_error!("`user_flag` is not set");
+
+fn main() {}
+"#,
+    );
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .env(env::RUSTFLAGS, "--cfg=user_flag")
+        .args([
+            "dylint",
+            "--manifest-path",
+            &tempdir.path().join("Cargo.toml").to_string_lossy(),
+            "--path",
+            "../examples/general/crate_wide_allow",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "silently overrides `--warn dead-code` and `--deny dead-code`",
+        ));
+}
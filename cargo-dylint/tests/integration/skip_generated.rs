@@ -0,0 +1,80 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn generated_is_linted_by_default() {
+    cargo_dylint("skip_generated")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: using the `?` operator"));
+}
+
+// The lint is denied to verify that discarded diagnostics do not cause a failure.
+#[test]
+fn skip_generated_env() {
+    cargo_dylint("skip_generated")
+        .env("DYLINT_RUSTFLAGS", "--deny question_mark_in_expression")
+        .env("DYLINT_SKIP_GENERATED", "1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("using the `?` operator").not());
+}
+
+#[test]
+fn skip_generated_dylint_toml() {
+    cargo_dylint("skip_generated_dylint_toml")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("using the `?` operator").not());
+}
+
+// Verify that changing whether generated files are skipped causes the package to be rechecked,
+// rather than Cargo replaying the diagnostics of the previous check.
+#[test]
+fn skip_generated_toggle() {
+    cargo_dylint("skip_generated")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: using the `?` operator"));
+
+    cargo_dylint("skip_generated")
+        .env("DYLINT_SKIP_GENERATED", "1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("using the `?` operator").not());
+
+    cargo_dylint("skip_generated")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("warning: using the `?` operator"));
+}
+
+// `dependency`'s only error is in a generated file and is discarded. `dependent`, which depends on
+// `dependency`, can be checked only if `dependency`'s check still produces metadata.
+#[test]
+fn skip_generated_dependent() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/skip_generated_dependent")
+        .env("DYLINT_RUSTFLAGS", "--deny question_mark_in_expression")
+        .env("DYLINT_SKIP_GENERATED", "1")
+        .args([
+            "dylint",
+            "--path",
+            "../../examples/restriction/question_mark_in_expression",
+            "--workspace",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("using the `?` operator").not());
+}
+
+fn cargo_dylint(fixture: &str) -> std::process::Command {
+    let mut command = std::process::Command::cargo_bin("cargo-dylint").unwrap();
+    command.current_dir(format!("../fixtures/{fixture}")).args([
+        "dylint",
+        "--path",
+        "../../examples/restriction/question_mark_in_expression",
+    ]);
+    command
+}
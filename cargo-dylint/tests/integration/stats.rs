@@ -0,0 +1,69 @@
+use crate::util::init_package;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs::read_to_string;
+use tempfile::tempdir;
+
+#[test]
+fn stats() {
+    let tempdir = tempdir().unwrap();
+
+    init_package(
+        tempdir.path(),
+        &[],
+        "#![allow(dead_code)]\n\nfn main() {}\n",
+    );
+
+    let stats_path = tempdir.path().join("stats.json");
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "--manifest-path",
+            &tempdir.path().join("Cargo.toml").to_string_lossy(),
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--stats",
+            &stats_path.to_string_lossy(),
+        ])
+        .assert()
+        .success();
+
+    let stats: serde_json::Value =
+        serde_json::from_str(&read_to_string(stats_path).unwrap()).unwrap();
+
+    assert_eq!(1, stats["libraries"]);
+    assert_eq!(1, stats["toolchains"].as_array().unwrap().len());
+    assert_eq!(1, stats["diagnostics"]["warning"]);
+    assert_eq!(1, stats["lints"]["crate_wide_allow"]["warning"]);
+    assert!(stats["wall_time_secs"].is_f64());
+}
+
+#[test]
+fn summary() {
+    let tempdir = tempdir().unwrap();
+
+    // `unused_variables` is a rustc lint, so it should not be counted.
+    init_package(
+        tempdir.path(),
+        &[],
+        "#![allow(dead_code)]\n\nfn main() {\n    let x = 0;\n}\n",
+    );
+
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .args([
+            "dylint",
+            "--manifest-path",
+            &tempdir.path().join("Cargo.toml").to_string_lossy(),
+            "--path",
+            "../examples/general/crate_wide_allow",
+            "--summary",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Dylint: 1 warning across 1 library\n",
+        ));
+}
@@ -0,0 +1,22 @@
+use assert_cmd::prelude::*;
+use std::{fs::write, path::Path};
+
+/// Runs `cargo init` with `args` in `path`, and replaces the new package's `src/main.rs` with
+/// `main_rs`. The package is named after `path`'s last component.
+pub fn init_package(path: &Path, args: &[&str], main_rs: &str) {
+    std::process::Command::new("cargo")
+        .current_dir(path)
+        .arg("init")
+        .args(args)
+        .args([
+            "--name",
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .trim_start_matches('.'),
+        ])
+        .assert()
+        .success();
+
+    write(path.join("src/main.rs"), main_rs).unwrap();
+}
@@ -0,0 +1,22 @@
+use assert_cmd::prelude::*;
+use dylint_internal::env;
+use predicates::prelude::*;
+
+#[test]
+fn verbose() {
+    std::process::Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/verbose")
+        .env_remove(env::RUST_LOG)
+        .args([
+            "dylint",
+            "--path",
+            "../../examples/general/crate_wide_allow",
+            "--verbose",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("registering lints from `").and(
+            predicate::str::contains("`crate_wide_allow` registered 1 lint(s): crate_wide_allow"),
+        ));
+}
@@ -59,6 +59,15 @@ fn nothing_to_do() {
         ));
 }
 
+#[test]
+fn quiet_suppresses_warnings() {
+    cargo_dylint()
+        .args(["dylint", "--quiet"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning:").not());
+}
+
 /// `--all` should not be required when `--git` or `--path` is used on the command line.
 #[test]
 fn opts_library_package_no_warn() {
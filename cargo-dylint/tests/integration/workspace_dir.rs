@@ -0,0 +1,56 @@
+use assert_cmd::prelude::*;
+use dylint_internal::env;
+use predicates::prelude::*;
+use std::process::Command;
+
+// `question_mark_in_expression` fires in `b` but not in `a`. So with `--no-deps`, checking
+// `a` should succeed and checking `b` should fail.
+#[test]
+fn workspace_dir() {
+    Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/no_deps")
+        .env(env::RUSTFLAGS, "-D warnings")
+        .args([
+            "dylint",
+            "--lib",
+            "question_mark_in_expression",
+            "--no-deps",
+            "--workspace-dir",
+            "a",
+            "--workspace-dir",
+            "b",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("Dylint: workspace `a`: ok")
+                .and(predicate::str::contains("Dylint: workspace `b`: failed")),
+        );
+}
+
+// `a` and `b` are separate workspaces whose `dylint.toml` files set different levels for
+// `question_mark_in_expression`. Each workspace should be checked with its own levels.
+#[test]
+fn workspace_dir_levels() {
+    Command::cargo_bin("cargo-dylint")
+        .unwrap()
+        .current_dir("../fixtures/workspace_dir_levels")
+        .args([
+            "dylint",
+            "--path",
+            "../../examples/restriction/question_mark_in_expression",
+            "--workspace-dir",
+            "a",
+            "--workspace-dir",
+            "b",
+        ])
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("Dylint: workspace `a`: ok")
+                .and(predicate::str::contains("Dylint: workspace `b`: failed"))
+                .and(predicate::str::contains("warning: using the `?` operator").not())
+                .and(predicate::str::contains("error: using the `?` operator")),
+        );
+}
@@ -0,0 +1,154 @@
+//! Support for `DYLINT_CATCH_PANICS`.
+//!
+//! When `DYLINT_CATCH_PANICS` is enabled, each late lint pass registered by a library is wrapped in
+//! a [`CatchPanicsPass`]. The wrapper forwards every `LateLintPass` method to the wrapped pass
+//! inside [`std::panic::catch_unwind`]. If the wrapped pass panics, the panic is reported as an
+//! error, the wrapped pass is not called again, and the other libraries' passes continue to run.
+//!
+//! Catching panics this way is best effort, which is why it is opt-in:
+//!
+//! - The wrapped pass is called through [`AssertUnwindSafe`]. A pass that panics may have been
+//!   interrupted midway through updating its own state. That is why a pass is never called again
+//!   after it panics.
+//! - The compiler's state is not protected at all. A pass that panics while, e.g., a query is
+//!   executing, a lock is held, or a `RefCell` is borrowed can leave the compiler in a state that
+//!   causes later, unrelated errors or an internal compiler error. The original panic is still
+//!   reported first.
+//! - `rustc` itself uses unwinding to abort compilation after a fatal error. Such unwinds are not
+//!   panics in a lint pass and are resumed rather than caught.
+//! - The panic hook installed by `rustc` still runs when a pass panics. So the panic message and
+//!   the "compiler unexpectedly panicked" note are still printed, even though compilation
+//!   continues.
+//! - Early and pre-expansion passes are not wrapped, and a library built with `panic = "abort"`
+//!   cannot be caught.
+
+use anyhow::Result;
+
+#[rustversion::since(2024-10-22)]
+extern crate rustc_ast;
+#[rustversion::since(2024-10-22)]
+extern crate rustc_hir;
+
+#[rustversion::since(2024-10-22)]
+use rustc_lint::{LateLintPass, LintContext, LintPass};
+#[rustversion::since(2024-10-22)]
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+
+/// Wraps the late lint passes (module-level and crate-level) that were registered after
+/// `n_late_passes` and `n_late_module_passes` in [`CatchPanicsPass`]es.
+// The delegating methods rely on `rustc_lint::late_lint_methods!` naming its types with
+// `'tcx`, and on `LintPass` requiring `get_lints`. The latter is a relatively recent change, hence
+// the version check.
+#[allow(clippy::unnecessary_wraps)]
+#[rustversion::since(2024-10-22)]
+pub fn wrap_late_passes(
+    lint_store: &mut rustc_lint::LintStore,
+    n_late_passes: usize,
+    n_late_module_passes: usize,
+    library: &str,
+) -> Result<()> {
+    for factory in lint_store.late_passes.split_off(n_late_passes) {
+        let library = library.to_owned();
+        lint_store.register_late_pass(move |tcx| {
+            Box::new(CatchPanicsPass::new(library.clone(), factory(tcx)))
+        });
+    }
+    for factory in lint_store
+        .late_module_passes
+        .split_off(n_late_module_passes)
+    {
+        let library = library.to_owned();
+        lint_store.register_late_mod_pass(move |tcx| {
+            Box::new(CatchPanicsPass::new(library.clone(), factory(tcx)))
+        });
+    }
+    Ok(())
+}
+
+#[rustversion::before(2024-10-22)]
+pub fn wrap_late_passes(
+    _lint_store: &mut rustc_lint::LintStore,
+    _n_late_passes: usize,
+    _n_late_module_passes: usize,
+    _library: &str,
+) -> Result<()> {
+    anyhow::bail!(
+        "`{}` is not supported by this toolchain",
+        dylint_internal::env::DYLINT_CATCH_PANICS
+    )
+}
+
+#[rustversion::since(2024-10-22)]
+pub struct CatchPanicsPass<'tcx> {
+    library: String,
+    inner: rustc_lint::LateLintPassObject<'tcx>,
+    panicked: bool,
+}
+
+#[rustversion::since(2024-10-22)]
+impl<'tcx> CatchPanicsPass<'tcx> {
+    fn new(library: String, inner: rustc_lint::LateLintPassObject<'tcx>) -> Self {
+        Self {
+            library,
+            inner,
+            panicked: false,
+        }
+    }
+
+    fn catch_panics(
+        &mut self,
+        cx: &rustc_lint::LateContext<'tcx>,
+        f: impl FnOnce(&mut (dyn LateLintPass<'tcx> + 'tcx)),
+    ) {
+        if self.panicked {
+            return;
+        }
+        let inner = &mut *self.inner;
+        let Err(payload) = catch_unwind(AssertUnwindSafe(|| f(inner))) else {
+            return;
+        };
+        if payload.is::<rustc_span::fatal_error::FatalErrorMarker>() {
+            resume_unwind(payload);
+        }
+        self.panicked = true;
+        let msg = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        super::session_err(
+            cx.sess(),
+            &format!(
+                "lint pass `{}` from `{}` panicked: {}",
+                self.inner.name(),
+                self.library,
+                msg
+            ),
+        );
+    }
+}
+
+#[rustversion::since(2024-10-22)]
+impl LintPass for CatchPanicsPass<'_> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn get_lints(&self) -> Vec<&'static rustc_lint::Lint> {
+        self.inner.get_lints()
+    }
+}
+
+#[rustversion::since(2024-10-22)]
+macro_rules! catch_panics_methods {
+    ([], [$(fn $name:ident($($param:ident: $arg:ty),*);)*]) => {
+        $(fn $name(&mut self, cx: &rustc_lint::LateContext<'tcx>, $($param: $arg),*) {
+            self.catch_panics(cx, |inner| inner.$name(cx, $($param),*));
+        })*
+    };
+}
+
+#[rustversion::since(2024-10-22)]
+impl<'tcx> LateLintPass<'tcx> for CatchPanicsPass<'tcx> {
+    rustc_lint::late_lint_methods!(catch_panics_methods, []);
+}
@@ -10,18 +10,37 @@ extern crate rustc_lint;
 extern crate rustc_session;
 extern crate rustc_span;
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, Context, Result};
 use dylint_internal::{env, parse_path_filename, rustup::is_rustc};
+use regex::Regex;
 use std::{
-    collections::BTreeSet,
-    ffi::{CString, OsStr},
+    collections::{BTreeMap, BTreeSet},
+    ffi::{CStr, CString, OsStr},
+    fs::OpenOptions,
+    io::{Read, Seek, SeekFrom, Write},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+    thread::{sleep, spawn},
+    time::Duration,
 };
 
+mod catch_panics;
+
 pub const DYLINT_VERSION: &str = "0.1.0";
 
 type DylintVersionFunc = unsafe fn() -> *mut std::os::raw::c_char;
 
+type DylintConfigKeysFunc = unsafe extern "C" fn() -> *mut std::os::raw::c_char;
+
+type DylintValidateConfigFunc = unsafe extern "C" fn() -> *mut std::os::raw::c_char;
+
+type DiagnosticHandlerFunc =
+    unsafe extern "C" fn(diagnostic: *const std::os::raw::c_char) -> *mut std::os::raw::c_char;
+
+type FreeStringFunc = unsafe extern "C" fn(string: *mut std::os::raw::c_char);
+
 type RegisterLintsFunc =
     unsafe fn(sess: &rustc_session::Session, store: &mut rustc_lint::LintStore);
 
@@ -70,6 +89,21 @@ impl LoadedLibrary {
                     self.path.to_string_lossy()
                 );
             }
+            // A library built with a different toolchain than the driver may load
+            // successfully, but its compiler crates are not the driver's, so calling into it is
+            // undefined behavior. The library's toolchain is recovered from its filename. The
+            // driver's is the one it was built with: at runtime, `RUSTUP_TOOLCHAIN` is set to the
+            // library's toolchain.
+            if let Some((_, toolchain)) = parse_path_filename(&self.path) {
+                let driver_toolchain = env!("RUSTUP_TOOLCHAIN");
+                ensure!(
+                    toolchain == driver_toolchain,
+                    "`{}` was built with toolchain `{}`, but the driver uses toolchain `{}`",
+                    self.path.to_string_lossy(),
+                    toolchain,
+                    driver_toolchain
+                );
+            }
             if let Ok(func) = self.lib.get::<RegisterLintsFunc>(b"register_lints") {
                 func(sess, lint_store);
             } else {
@@ -84,6 +118,47 @@ impl LoadedLibrary {
             session_err(sess, &err);
         });
     }
+
+    /// Returns the library's name, or its path if its filename cannot be parsed.
+    fn name(&self) -> String {
+        parse_path_filename(&self.path)
+            .map_or_else(|| self.path.to_string_lossy().to_string(), |(name, _)| name)
+    }
+
+    /// Returns the configuration keys the library accepts, or `None` if the library does not
+    /// export a `dylint_config_keys` function.
+    fn config_keys(&self) -> Result<Option<Vec<String>>> {
+        unsafe {
+            let Ok(func) = self.lib.get::<DylintConfigKeysFunc>(b"dylint_config_keys") else {
+                return Ok(None);
+            };
+            let free = get_free_string_func(&self.lib, &self.path, "dylint_config_keys_free")?;
+            let keys = copy_and_free_string(func(), &free)?;
+            Ok(Some(
+                keys.lines()
+                    .filter(|key| !key.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect(),
+            ))
+        }
+    }
+
+    /// Returns the errors that resulted from deserializing the library's configuration (empty if
+    /// there were none), or `None` if the library does not export a `dylint_validate_config`
+    /// function.
+    fn config_errors(&self) -> Result<Option<String>> {
+        unsafe {
+            let Ok(func) = self
+                .lib
+                .get::<DylintValidateConfigFunc>(b"dylint_validate_config")
+            else {
+                return Ok(None);
+            };
+            let free = get_free_string_func(&self.lib, &self.path, "dylint_validate_config_free")?;
+            let errors = copy_and_free_string(func(), &free)?;
+            Ok(Some(errors))
+        }
+    }
 }
 
 #[rustversion::before(2023-12-18)]
@@ -98,6 +173,9 @@ fn session_err(sess: &rustc_session::Session, err: &impl ToString) -> rustc_span
 
 struct Callbacks {
     loaded_libs: Vec<LoadedLibrary>,
+    /// The names of the lints registered by the loaded libraries, filled in when the lints are
+    /// registered
+    dylint_lint_names: Arc<Mutex<BTreeSet<String>>>,
 }
 
 // smoelius: Use of thread local storage was added to Clippy by:
@@ -143,7 +221,10 @@ impl Callbacks {
                 loaded_libs.push(LoadedLibrary { path, lib });
             }
         }
-        Self { loaded_libs }
+        Self {
+            loaded_libs,
+            dylint_lint_names: Arc::default(),
+        }
     }
 }
 
@@ -237,21 +318,54 @@ impl rustc_driver::Callbacks for Callbacks {
     fn config(&mut self, config: &mut rustc_interface::Config) {
         let previous = config.register_lints.take();
         let loaded_libs = self.loaded_libs.split_off(0);
+        let dylint_lint_names = self.dylint_lint_names.clone();
         config.register_lints = Some(Box::new(move |sess, lint_store| {
             if let Some(previous) = &previous {
                 previous(sess, lint_store);
             }
 
+            let dylint_catch_panics = env::var(env::DYLINT_CATCH_PANICS).ok();
+            let dylint_cfgs = env::var(env::DYLINT_CFGS).ok();
+            let dylint_exclude_lints = env::var(env::DYLINT_EXCLUDE_LINTS).ok();
+            let dylint_levels = env::var(env::DYLINT_LEVELS).ok();
             let dylint_libs = env::var(env::DYLINT_LIBS).ok();
+            let dylint_lint_coverage = env::var(env::DYLINT_LINT_COVERAGE).ok();
             let dylint_metadata = env::var(env::DYLINT_METADATA).ok();
             let dylint_no_deps = env::var(env::DYLINT_NO_DEPS).ok();
             let dylint_no_deps_enabled = dylint_no_deps.as_ref().is_some_and(|value| value != "0");
+            let dylint_skip_generated = env::var(env::DYLINT_SKIP_GENERATED).ok();
             let cargo_primary_package_is_set = env::var(env::CARGO_PRIMARY_PACKAGE).is_ok();
 
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_CATCH_PANICS),
+                dylint_catch_panics
+                    .as_deref()
+                    .map(rustc_span::Symbol::intern),
+            ));
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_CFGS),
+                dylint_cfgs.as_deref().map(rustc_span::Symbol::intern),
+            ));
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_EXCLUDE_LINTS),
+                dylint_exclude_lints
+                    .as_deref()
+                    .map(rustc_span::Symbol::intern),
+            ));
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_LEVELS),
+                dylint_levels.as_deref().map(rustc_span::Symbol::intern),
+            ));
             sess.parse_sess().env_depinfo.lock().insert((
                 rustc_span::Symbol::intern(env::DYLINT_LIBS),
                 dylint_libs.as_deref().map(rustc_span::Symbol::intern),
             ));
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_LINT_COVERAGE),
+                dylint_lint_coverage
+                    .as_deref()
+                    .map(rustc_span::Symbol::intern),
+            ));
             sess.parse_sess().env_depinfo.lock().insert((
                 rustc_span::Symbol::intern(env::DYLINT_METADATA),
                 dylint_metadata.as_deref().map(rustc_span::Symbol::intern),
@@ -260,17 +374,29 @@ impl rustc_driver::Callbacks for Callbacks {
                 rustc_span::Symbol::intern(env::DYLINT_NO_DEPS),
                 dylint_no_deps.as_deref().map(rustc_span::Symbol::intern),
             ));
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_SKIP_GENERATED),
+                dylint_skip_generated
+                    .as_deref()
+                    .map(rustc_span::Symbol::intern),
+            ));
 
             if dylint_no_deps_enabled && !cargo_primary_package_is_set {
                 return;
             }
 
+            // With `DYLINT_VERBOSE`, each library's path and lints are logged as the
+            // library is registered, so that the order in which libraries register their lints is
+            // visible.
+            let verbose = env::enabled(env::DYLINT_VERBOSE);
+
             let mut before = BTreeSet::<Lint>::new();
             if list_enabled() {
                 lint_store.get_lints().iter().for_each(|&lint| {
                     before.insert(lint.into());
                 });
             }
+            let lint_names_before = lint_names(lint_store);
             for loaded_lib in &loaded_libs {
                 if let Some(path) = loaded_lib.path.to_str() {
                     sess.parse_sess()
@@ -278,18 +404,80 @@ impl rustc_driver::Callbacks for Callbacks {
                         .lock()
                         .insert(rustc_span::Symbol::intern(path));
                 }
+                let n_late_passes = lint_store.late_passes.len();
+                let n_late_module_passes = lint_store.late_module_passes.len();
+                let lints_before = verbose.then(|| lint_names(lint_store));
+                if verbose {
+                    log::info!(
+                        "registering lints from `{}`",
+                        loaded_lib.path.to_string_lossy()
+                    );
+                }
                 loaded_lib.register_lints(sess, lint_store);
+                if let Some(lints_before) = lints_before {
+                    let lints_after = lint_names(lint_store);
+                    let lints = lints_after
+                        .difference(&lints_before)
+                        .map(String::as_str)
+                        .collect::<Vec<_>>();
+                    log::info!(
+                        "`{}` registered {} lint(s): {}",
+                        loaded_lib.name(),
+                        lints.len(),
+                        lints.join(", ")
+                    );
+                }
+                if env::enabled(env::DYLINT_CATCH_PANICS) {
+                    catch_panics::wrap_late_passes(
+                        lint_store,
+                        n_late_passes,
+                        n_late_module_passes,
+                        &loaded_lib.name(),
+                    )
+                    .unwrap_or_else(|err| {
+                        session_err(sess, &err);
+                    });
+                }
+            }
+            *dylint_lint_names
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = lint_names(lint_store)
+                .difference(&lint_names_before)
+                .cloned()
+                .collect();
+            if env::enabled(env::DYLINT_VALIDATE_CONFIG) {
+                let valid = validate_config(sess, &loaded_libs)
+                    .unwrap_or_else(|err| early_error(err.to_string()));
+                std::process::exit(if valid { 0 } else { 1 });
+            }
+            if list_enabled() && env::enabled(env::DYLINT_LIST_CONFIGS) {
+                list_configs(&loaded_libs).unwrap_or_else(|err| early_error(err.to_string()));
+                std::process::exit(0);
             }
             if list_enabled() {
                 let mut after = BTreeSet::<Lint>::new();
                 lint_store.get_lints().iter().for_each(|&lint| {
                     after.insert(lint.into());
                 });
-                list_lints(&before, &after);
+                list_lints(
+                    &before,
+                    &after,
+                    env::enabled(env::DYLINT_LIST_WITH_DOCS),
+                    env::enabled(env::DYLINT_LIST_JSON),
+                );
                 std::process::exit(0);
             }
         }));
 
+        // The excluded lints are added after the lint options from the command line so that
+        // they take precedence.
+        let exclude_lints = exclude_lints().unwrap_or_else(|err| early_error(err.to_string()));
+        config.opts.lint_opts.extend(
+            exclude_lints
+                .into_iter()
+                .map(|name| (name, rustc_lint::Level::Allow)),
+        );
+
         // smoelius: Choose to be compatible with Clippy:
         // https://github.com/rust-lang/rust-clippy/commit/7bae5bd828e98af9d245b77118c075a7f1a036b9
         zero_mir_opt_level(config);
@@ -301,9 +489,63 @@ fn list_enabled() -> bool {
     env::var(env::DYLINT_LIST).is_ok_and(|value| value != "0")
 }
 
-fn list_lints(before: &BTreeSet<Lint>, after: &BTreeSet<Lint>) {
+fn list_configs(loaded_libs: &[LoadedLibrary]) -> Result<()> {
+    for loaded_lib in loaded_libs {
+        match loaded_lib.config_keys()? {
+            Some(keys) if !keys.is_empty() => {
+                for key in keys {
+                    println!("    {key}");
+                }
+            }
+            _ => println!("    no configuration"),
+        }
+    }
+    Ok(())
+}
+
+fn lint_names(lint_store: &rustc_lint::LintStore) -> BTreeSet<String> {
+    lint_store
+        .get_lints()
+        .iter()
+        .map(|lint| lint.name_lower())
+        .collect()
+}
+
+fn validate_config(sess: &rustc_session::Session, loaded_libs: &[LoadedLibrary]) -> Result<bool> {
+    let mut valid = true;
+    for loaded_lib in loaded_libs {
+        match loaded_lib.config_errors()? {
+            Some(errors) if errors.is_empty() => println!("    ok"),
+            Some(errors) => {
+                session_err(sess, &errors);
+                valid = false;
+            }
+            None => println!("    not checked (library does not export `dylint_validate_config`)"),
+        }
+    }
+    Ok(valid)
+}
+
+fn list_lints(before: &BTreeSet<Lint>, after: &BTreeSet<Lint>, with_docs: bool, json: bool) {
     let difference: Vec<Lint> = after.difference(before).cloned().collect();
 
+    // With `DYLINT_LIST_JSON`, the lints are printed as one JSON array, for consumption
+    // by `dylint::library_lints`.
+    if json {
+        let lints = difference
+            .iter()
+            .map(|lint| {
+                serde_json::json!({
+                    "name": lint.name.to_lowercase(),
+                    "level": lint.level.as_str(),
+                    "desc": lint.desc,
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::Value::from(lints));
+        return;
+    }
+
     let name_width = difference
         .iter()
         .map(|lint| lint.name.len())
@@ -317,6 +559,20 @@ fn list_lints(before: &BTreeSet<Lint>, after: &BTreeSet<Lint>) {
         .unwrap_or_default();
 
     for Lint { name, level, desc } in difference {
+        // With docs, the description goes on the lines following the name and level, one
+        // line per line of the description, so that line breaks are preserved.
+        if with_docs {
+            println!(
+                "    {:<name_width$}    {}",
+                name.to_lowercase(),
+                level.as_str(),
+                name_width = name_width,
+            );
+            for line in desc.lines() {
+                println!("        {line}");
+            }
+            continue;
+        }
         println!(
             "    {:<name_width$}    {:<level_width$}    {}",
             name.to_lowercase(),
@@ -334,23 +590,401 @@ pub fn dylint_driver<T: AsRef<OsStr>>(args: &[T]) -> Result<()> {
         return Ok(());
     }
 
+    // `dylint_testing` sets `DYLINT_DRIVER_TIMEOUT` (in milliseconds) so that a hanging
+    // lint cannot keep the driver running after the test that spawned it has failed.
+    if let Ok(timeout) = env::var(env::DYLINT_DRIVER_TIMEOUT) {
+        let timeout = Duration::from_millis(timeout.parse()?);
+        spawn(move || {
+            sleep(timeout);
+            eprintln!("dylint-driver timed out after {timeout:?}");
+            std::process::exit(1);
+        });
+    }
+
+    // `dylint_testing` sets `DYLINT_NORMALIZE_STDERR` because `compiletest` provides no
+    // way to normalize a test's standard error other than per-file headers.
+    if let Ok(normalizations) = env::var(env::DYLINT_NORMALIZE_STDERR) {
+        return run_and_normalize_stderr(args, &normalizations);
+    }
+
+    if let Ok(path) = env::var(env::DYLINT_DIAGNOSTIC_HANDLER) {
+        return run_with_diagnostic_handler(args, Path::new(&path));
+    }
+
     run(&args[1..])
 }
 
+/// Re-runs the driver with `args` and with `key` removed from the environment, and returns the
+/// driver's output. The driver's standard output is inherited.
+fn rerun<T: AsRef<OsStr>>(args: &[T], key: &str) -> Result<Output> {
+    Command::new(std::env::current_exe()?)
+        .args(args.iter().skip(1))
+        .env_remove(key)
+        .stdout(Stdio::inherit())
+        .output()
+        .map_err(Into::into)
+}
+
+/// Re-runs the driver with `args`, rewrites its standard error according to `normalizations`, and
+/// exits with the driver's exit code.
+///
+/// `normalizations` is a JSON array of `[regex, replacement]` pairs. On Windows, backslashes in the
+/// paths of spans are rewritten to forward slashes before the pairs are applied.
+fn run_and_normalize_stderr<T: AsRef<OsStr>>(args: &[T], normalizations: &str) -> Result<()> {
+    let normalizations = serde_json::from_str::<Vec<(String, String)>>(normalizations)?
+        .into_iter()
+        .map(|(regex, replacement)| Ok((Regex::new(&regex)?, replacement)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = rerun(args, env::DYLINT_NORMALIZE_STDERR)?;
+
+    let mut stderr = String::from_utf8(output.stderr)?;
+    if cfg!(windows) {
+        stderr = normalize_span_paths(&stderr);
+    }
+    for (regex, replacement) in &normalizations {
+        stderr = regex
+            .replace_all(&stderr, replacement.as_str())
+            .into_owned();
+    }
+    eprint!("{stderr}");
+
+    std::process::exit(output.status.code().unwrap_or(1));
+}
+
+/// Rewrites backslashes to forward slashes in the lines of `stderr` that give the paths of spans,
+/// i.e., those beginning with `-->` or `:::`. Other backslashes, e.g., those in string literals,
+/// are left alone.
+fn normalize_span_paths(stderr: &str) -> String {
+    stderr
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("--> ") || trimmed.starts_with("::: ") {
+                line.replace('\\', "/")
+            } else {
+                line.to_owned()
+            }
+        })
+        .collect()
+}
+
+/// Returns the function named `name` that frees the strings returned by another of `lib`'s
+/// functions.
+fn get_free_string_func<'lib>(
+    lib: &'lib libloading::Library,
+    path: &Path,
+    name: &str,
+) -> Result<libloading::Symbol<'lib, FreeStringFunc>> {
+    unsafe { lib.get::<FreeStringFunc>(name.as_bytes()) }
+        .with_context(|| format!("could not find `{name}` in `{}`", path.to_string_lossy()))
+}
+
+/// Copies the string that `string` points to, and then frees it with `free`.
+///
+/// A string returned by a library was allocated by the library's allocator, which need not be the
+/// driver's. So the string must be freed by the library.
+unsafe fn copy_and_free_string(
+    string: *mut std::os::raw::c_char,
+    free: &FreeStringFunc,
+) -> Result<String> {
+    let copy = unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .map(ToOwned::to_owned);
+    unsafe { free(string) };
+    copy.map_err(Into::into)
+}
+
+/// Re-runs the driver with `args`, passes each line of its standard error through the
+/// `dylint_diagnostic_handler` function of the library at `path`, and exits with the driver's exit
+/// code.
+///
+/// The function receives a line and returns the line to write in its place, or null to write
+/// nothing. The returned line is freed with the library's `dylint_diagnostic_handler_free`
+/// function. When the driver is run by Cargo, each line is a JSON-encoded diagnostic.
+fn run_with_diagnostic_handler<T: AsRef<OsStr>>(args: &[T], path: &Path) -> Result<()> {
+    let lib = unsafe { libloading::Library::new(path) }
+        .with_context(|| format!("could not load library `{}`", path.to_string_lossy()))?;
+    let func = unsafe { lib.get::<DiagnosticHandlerFunc>(b"dylint_diagnostic_handler") }
+        .with_context(|| {
+            format!(
+                "could not find `dylint_diagnostic_handler` in `{}`",
+                path.to_string_lossy()
+            )
+        })?;
+    let free = get_free_string_func(&lib, path, "dylint_diagnostic_handler_free")?;
+
+    let output = rerun(args, env::DYLINT_DIAGNOSTIC_HANDLER)?;
+
+    for line in String::from_utf8(output.stderr)?.lines() {
+        let line = CString::new(line)?;
+        let handled = unsafe { func(line.as_ptr()) };
+        if handled.is_null() {
+            continue;
+        }
+        let handled = unsafe { copy_and_free_string(handled, &free) }?;
+        eprintln!("{handled}");
+    }
+
+    std::process::exit(output.status.code().unwrap_or(1));
+}
+
+/// Appends to the file at `path` the name of each lint in `lint_names` that emitted at least one of
+/// the diagnostics in `stderr`.
+///
+/// The diagnostics are recognized only if they are JSON-encoded, as they are when the driver is run
+/// by Cargo.
+fn record_lint_coverage(stderr: &str, lint_names: &BTreeSet<String>, path: &Path) -> Result<()> {
+    let fired = stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|diagnostic| {
+            diagnostic
+                .pointer("/code/code")
+                .and_then(serde_json::Value::as_str)
+                .map(ToOwned::to_owned)
+        })
+        .filter(|name| lint_names.contains(name))
+        .collect::<BTreeSet<_>>();
+
+    // Cargo may run several instances of the driver at once, one per crate. Each appends
+    // its lint names in a single write, so the file may contain duplicates but not interleavings.
+    let contents = fired
+        .iter()
+        .map(|name| format!("{name}\n"))
+        .collect::<String>();
+    OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .with_context(|| format!("could not write to `{}`", path.to_string_lossy()))
+}
+
+/// Discards from `stderr` each diagnostic from a lint in `lint_names` whose primary span is in a
+/// generated file. A file is considered generated if it is in `OUT_DIR` or in `DYLINT_TARGET_DIR`.
+///
+/// Returns the remaining standard error, and whether the compilation failed only because of
+/// discarded diagnostics. In the latter case, the "aborting due to" message is discarded as well.
+///
+/// Like with `DYLINT_LINT_COVERAGE`, the diagnostics are recognized only if they are JSON-encoded.
+fn skip_generated(stderr: &str, lint_names: &BTreeSet<String>) -> (String, bool) {
+    let generated_dirs = [env::OUT_DIR, env::DYLINT_TARGET_DIR]
+        .into_iter()
+        .filter_map(|key| env::var(key).ok())
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    let mut kept = Vec::new();
+    let mut skipped_error = false;
+    for line in stderr.lines() {
+        let diagnostic = serde_json::from_str::<serde_json::Value>(line).ok();
+        if let Some(diagnostic) = diagnostic
+            .as_ref()
+            .filter(|diagnostic| is_generated(diagnostic, lint_names, &generated_dirs))
+        {
+            skipped_error |= is_error(diagnostic);
+            continue;
+        }
+        kept.push((line, diagnostic));
+    }
+
+    // `aborting_only` means that every error that was kept is an "aborting due to"
+    // message, i.e., that the compilation failed only because of errors that were discarded.
+    let aborting_only = skipped_error
+        && kept
+            .iter()
+            .filter_map(|(_, diagnostic)| diagnostic.as_ref())
+            .all(|diagnostic| !is_error(diagnostic) || is_aborting(diagnostic));
+
+    let stderr = kept
+        .into_iter()
+        .filter(|(_, diagnostic)| !(aborting_only && diagnostic.as_ref().is_some_and(is_aborting)))
+        .map(|(line, _)| format!("{line}\n"))
+        .collect();
+
+    (stderr, aborting_only)
+}
+
+fn is_generated(
+    diagnostic: &serde_json::Value,
+    lint_names: &BTreeSet<String>,
+    generated_dirs: &[PathBuf],
+) -> bool {
+    let Some(code) = diagnostic
+        .pointer("/code/code")
+        .and_then(serde_json::Value::as_str)
+    else {
+        return false;
+    };
+    if !lint_names.contains(code) {
+        return false;
+    }
+    let Some(file_name) = diagnostic
+        .get("spans")
+        .and_then(serde_json::Value::as_array)
+        .and_then(|spans| {
+            spans.iter().find(|span| {
+                span.get("is_primary")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or_default()
+            })
+        })
+        .and_then(|span| span.get("file_name"))
+        .and_then(serde_json::Value::as_str)
+    else {
+        return false;
+    };
+    // The paths of files in the workspace are relative to the current directory, while
+    // those of generated files are typically absolute.
+    let path = std::env::current_dir()
+        .map(|current_dir| current_dir.join(file_name))
+        .unwrap_or_else(|_| PathBuf::from(file_name));
+    generated_dirs.iter().any(|dir| path.starts_with(dir))
+}
+
+fn is_error(diagnostic: &serde_json::Value) -> bool {
+    diagnostic.get("level").and_then(serde_json::Value::as_str) == Some("error")
+}
+
+fn is_aborting(diagnostic: &serde_json::Value) -> bool {
+    diagnostic
+        .get("message")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|message| message.starts_with("aborting due to"))
+}
+
 pub fn run<T: AsRef<OsStr>>(args: &[T]) -> Result<()> {
     let sysroot = sysroot().ok();
+    let cfgs = cfgs()?;
+    let levels = levels()?;
     let rustflags = rustflags();
     let paths = paths();
 
-    let rustc_args = rustc_args(args, sysroot.as_deref(), &rustflags, &paths)?;
+    let rustc_args = rustc_args(args, sysroot.as_deref(), &cfgs, &levels, &rustflags, &paths)?;
 
-    let mut callbacks = Callbacks::new(paths);
+    let mut callbacks = Callbacks::new(paths.clone());
 
     // smoelius: I am not sure that this should be here. `RUST_LOG=debug cargo test` fails because
     // of the log messages.
     log::debug!("{:?}", rustc_args);
 
-    map_run_compiler_err(rustc_driver::RunCompiler::new(&rustc_args, &mut callbacks).run())
+    let lint_coverage = env::var(env::DYLINT_LINT_COVERAGE).ok();
+    let skip = env::enabled(env::DYLINT_SKIP_GENERATED);
+
+    if lint_coverage.is_none() && !skip {
+        return map_run_compiler_err(
+            rustc_driver::RunCompiler::new(&rustc_args, &mut callbacks).run(),
+        );
+    }
+
+    // With `DYLINT_LINT_COVERAGE` or `DYLINT_SKIP_GENERATED`, the compiler's diagnostics are
+    // captured as they are emitted, and examined once the compiler has finished.
+    let dylint_lint_names = callbacks.dylint_lint_names.clone();
+    let (result, stderr) =
+        capture_stderr(|| rustc_driver::RunCompiler::new(&rustc_args, &mut callbacks).run())?;
+    let lint_names = std::mem::take(
+        &mut *dylint_lint_names
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner),
+    );
+
+    if let Some(path) = lint_coverage {
+        record_lint_coverage(&stderr, &lint_names, Path::new(&path))?;
+    }
+
+    let (stderr, aborting_only) = if skip {
+        skip_generated(&stderr, &lint_names)
+    } else {
+        (stderr, false)
+    };
+
+    eprint!("{stderr}");
+
+    match result {
+        // A compilation error may be reported by unwinding with a `FatalErrorMarker`. Any other
+        // panic, e.g., an ICE, is propagated, even if every error was discarded.
+        Err(payload) if !payload.is::<rustc_span::fatal_error::FatalErrorMarker>() => {
+            resume_unwind(payload)
+        }
+        _ if aborting_only => rerun_with_capped_lints(&rustc_args, paths),
+        Ok(result) => map_run_compiler_err(result),
+        Err(payload) => resume_unwind(payload),
+    }
+}
+
+/// Reruns the compiler with every lint capped at `warn`, after a compilation failed only because
+/// of errors that `skip_generated` discarded. `rustc` writes no `.rmeta` file for a failed
+/// compilation, so without the rerun, Cargo would fail to check the crates that depend on this one.
+///
+/// The rerun's diagnostics repeat those of the first run, so they are shown only if the rerun
+/// fails.
+fn rerun_with_capped_lints(rustc_args: &[String], paths: Vec<PathBuf>) -> Result<()> {
+    // The first run produced lint errors, so `rustc_args` cannot already contain a `--cap-lints`
+    // flag that would conflict with this one.
+    let mut rustc_args = rustc_args.to_vec();
+    rustc_args.push("--cap-lints=warn".to_owned());
+
+    let mut callbacks = Callbacks::new(paths);
+    let (result, stderr) =
+        capture_stderr(|| rustc_driver::RunCompiler::new(&rustc_args, &mut callbacks).run())?;
+
+    let failed = result.is_err()
+        || stderr
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .any(|diagnostic| is_error(&diagnostic));
+    if failed {
+        eprint!("{stderr}");
+    }
+
+    match result {
+        Ok(result) => map_run_compiler_err(result),
+        Err(payload) => resume_unwind(payload),
+    }
+}
+
+/// Calls `f` with the process's standard error redirected to a temporary file, and returns `f`'s
+/// result (or the payload of its panic) along with what was written to the file.
+fn capture_stderr<R>(f: impl FnOnce() -> R) -> Result<(std::thread::Result<R>, String)> {
+    let mut file = tempfile::tempfile()?;
+
+    std::io::stderr().flush()?;
+    let saved = unsafe { libc::dup(STDERR_FILENO) };
+    ensure!(saved >= 0, "could not duplicate stderr");
+    ensure!(
+        unsafe { libc::dup2(raw_fd(&file)?, STDERR_FILENO) } >= 0,
+        "could not redirect stderr"
+    );
+
+    let result = catch_unwind(AssertUnwindSafe(f));
+
+    let _ = std::io::stderr().flush();
+    let restored = unsafe { libc::dup2(saved, STDERR_FILENO) } >= 0;
+    unsafe { libc::close(saved) };
+    ensure!(restored, "could not restore stderr");
+
+    let mut stderr = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut stderr)?;
+
+    Ok((result, stderr))
+}
+
+const STDERR_FILENO: std::os::raw::c_int = 2;
+
+#[cfg(unix)]
+fn raw_fd(file: &std::fs::File) -> Result<std::os::raw::c_int> {
+    use std::os::unix::io::AsRawFd;
+    Ok(file.as_raw_fd())
+}
+
+#[cfg(windows)]
+fn raw_fd(file: &std::fs::File) -> Result<std::os::raw::c_int> {
+    use std::os::windows::io::AsRawHandle;
+    let fd = unsafe { libc::open_osfhandle(file.as_raw_handle() as libc::intptr_t, 0) };
+    ensure!(fd >= 0, "could not get file descriptor");
+    Ok(fd)
 }
 
 fn sysroot() -> Result<PathBuf> {
@@ -361,6 +995,47 @@ fn sysroot() -> Result<PathBuf> {
         .join(rustup_toolchain))
 }
 
+// `DYLINT_CFGS` holds a JSON-encoded array of strings, each of which is passed to `rustc`
+// as a `--cfg` flag, e.g., `["my_lint_strict"]` becomes `--cfg=my_lint_strict`.
+fn cfgs() -> Result<Vec<String>> {
+    let Ok(dylint_cfgs) = env::var(env::DYLINT_CFGS) else {
+        return Ok(Vec::new());
+    };
+    let cfgs = serde_json::from_str::<Vec<String>>(&dylint_cfgs)
+        .with_context(|| format!("could not parse `{}`", env::DYLINT_CFGS))?;
+    Ok(cfgs.into_iter().map(|cfg| format!("--cfg={cfg}")).collect())
+}
+
+// `DYLINT_EXCLUDE_LINTS` holds a JSON-encoded array of the names of lints to allow. `cargo
+// dylint` includes only lints that some library registers.
+fn exclude_lints() -> Result<Vec<String>> {
+    let Ok(dylint_exclude_lints) = env::var(env::DYLINT_EXCLUDE_LINTS) else {
+        return Ok(Vec::new());
+    };
+    serde_json::from_str(&dylint_exclude_lints)
+        .with_context(|| format!("could not parse `{}`", env::DYLINT_EXCLUDE_LINTS))
+}
+
+// `DYLINT_LEVELS` holds the `[levels]` table from the workspace's `dylint.toml` file,
+// JSON-encoded. The levels are passed to `rustc` before any other arguments so that lint flags on
+// the command line (e.g., in `RUSTFLAGS` or `DYLINT_RUSTFLAGS`) take precedence.
+fn levels() -> Result<Vec<String>> {
+    let Ok(dylint_levels) = env::var(env::DYLINT_LEVELS) else {
+        return Ok(Vec::new());
+    };
+    let levels = serde_json::from_str::<BTreeMap<String, String>>(&dylint_levels)?;
+    levels
+        .into_iter()
+        .map(|(name, level)| {
+            ensure!(
+                ["allow", "warn", "force-warn", "deny", "forbid"].contains(&level.as_str()),
+                "unknown level `{level}` for lint `{name}`"
+            );
+            Ok(format!("--{level}={name}"))
+        })
+        .collect()
+}
+
 fn rustflags() -> Vec<String> {
     env::var(env::DYLINT_RUSTFLAGS).map_or_else(
         |_| Vec::new(),
@@ -369,16 +1044,39 @@ fn rustflags() -> Vec<String> {
 }
 
 fn paths() -> Vec<PathBuf> {
-    (|| -> Result<_> {
+    let paths = (|| -> Result<_> {
         let dylint_libs = env::var(env::DYLINT_LIBS)?;
         serde_json::from_str(&dylint_libs).map_err(Into::into)
     })()
-    .unwrap_or_default()
+    .unwrap_or_default();
+    let manifest_dir = env::var(env::CARGO_MANIFEST_DIR).ok();
+    resolve_paths(paths, manifest_dir.as_deref().map(Path::new))
+}
+
+// During recursive dependency builds, the driver's current directory need not be the
+// directory in which the libraries were named. So resolve relative paths against the manifest
+// directory, which is that of the crate being compiled.
+fn resolve_paths(paths: Vec<PathBuf>, manifest_dir: Option<&Path>) -> Vec<PathBuf> {
+    let Some(manifest_dir) = manifest_dir else {
+        return paths;
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            if path.is_relative() {
+                manifest_dir.join(path)
+            } else {
+                path
+            }
+        })
+        .collect()
 }
 
 fn rustc_args<T: AsRef<OsStr>, U: AsRef<str>, V: AsRef<Path>>(
     args: &[T],
     sysroot: Option<&Path>,
+    cfgs: &[U],
+    levels: &[U],
     rustflags: &[U],
     paths: &[V],
 ) -> Result<Vec<String>> {
@@ -410,6 +1108,8 @@ fn rustc_args<T: AsRef<OsStr>, U: AsRef<str>, V: AsRef<Path>>(
             bail!("could not parse `{}`", path.as_ref().to_string_lossy());
         }
     }
+    rustc_args.extend(cfgs.iter().map(|cfg| cfg.as_ref().to_owned()));
+    rustc_args.extend(levels.iter().map(|level| level.as_ref().to_owned()));
     rustc_args.extend(args.map(|s| s.as_ref().to_string_lossy().to_string()));
     rustc_args.extend(
         rustflags
@@ -448,6 +1148,8 @@ mod test {
                 &["--crate-name", "name"],
                 None,
                 &[] as &[&str],
+                &[] as &[&str],
+                &[] as &[&str],
                 &[] as &[&Path]
             )
             .unwrap(),
@@ -462,6 +1164,8 @@ mod test {
                 &["rustc", "--crate-name", "name"],
                 None,
                 &[] as &[&str],
+                &[] as &[&str],
+                &[] as &[&str],
                 &[] as &[&Path]
             )
             .unwrap(),
@@ -476,10 +1180,93 @@ mod test {
                 &["/bin/rustc", "--crate-name", "name"],
                 None,
                 &[] as &[&str],
+                &[] as &[&str],
+                &[] as &[&str],
                 &[] as &[&Path]
             )
             .unwrap(),
             vec!["/bin/rustc", "--crate-name", "name"]
         );
     }
+
+    #[test]
+    fn levels_precede_args() {
+        assert_eq!(
+            rustc_args(
+                &["--crate-name", "name"],
+                None,
+                &[] as &[&str],
+                &["--deny=lint"],
+                &["--warn=lint"],
+                &[] as &[&Path]
+            )
+            .unwrap(),
+            vec![
+                "rustc",
+                "--deny=lint",
+                "--crate-name",
+                "name",
+                "--warn=lint"
+            ]
+        );
+    }
+
+    #[test]
+    fn cfgs_precede_args() {
+        assert_eq!(
+            rustc_args(
+                &["--crate-name", "name"],
+                None,
+                &["--cfg=my_lint_strict"],
+                &[] as &[&str],
+                &[] as &[&str],
+                &[] as &[&Path]
+            )
+            .unwrap(),
+            vec!["rustc", "--cfg=my_lint_strict", "--crate-name", "name"]
+        );
+    }
+
+    #[test]
+    fn relative_path() {
+        let manifest_dir =
+            std::env::temp_dir().join(format!("dylint_driver_{}", std::process::id()));
+        let lib_dir = manifest_dir.join("target/release");
+        std::fs::create_dir_all(&lib_dir).unwrap();
+        let file_name = format!(
+            "{}library@toolchain{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        );
+        std::fs::write(lib_dir.join(&file_name), []).unwrap();
+
+        // The current directory differs from `manifest_dir`, so the relative path would
+        // not exist if it were not resolved against `manifest_dir`.
+        let relative_path = Path::new("target/release").join(&file_name);
+        assert!(!relative_path.exists());
+
+        let paths = resolve_paths(vec![relative_path], Some(&manifest_dir));
+        assert_eq!(paths, vec![lib_dir.join(&file_name)]);
+        assert!(paths[0].exists());
+
+        std::fs::remove_dir_all(manifest_dir).unwrap();
+    }
+
+    #[test]
+    fn absolute_path() {
+        let path = std::env::temp_dir().join("library@toolchain.so");
+        assert_eq!(
+            resolve_paths(vec![path.clone()], Some(Path::new("manifest_dir"))),
+            vec![path]
+        );
+    }
+
+    #[test]
+    fn normalize_span_paths_only() {
+        let stderr = "warning: `\\n`\n  --> src\\main.rs:1:1\n   ::: src\\lib.rs:2:2\n";
+        assert_eq!(
+            normalize_span_paths(stderr),
+            "warning: `\\n`\n  --> src/main.rs:1:1\n   ::: src/lib.rs:2:2\n"
+        );
+    }
 }
@@ -87,6 +87,17 @@ pub fn get(opts: &opts::Dylint, toolchain: &str) -> Result<PathBuf> {
 
     let driver = driver_dir.join("dylint-driver");
     if !driver.exists() || is_outdated(opts, toolchain, &driver)? {
+        ensure!(
+            !opts.library_selection().frozen_driver,
+            "`--frozen-driver` was used, but the driver at `{}` for toolchain `{}` is {}",
+            driver.to_string_lossy(),
+            toolchain,
+            if driver.exists() {
+                "outdated"
+            } else {
+                "missing"
+            }
+        );
         build(opts, toolchain, &driver_dir)?;
     }
 
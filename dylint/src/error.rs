@@ -45,7 +45,129 @@ where
     }
 }
 
-pub type ColorizedResult<T> = Result<T, ColorizedError<anyhow::Error>>;
+pub type ColorizedResult<T> = Result<T, ColorizedError<DylintError>>;
+
+/// The error type returned by [`crate::run`]
+///
+/// Each variant wraps the underlying error, whose `Display` and `Debug` implementations are used
+/// for the variant's own. So the variant can be matched on, while the error is still printed as
+/// it would have been otherwise.
+#[allow(clippy::module_name_repetitions)]
+#[non_exhaustive]
+pub enum DylintError {
+    /// A library named with `--lib` or `--path` could not be found.
+    LibraryNotFound(Box<dyn std::error::Error + Send + Sync>),
+    /// A library could not be built.
+    BuildFailed(Box<dyn std::error::Error + Send + Sync>),
+    /// A package could not be checked, e.g., because of a compilation error or a `deny`-level lint.
+    CompilationFailed(Box<dyn std::error::Error + Send + Sync>),
+    /// A `dylint.toml` file or `workspace.metadata.dylint` table could not be read or is invalid.
+    Config(Box<dyn std::error::Error + Send + Sync>),
+    /// Any other error.
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl DylintError {
+    /// Converts an internal error into a `DylintError`. The variant is determined by the
+    /// outermost [`ErrorKind`] attached to the error, if any.
+    pub(crate) fn new(error: anyhow::Error) -> Self {
+        let kind = ErrorKind::of(&error);
+        let error = error.into();
+        match kind {
+            Some(ErrorKind::LibraryNotFound) => Self::LibraryNotFound(error),
+            Some(ErrorKind::BuildFailed) => Self::BuildFailed(error),
+            Some(ErrorKind::CompilationFailed) => Self::CompilationFailed(error),
+            Some(ErrorKind::Config) => Self::Config(error),
+            None => Self::Other(error),
+        }
+    }
+
+    fn inner(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        match self {
+            Self::LibraryNotFound(error)
+            | Self::BuildFailed(error)
+            | Self::CompilationFailed(error)
+            | Self::Config(error)
+            | Self::Other(error) => error.as_ref(),
+        }
+    }
+}
+
+impl std::fmt::Debug for DylintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.inner(), f)
+    }
+}
+
+impl std::fmt::Display for DylintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.inner(), f)
+    }
+}
+
+impl std::error::Error for DylintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ErrorKind {
+    LibraryNotFound,
+    BuildFailed,
+    CompilationFailed,
+    Config,
+}
+
+impl ErrorKind {
+    /// Attaches `self` to `error` so that [`DylintError::new`] can recover it, without changing
+    /// how `error` is displayed.
+    pub(crate) fn attach(self, error: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(Classified { kind: self, error })
+    }
+
+    /// Returns the outermost `ErrorKind` attached to `error`, if any.
+    pub(crate) fn of(error: &anyhow::Error) -> Option<Self> {
+        error
+            .chain()
+            .find_map(|error| error.downcast_ref::<Classified>())
+            .map(|classified| classified.kind)
+    }
+}
+
+pub(crate) trait ResultExt<T> {
+    fn error_kind(self, kind: ErrorKind) -> anyhow::Result<T>;
+}
+
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: Into<anyhow::Error>,
+{
+    fn error_kind(self, kind: ErrorKind) -> anyhow::Result<T> {
+        self.map_err(|error| kind.attach(error.into()))
+    }
+}
+
+// `Classified` is transparent: its `Display` implementation is that of the wrapped error,
+// and its `source` is the wrapped error's `source`. So attaching an `ErrorKind` does not add a link
+// to the error chain.
+#[derive(Debug)]
+struct Classified {
+    kind: ErrorKind,
+    error: anyhow::Error,
+}
+
+impl std::fmt::Display for Classified {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&*self.error, f)
+    }
+}
+
+impl std::error::Error for Classified {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
 
 #[allow(clippy::expect_used)]
 pub fn warn(opts: &crate::opts::Dylint, message: &str) {
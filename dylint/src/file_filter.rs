@@ -0,0 +1,84 @@
+use anyhow::{anyhow, Context, Result};
+use cargo_metadata::{diagnostic::Diagnostic, Metadata};
+use glob::Pattern;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Per-lint globs from the `include` and `exclude` tables of the workspace's `dylint.toml` file
+pub struct FileFilter {
+    workspace_root: PathBuf,
+    include: BTreeMap<String, Vec<Pattern>>,
+    exclude: BTreeMap<String, Vec<Pattern>>,
+}
+
+impl FileFilter {
+    /// Returns `None` if the workspace's `dylint.toml` file has neither an `include` nor an
+    /// `exclude` table.
+    pub fn from_dylint_toml(
+        metadata: &Metadata,
+        dylint_toml: Option<&toml::Table>,
+    ) -> Result<Option<Self>> {
+        let include = patterns(dylint_toml, "include")?;
+        let exclude = patterns(dylint_toml, "exclude")?;
+        if include.is_empty() && exclude.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            workspace_root: metadata.workspace_root.clone().into(),
+            include,
+            exclude,
+        }))
+    }
+
+    /// Returns true if `diagnostic` should be reported, i.e., if `diagnostic` is not for a lint
+    /// named in the `include` or `exclude` tables, or if its primary span's file is matched by the
+    /// lint's `include` globs (if any) and not by its `exclude` globs.
+    pub fn allows(&self, diagnostic: &Diagnostic) -> bool {
+        let Some(code) = &diagnostic.code else {
+            return true;
+        };
+        let Some(span) = diagnostic.spans.iter().find(|span| span.is_primary) else {
+            return true;
+        };
+        // Cargo runs `rustc` from the workspace root, so the paths of workspace members'
+        // files are relative to it.
+        let path = Path::new(&span.file_name);
+        let path = path.strip_prefix(&self.workspace_root).unwrap_or(path);
+        let matches =
+            |patterns: &Vec<Pattern>| patterns.iter().any(|pattern| pattern.matches_path(path));
+        self.include.get(&code.code).map_or(true, matches)
+            && !self.exclude.get(&code.code).is_some_and(matches)
+    }
+}
+
+fn patterns(
+    dylint_toml: Option<&toml::Table>,
+    key: &str,
+) -> Result<BTreeMap<String, Vec<Pattern>>> {
+    let Some(value) = dylint_toml.and_then(|table| table.get(key)) else {
+        return Ok(BTreeMap::new());
+    };
+    let table = value
+        .as_table()
+        .ok_or_else(|| anyhow!("`{key}` value must be a table"))?;
+    table
+        .iter()
+        .map(|(name, value)| {
+            let globs = value
+                .as_array()
+                .ok_or_else(|| anyhow!("`{key}` value for lint `{name}` must be an array"))?;
+            let patterns = globs
+                .iter()
+                .map(|glob| {
+                    let glob = glob.as_str().ok_or_else(|| {
+                        anyhow!("`{key}` value for lint `{name}` must contain only strings")
+                    })?;
+                    Pattern::new(glob).with_context(|| format!("Could not parse glob `{glob}`"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok((name.clone(), patterns))
+        })
+        .collect()
+}
@@ -0,0 +1,378 @@
+use anyhow::{Context, Result};
+use cargo_metadata::diagnostic::{Applicability, Diagnostic};
+use similar::TextDiff;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{read, write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct Replacement {
+    byte_start: u32,
+    byte_end: u32,
+    text: String,
+}
+
+/// A machine-applicable suggestion, i.e., a set of replacements that must be applied together
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Suggestion {
+    lint: String,
+    replacements: BTreeMap<PathBuf, Vec<Replacement>>,
+}
+
+/// The suggestions applied for one lint
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Fixes {
+    pub n_applied: usize,
+    pub files: BTreeSet<PathBuf>,
+}
+
+/// The contents of the files modified by [`apply`] before they were modified, keyed by their paths
+/// relative to `workspace_root`
+pub type Snapshot = BTreeMap<PathBuf, Vec<u8>>;
+
+impl Suggestion {
+    #[must_use]
+    pub fn lint(&self) -> &str {
+        &self.lint
+    }
+
+    fn overlaps(&self, other: &Self) -> bool {
+        self.replacements.iter().any(|(path, replacements)| {
+            other
+                .replacements
+                .get(path)
+                .is_some_and(|other_replacements| {
+                    replacements.iter().any(|replacement| {
+                        other_replacements
+                            .iter()
+                            .any(|other_replacement| replacement.overlaps(other_replacement))
+                    })
+                })
+        })
+    }
+}
+
+impl Replacement {
+    // Two insertions at the same position are considered to overlap, since the order in
+    // which they would be applied is ambiguous.
+    fn overlaps(&self, other: &Self) -> bool {
+        (self.byte_start < other.byte_end && other.byte_start < self.byte_end)
+            || (self.byte_start == other.byte_start && self.byte_end == other.byte_end)
+    }
+}
+
+/// Collects the machine-applicable suggestions in `diagnostic` and its children. Each child
+/// diagnostic whose spans all carry machine-applicable replacements is one suggestion.
+pub fn collect(diagnostic: &Diagnostic, suggestions: &mut Vec<Suggestion>) {
+    // Only the top-level diagnostic carries the lint name.
+    let lint = diagnostic
+        .code
+        .as_ref()
+        .map_or_else(|| String::from("<unknown>"), |code| code.code.clone());
+    collect_with_lint(diagnostic, &lint, suggestions);
+}
+
+fn collect_with_lint(diagnostic: &Diagnostic, lint: &str, suggestions: &mut Vec<Suggestion>) {
+    if let Some(suggestion) = suggestion(diagnostic, lint) {
+        suggestions.push(suggestion);
+    }
+    for child in &diagnostic.children {
+        collect_with_lint(child, lint, suggestions);
+    }
+}
+
+fn suggestion(diagnostic: &Diagnostic, lint: &str) -> Option<Suggestion> {
+    if diagnostic.spans.is_empty() {
+        return None;
+    }
+    let mut replacements = BTreeMap::<_, Vec<_>>::new();
+    for span in &diagnostic.spans {
+        let text = span.suggested_replacement.as_ref()?;
+        if span.suggestion_applicability != Some(Applicability::MachineApplicable) {
+            return None;
+        }
+        replacements
+            .entry(PathBuf::from(&span.file_name))
+            .or_default()
+            .push(Replacement {
+                byte_start: span.byte_start,
+                byte_end: span.byte_end,
+                text: text.clone(),
+            });
+    }
+    Some(Suggestion {
+        lint: lint.to_owned(),
+        replacements,
+    })
+}
+
+/// Applies the suggestions that do not overlap with one another, and returns the suggestions
+/// applied, grouped by lint, along with the number of suggestions skipped and a [`Snapshot`] that
+/// can be passed to [`restore`]. Suggestions are considered in order; a suggestion that overlaps
+/// one already selected is skipped, so that it can be applied by a subsequent run.
+///
+/// Relative paths are resolved against `workspace_root`. Suggestions for files outside of
+/// `workspace_root` are ignored.
+pub fn apply(
+    workspace_root: &Path,
+    suggestions: Vec<Suggestion>,
+) -> Result<(BTreeMap<String, Fixes>, usize, Snapshot)> {
+    let (selected, n_skipped) = select(workspace_root, suggestions);
+
+    let mut fixes = BTreeMap::<_, Fixes>::new();
+    for suggestion in &selected {
+        let lint_fixes = fixes.entry(suggestion.lint.clone()).or_default();
+        lint_fixes.n_applied += 1;
+        lint_fixes
+            .files
+            .extend(suggestion.replacements.keys().cloned());
+    }
+
+    let mut snapshot = Snapshot::new();
+    for (path, Edit { original, edited }) in edit(workspace_root, selected)? {
+        let full_path = workspace_root.join(&path);
+        write(&full_path, edited)
+            .with_context(|| format!("`write` failed for `{}`", full_path.display()))?;
+        snapshot.insert(path, original);
+    }
+
+    Ok((fixes, n_skipped, snapshot))
+}
+
+/// Restores the files modified by [`apply`] to their contents in `snapshot`
+pub fn restore(workspace_root: &Path, snapshot: &Snapshot) -> Result<()> {
+    for (path, original) in snapshot {
+        let full_path = workspace_root.join(path);
+        write(&full_path, original)
+            .with_context(|| format!("`write` failed for `{}`", full_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Like [`apply`], but rather than modify any files, returns a unified diff of the changes that
+/// `apply` would make, along with the number of suggestions skipped. The diff's paths are relative
+/// to `workspace_root`.
+pub fn patch(workspace_root: &Path, suggestions: Vec<Suggestion>) -> Result<(String, usize)> {
+    let (selected, n_skipped) = select(workspace_root, suggestions);
+
+    let mut patch = String::new();
+    for (path, Edit { original, edited }) in edit(workspace_root, selected)? {
+        let original = std::str::from_utf8(&original)
+            .with_context(|| format!("`{}` is not valid UTF-8", path.display()))?;
+        let edited = std::str::from_utf8(&edited)
+            .with_context(|| format!("Suggestions for `{}` are not valid UTF-8", path.display()))?;
+        // Use forward slashes so that the patch can be applied on any platform.
+        let path = path.to_string_lossy().replace('\\', "/");
+        patch.push_str(
+            &TextDiff::from_lines(original, edited)
+                .unified_diff()
+                .header(&format!("a/{path}"), &format!("b/{path}"))
+                .to_string(),
+        );
+    }
+
+    Ok((patch, n_skipped))
+}
+
+/// Returns the suggestions that do not overlap with one another, along with the number of
+/// suggestions skipped.
+fn select(workspace_root: &Path, suggestions: Vec<Suggestion>) -> (Vec<Suggestion>, usize) {
+    // The same suggestion can be reported more than once, e.g., when a file is part of
+    // multiple targets.
+    let mut seen = BTreeSet::new();
+    let mut selected = Vec::<Suggestion>::new();
+    let mut n_skipped = 0;
+
+    for suggestion in suggestions {
+        if !suggestion
+            .replacements
+            .keys()
+            .all(|path| workspace_root.join(path).starts_with(workspace_root))
+            || !seen.insert(suggestion.clone())
+        {
+            continue;
+        }
+        if selected.iter().any(|other| other.overlaps(&suggestion)) {
+            n_skipped += 1;
+            continue;
+        }
+        selected.push(suggestion);
+    }
+
+    (selected, n_skipped)
+}
+
+/// The contents of a file before and after a set of replacements are applied
+struct Edit {
+    original: Vec<u8>,
+    edited: Vec<u8>,
+}
+
+/// Returns the contents of each file affected by `selected`, before and after the suggestions'
+/// replacements are applied. The files are keyed by their paths relative to `workspace_root`.
+fn edit(workspace_root: &Path, selected: Vec<Suggestion>) -> Result<BTreeMap<PathBuf, Edit>> {
+    let mut replacements_by_path = BTreeMap::<_, Vec<_>>::new();
+    for suggestion in selected {
+        for (path, replacements) in suggestion.replacements {
+            let path = workspace_root.join(path);
+            let path = path
+                .strip_prefix(workspace_root)
+                .map(Path::to_path_buf)
+                .unwrap_or(path);
+            replacements_by_path
+                .entry(path)
+                .or_default()
+                .extend(replacements);
+        }
+    }
+
+    let mut edited = BTreeMap::new();
+    for (path, mut replacements) in replacements_by_path {
+        let full_path = workspace_root.join(&path);
+        let original = read(&full_path)
+            .with_context(|| format!("`read` failed for `{}`", full_path.display()))?;
+        let mut contents = original.clone();
+        // Apply the replacements from last to first so that earlier byte offsets remain
+        // valid.
+        replacements.sort_by(|x, y| y.cmp(x));
+        for Replacement {
+            byte_start,
+            byte_end,
+            text,
+        } in replacements
+        {
+            contents.splice(byte_start as usize..byte_end as usize, text.into_bytes());
+        }
+        edited.insert(
+            path,
+            Edit {
+                original,
+                edited: contents,
+            },
+        );
+    }
+
+    Ok(edited)
+}
+
+/// Returns one line per lint, like "Dylint: applied 3 fixes for `lint` in 2 files"
+pub fn summary(fixes: &BTreeMap<String, Fixes>) -> Vec<String> {
+    fixes
+        .iter()
+        .map(|(lint, Fixes { n_applied, files })| {
+            format!(
+                "Dylint: applied {n_applied} fix{} for `{lint}` in {} file{}",
+                if *n_applied == 1 { "" } else { "es" },
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn suggestion(
+        lint: &str,
+        path: &str,
+        byte_start: u32,
+        byte_end: u32,
+        text: &str,
+    ) -> Suggestion {
+        Suggestion {
+            lint: lint.to_owned(),
+            replacements: BTreeMap::from([(
+                PathBuf::from(path),
+                vec![Replacement {
+                    byte_start,
+                    byte_end,
+                    text: text.to_owned(),
+                }],
+            )]),
+        }
+    }
+
+    #[test]
+    fn overlapping_suggestions_are_skipped() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("lib.rs");
+        write(&path, "let x = a.clone().clone();").unwrap();
+
+        let (fixes, n_skipped, _) = apply(
+            tempdir.path(),
+            vec![
+                suggestion("redundant_clone", "lib.rs", 9, 17, ""),
+                suggestion("redundant_clone", "lib.rs", 9, 17, ""),
+                suggestion("redundant_clone", "lib.rs", 8, 25, "b"),
+                suggestion("let_to_const", "lib.rs", 0, 3, "const"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(1, n_skipped);
+        assert_eq!(
+            vec![
+                "Dylint: applied 1 fix for `let_to_const` in 1 file",
+                "Dylint: applied 1 fix for `redundant_clone` in 1 file",
+            ],
+            summary(&fixes)
+        );
+        assert_eq!(
+            "const x = a.clone();",
+            std::fs::read_to_string(path).unwrap()
+        );
+    }
+
+    #[test]
+    fn restore_undoes_apply() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("lib.rs");
+        write(&path, "let x = a.clone().clone();\n").unwrap();
+
+        let (_, _, snapshot) = apply(
+            tempdir.path(),
+            vec![suggestion("redundant_clone", "lib.rs", 9, 17, "")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            "let x = a.clone();\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+
+        restore(tempdir.path(), &snapshot).unwrap();
+
+        assert_eq!(
+            "let x = a.clone().clone();\n",
+            std::fs::read_to_string(path).unwrap()
+        );
+    }
+
+    #[test]
+    fn patch_does_not_modify_files() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("lib.rs");
+        write(&path, "let x = a.clone().clone();\n").unwrap();
+
+        let (patch, n_skipped) = patch(
+            tempdir.path(),
+            vec![suggestion("redundant_clone", "lib.rs", 9, 17, "")],
+        )
+        .unwrap();
+
+        assert_eq!(0, n_skipped);
+        assert_eq!(
+            "--- a/lib.rs\n+++ b/lib.rs\n@@ -1 +1 @@\n-let x = a.clone().clone();\n+let x = \
+             a.clone();\n",
+            patch
+        );
+        assert_eq!(
+            "let x = a.clone().clone();\n",
+            std::fs::read_to_string(path).unwrap()
+        );
+    }
+}
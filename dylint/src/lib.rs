@@ -4,18 +4,26 @@
 #![deny(clippy::panic)]
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
-use cargo_metadata::MetadataCommand;
+use cargo_metadata::{diagnostic::Diagnostic, Metadata, MetadataCommand, PackageId};
 use dylint_internal::{
-    driver as dylint_driver, env, parse_path_filename, rustup::SanitizeEnvironment, CommandExt,
+    config, driver as dylint_driver, env, parse_path_filename, rustup::SanitizeEnvironment,
+    CommandExt,
 };
+use is_terminal::IsTerminal;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env::{consts, current_dir},
     ffi::OsStr,
-    fs::{metadata, OpenOptions},
+    fs::{metadata, read_to_string, write, OpenOptions},
+    io::Write,
+    ops::Range,
     path::{Path, PathBuf, MAIN_SEPARATOR},
+    process::{Command, Stdio},
 };
+use tempfile::tempdir;
+use walkdir::WalkDir;
 
 type Object = serde_json::Map<String, serde_json::Value>;
 
@@ -26,10 +34,10 @@ pub(crate) use cargo::{core, sources, util};
 pub mod driver_builder;
 
 mod error;
-use error::warn;
 #[doc(hidden)]
 pub use error::warn as __warn;
-pub use error::{ColorizedError, ColorizedResult};
+use error::{warn, ErrorKind, ResultExt};
+pub use error::{ColorizedError, ColorizedResult, DylintError};
 
 mod name_toolchain_map;
 pub use name_toolchain_map::{Lazy as NameToolchainMap, ToolchainMap};
@@ -43,6 +51,19 @@ pub mod opts;
 #[cfg(feature = "package_options")]
 mod package_options;
 
+mod file_filter;
+use file_filter::FileFilter;
+
+mod fix_noconflict;
+
+mod messages;
+
+mod progress;
+use progress::Progress;
+
+mod stats;
+use stats::Stats;
+
 static REQUIRED_FORM: Lazy<String> = Lazy::new(|| {
     format!(
         r#""{}" LIBRARY_NAME "@" TOOLCHAIN "{}""#,
@@ -51,53 +72,17 @@ static REQUIRED_FORM: Lazy<String> = Lazy::new(|| {
     )
 });
 
-#[cfg_attr(dylint_lib = "general", allow(non_local_effect_before_error_return))]
-pub fn run(opts: &opts::Dylint) -> Result<()> {
-    let opts = {
-        let opts_orig = opts;
-
-        let mut opts = opts.clone();
-
-        if matches!(
-            opts.operation,
-            opts::Operation::Check(_) | opts::Operation::List(_)
-        ) {
-            let lib_sel = opts.library_selection_mut();
-
-            let path_refers_to_libraries =
-                lib_sel
-                    .paths
-                    .iter()
-                    .try_fold(false, |is_file, path| -> Result<_> {
-                        let metadata =
-                            metadata(path).with_context(|| "Could not read file metadata")?;
-                        Ok(is_file || metadata.is_file())
-                    })?;
-
-            if path_refers_to_libraries {
-                warn(
-                    opts_orig,
-                    "Referring to libraries with `--path` is deprecated. Use `--lib-path`.",
-                );
-                lib_sel.lib_paths.extend(lib_sel.paths.split_off(0));
-            };
+/// Runs Dylint with the given options.
+///
+/// The returned error's variant indicates the kind of failure, e.g., whether a library could not
+/// be found or built, or whether the package being checked failed to compile.
+pub fn run(opts: &opts::Dylint) -> std::result::Result<(), DylintError> {
+    run_impl(opts).map_err(DylintError::new)
+}
 
-            // smoelius: Use of `--git` or `--path` implies `--all`.
-            //
-            // smoelius: The intuition is as follows. Option `--git` or `--path` creates an
-            // alternative namespace of library packages, i.e., alternative to the one built
-            // from workspace metadata. The alternative namespace consists of just the
-            // library packages in the `--git`-named repository, or the `--path`-named
-            // directory.
-            //
-            // It would seem silly to require the user to pass `--lib` in addition to `--git` or
-            // `--path`. For this reason, all of the libraries in the alternative namespace are
-            // loaded.
-            lib_sel.all |= lib_sel.git_or_path();
-        }
-
-        opts
-    };
+#[cfg_attr(dylint_lib = "general", allow(non_local_effect_before_error_return))]
+fn run_impl(opts: &opts::Dylint) -> Result<()> {
+    let opts = preprocess_opts(opts)?;
 
     if opts.has_library_selection()
         && opts.library_selection().pattern.is_some()
@@ -115,7 +100,10 @@ pub fn run(opts: &opts::Dylint) -> Result<()> {
     }
 
     match &opts.operation {
-        opts::Operation::Check(_) | opts::Operation::List(_) => {
+        opts::Operation::Check(_)
+        | opts::Operation::CheckConfig(_)
+        | opts::Operation::CheckFile(_)
+        | opts::Operation::List(_) => {
             let name_toolchain_map = NameToolchainMap::new(&opts);
             run_with_name_toolchain_map(&opts, &name_toolchain_map)
         }
@@ -128,13 +116,105 @@ pub fn run(opts: &opts::Dylint) -> Result<()> {
     }
 }
 
+fn preprocess_opts(opts: &opts::Dylint) -> Result<opts::Dylint> {
+    let opts_orig = opts;
+
+    let mut opts = opts.clone();
+
+    if matches!(
+        opts.operation,
+        opts::Operation::Check(_)
+            | opts::Operation::CheckConfig(_)
+            | opts::Operation::CheckFile(_)
+            | opts::Operation::List(_)
+    ) {
+        let lib_sel = opts.library_selection_mut();
+
+        let path_refers_to_libraries =
+            lib_sel
+                .paths
+                .iter()
+                .try_fold(false, |is_file, path| -> Result<_> {
+                    let metadata =
+                        metadata(path).with_context(|| "Could not read file metadata")?;
+                    Ok(is_file || metadata.is_file())
+                })?;
+
+        if path_refers_to_libraries {
+            warn(
+                opts_orig,
+                "Referring to libraries with `--path` is deprecated. Use `--lib-path`.",
+            );
+            lib_sel.lib_paths.extend(lib_sel.paths.split_off(0));
+        };
+
+        // smoelius: Use of `--git` or `--path` implies `--all`.
+        //
+        // smoelius: The intuition is as follows. Option `--git` or `--path` creates an
+        // alternative namespace of library packages, i.e., alternative to the one built
+        // from workspace metadata. The alternative namespace consists of just the
+        // library packages in the `--git`-named repository, or the `--path`-named
+        // directory.
+        //
+        // It would seem silly to require the user to pass `--lib` in addition to `--git` or
+        // `--path`. For this reason, all of the libraries in the alternative namespace are
+        // loaded.
+        lib_sel.all |= lib_sel.git_or_path();
+    }
+
+    // `--collect-only` implies `--keep-going`. Otherwise, there would be little to
+    // collect.
+    if let opts::Operation::Check(check_opts) = &mut opts.operation {
+        check_opts.keep_going |= check_opts.collect_only;
+    }
+
+    Ok(opts)
+}
+
 fn run_with_name_toolchain_map(
     opts: &opts::Dylint,
     name_toolchain_map: &NameToolchainMap,
 ) -> Result<()> {
     let lib_sel = opts.library_selection();
 
-    if lib_sel.libs.is_empty() && lib_sel.lib_paths.is_empty() && !lib_sel.all {
+    let nothing_selected = lib_sel.libs.is_empty()
+        && lib_sel.lib_dirs.is_empty()
+        && lib_sel.lib_paths.is_empty()
+        && !lib_sel.all;
+
+    if let opts::Operation::Check(check_opts) = &opts.operation {
+        if check_opts.print_driver_path || check_opts.print_sysroot {
+            let toolchains = if nothing_selected {
+                let current_dir =
+                    current_dir().with_context(|| "Could not get current directory")?;
+                vec![dylint_internal::rustup::active_toolchain(&current_dir)?]
+            } else {
+                resolve(opts, name_toolchain_map)?.into_keys().collect()
+            };
+            if check_opts.print_driver_path {
+                print_driver_paths(opts, &toolchains)?;
+            }
+            if check_opts.print_sysroot {
+                print_sysroots(&toolchains)?;
+            }
+            return Ok(());
+        }
+    }
+
+    if let opts::Operation::List(list_opts) = &opts.operation {
+        if list_opts.toolchains {
+            ensure!(
+                lib_sel.libs.is_empty()
+                    && lib_sel.lib_dirs.is_empty()
+                    && lib_sel.lib_paths.is_empty(),
+                "`--toolchains` cannot be used with `--lib`, `--lib-dir`, or `--lib-path`"
+            );
+            warn_if_empty(opts, name_toolchain_map)?;
+            return list_toolchains(list_opts, name_toolchain_map);
+        }
+    }
+
+    if nothing_selected {
         if matches!(opts.operation, opts::Operation::List(_)) {
             warn_if_empty(opts, name_toolchain_map)?;
             return list_libs(name_toolchain_map);
@@ -144,10 +224,13 @@ fn run_with_name_toolchain_map(
         return Ok(());
     }
 
-    let resolved = resolve(opts, name_toolchain_map)?;
+    let (resolved, build_failures) = resolve_with_build_failures(opts, name_toolchain_map)?;
 
-    if resolved.is_empty() {
+    // If libraries were skipped or could not be built, `resolved` can be empty even
+    // though libraries were found.
+    if resolved.is_empty() && build_failures.is_empty() && !lib_sel.skip_unbuildable {
         assert!(lib_sel.libs.is_empty());
+        assert!(lib_sel.lib_dirs.is_empty());
         assert!(lib_sel.lib_paths.is_empty());
 
         let name_toolchain_map_is_empty = warn_if_empty(opts, name_toolchain_map)?;
@@ -157,12 +240,145 @@ fn run_with_name_toolchain_map(
         assert!(name_toolchain_map_is_empty || !lib_sel.all);
     }
 
-    match &opts.operation {
-        opts::Operation::Check(check_opts) => check_or_fix(opts, check_opts, &resolved),
-        opts::Operation::List(_) => list_lints(opts, &resolved),
+    let result = match &opts.operation {
+        // The paths printed are exactly those that would be passed to the driver in
+        // `DYLINT_LIBS`, grouped by toolchain.
+        opts::Operation::Check(check_opts) if check_opts.print_libs_json => {
+            println!("{}", serde_json::to_string_pretty(&resolved)?);
+            Ok(())
+        }
+        opts::Operation::Check(check_opts) => {
+            ensure!(
+                !check_opts.deps_first || opts.pipe_stdout.is_none(),
+                "`--deps-first` cannot be used with `--pipe-stdout`"
+            );
+            if !check_opts.workspace_dirs.is_empty() {
+                check_workspace_dirs(opts, check_opts, &resolved)
+            } else if check_opts.stats.is_none() && !check_opts.summary {
+                check_or_fix(opts, check_opts, &resolved, None, None)
+            } else {
+                check_with_stats(opts, check_opts, &resolved)
+            }
+        }
+        opts::Operation::CheckConfig(check_config_opts) => {
+            check_config(opts, check_config_opts, &resolved)
+        }
+        opts::Operation::CheckFile(check_file_opts) => check_file(opts, check_file_opts, &resolved),
+        opts::Operation::List(list_opts) => list_lints(opts, list_opts, &resolved),
         #[allow(unreachable_patterns)]
         _ => unreachable!(),
+    };
+
+    // With `--keep-going`, the libraries that could not be built are reported together,
+    // after the others have been used.
+    let result = result.and_then(|()| ensure_no_build_failures(&build_failures));
+
+    // With `--collect-only`, build and compilation failures are reported as warnings.
+    // Other errors, e.g., an invalid `dylint.toml` file, are still returned.
+    match (&opts.operation, result) {
+        (opts::Operation::Check(check_opts), Err(error))
+            if check_opts.collect_only
+                && matches!(
+                    ErrorKind::of(&error),
+                    Some(ErrorKind::BuildFailed | ErrorKind::CompilationFailed)
+                ) =>
+        {
+            warn(opts, &format!("{error:#}"));
+            Ok(())
+        }
+        (_, result) => result,
+    }
+}
+
+// Each workspace is checked as though `--manifest-path DIR/Cargo.toml` had been passed.
+// The libraries are resolved only once, before any workspace is checked.
+fn check_workspace_dirs(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    resolved: &ToolchainMap,
+) -> Result<()> {
+    let mut failures = Vec::new();
+
+    for dir in &check_opts.workspace_dirs {
+        if !opts.quiet {
+            eprintln!("Dylint: checking workspace `{dir}`");
+        }
+        let manifest_path = Path::new(dir).join("Cargo.toml");
+        let check_opts = opts::Check {
+            lib_sel: opts::LibrarySelection {
+                manifest_path: Some(manifest_path.to_string_lossy().to_string()),
+                ..check_opts.lib_sel.clone()
+            },
+            workspace_dirs: Vec::new(),
+            ..check_opts.clone()
+        };
+        let result = if check_opts.summary {
+            check_with_stats(opts, &check_opts, resolved)
+        } else {
+            check_or_fix(opts, &check_opts, resolved, None, None)
+        };
+        if let Err(error) = result {
+            warn(
+                opts,
+                &format!("Checking workspace `{dir}` failed: {error:#}"),
+            );
+            failures.push(dir.clone());
+        }
+    }
+
+    if !opts.quiet {
+        for dir in &check_opts.workspace_dirs {
+            eprintln!(
+                "Dylint: workspace `{dir}`: {}",
+                if failures.contains(dir) {
+                    "failed"
+                } else {
+                    "ok"
+                }
+            );
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorKind::CompilationFailed.attach(anyhow!(
+            "Checking failed for the following workspaces: {:?}",
+            failures
+        )))
+    }
+}
+
+fn check_with_stats(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    resolved: &ToolchainMap,
+) -> Result<()> {
+    ensure!(
+        opts.pipe_stdout.is_none(),
+        "`{}` cannot be used with `--pipe-stdout`",
+        if check_opts.stats.is_some() {
+            "--stats"
+        } else {
+            "--summary"
+        }
+    );
+    // The lint names are needed to distinguish diagnostics produced by Dylint
+    // lints from those produced by rustc or Clippy.
+    let lint_names = if check_opts.summary {
+        Some(lint_names(opts, resolved)?)
+    } else {
+        None
+    };
+    let mut stats = Stats::new(resolved);
+    let result = check_or_fix(opts, check_opts, resolved, Some(&mut stats), None);
+    if let Some(lint_names) = lint_names {
+        eprintln!("{}", stats.summary(&lint_names));
     }
+    if let Some(stats_path) = &check_opts.stats {
+        stats.write(stats_path)?;
+    }
+    result
 }
 
 fn warn_if_empty(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) -> Result<bool> {
@@ -204,28 +420,75 @@ fn list_libs(name_toolchain_map: &NameToolchainMap) -> Result<()> {
     Ok(())
 }
 
+fn list_toolchains(list_opts: &opts::List, name_toolchain_map: &NameToolchainMap) -> Result<()> {
+    let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
+
+    let toolchains = name_toolchain_map
+        .values()
+        .flat_map(LazyToolchainMap::keys)
+        .collect::<BTreeSet<_>>();
+
+    if list_opts.message_format.as_deref() == Some("json") {
+        println!("{}", serde_json::to_string(&toolchains)?);
+    } else {
+        for toolchain in toolchains {
+            println!("{toolchain}");
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg_attr(
     dylint_lib = "question_mark_in_expression",
     allow(question_mark_in_expression)
 )]
 fn resolve(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) -> Result<ToolchainMap> {
+    let (toolchain_map, build_failures) = resolve_with_build_failures(opts, name_toolchain_map)?;
+    ensure_no_build_failures(&build_failures)?;
+    Ok(toolchain_map)
+}
+
+fn ensure_no_build_failures(build_failures: &[String]) -> Result<()> {
+    if build_failures.is_empty() {
+        return Ok(());
+    }
+    Err(ErrorKind::BuildFailed.attach(anyhow!(
+        "Could not build the following libraries: {:?}",
+        build_failures
+    )))
+}
+
+/// Like [`resolve`], but with `--keep-going`, a library that fails to build does not cause an
+/// error. Instead, the library is omitted from the returned map, and its name and toolchain are
+/// included in the returned vector.
+fn resolve_with_build_failures(
+    opts: &opts::Dylint,
+    name_toolchain_map: &NameToolchainMap,
+) -> Result<(ToolchainMap, Vec<String>)> {
     let lib_sel = opts.library_selection();
 
+    let keep_going = matches!(
+        &opts.operation,
+        opts::Operation::Check(check_opts) if check_opts.keep_going
+    );
+
+    let mut build_failures = Vec::new();
+
     let mut toolchain_map = ToolchainMap::new();
 
+    let mut maybe_libraries = Vec::new();
+
     if lib_sel.all {
         let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
 
-        for other in name_toolchain_map.values() {
-            for (toolchain, maybe_libraries) in other {
-                let paths = maybe_libraries
-                    .iter()
-                    .map(|maybe_library| maybe_library.build(opts))
-                    .collect::<Result<Vec<_>>>()?;
-                toolchain_map
-                    .entry(toolchain.clone())
-                    .or_default()
-                    .extend(paths);
+        for (name, other) in name_toolchain_map {
+            for (toolchain, other_maybe_libraries) in other {
+                maybe_libraries.extend(
+                    other_maybe_libraries.iter().map(|maybe_library| {
+                        (name.clone(), toolchain.clone(), maybe_library.clone())
+                    }),
+                );
             }
         }
     }
@@ -234,7 +497,48 @@ fn resolve(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) -> Result
         ensure!(!lib_sel.all, "`--lib` cannot be used with `--all`");
         let (toolchain, maybe_library) =
             name_as_lib(name_toolchain_map, name, true)?.unwrap_or_else(|| unreachable!());
-        let path = maybe_library.build(opts)?;
+        maybe_libraries.push((name.clone(), toolchain, maybe_library));
+    }
+
+    let mut progress = Progress::new(
+        opts,
+        maybe_libraries
+            .iter()
+            .filter(|(_, _, maybe_library)| maybe_library.is_package())
+            .count(),
+    );
+
+    for (name, toolchain, maybe_library) in maybe_libraries {
+        if maybe_library.is_package() {
+            progress.building(&name);
+        }
+        // `--skip-unbuildable` applies only to libraries found with `--all`. A library
+        // named with `--lib` that fails to build is still an error.
+        let path = match maybe_library.build(opts) {
+            Ok(path) => path,
+            Err(error) if lib_sel.all && lib_sel.skip_unbuildable => {
+                warn(
+                    opts,
+                    &format!(
+                        "Could not build `{name}` with toolchain `{toolchain}`; skipping: \
+                         {error:#}"
+                    ),
+                );
+                continue;
+            }
+            Err(error) if keep_going => {
+                warn(
+                    opts,
+                    &format!(
+                        "Could not build `{name}` with toolchain `{toolchain}`; continuing: \
+                         {error:#}"
+                    ),
+                );
+                build_failures.push(format!("{name}@{toolchain}"));
+                continue;
+            }
+            Err(error) => return Err(ErrorKind::BuildFailed.attach(error)),
+        };
         toolchain_map.entry(toolchain).or_default().insert(path);
     }
 
@@ -243,7 +547,96 @@ fn resolve(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) -> Result
         toolchain_map.entry(toolchain).or_default().insert(path);
     }
 
-    Ok(toolchain_map)
+    for dir in &lib_sel.lib_dirs {
+        for (toolchain, path) in libraries_in_dir(opts, dir)? {
+            toolchain_map.entry(toolchain).or_default().insert(path);
+        }
+    }
+
+    if lib_sel.require_single_toolchain {
+        ensure_single_toolchain(&toolchain_map)?;
+    }
+
+    Ok((toolchain_map, build_failures))
+}
+
+#[allow(clippy::format_collect)]
+fn ensure_single_toolchain(toolchain_map: &ToolchainMap) -> Result<()> {
+    if toolchain_map.len() <= 1 {
+        return Ok(());
+    }
+
+    bail!(
+        "`--require-single-toolchain` was used, but the libraries require multiple toolchains:{}",
+        toolchain_map
+            .iter()
+            .flat_map(|(toolchain, paths)| {
+                paths.iter().map(move |path| {
+                    let name = parse_path_filename(path)
+                        .map_or_else(|| path.to_string_lossy().to_string(), |(name, _)| name);
+                    format!("\n    {name}@{toolchain}")
+                })
+            })
+            .collect::<String>()
+    );
+}
+
+/// Returns the filename of the Dylint library named `name` built with `toolchain`.
+///
+/// The filename has the form `"<DLL_PREFIX>name@toolchain<DLL_SUFFIX>"` described in
+/// [`parse_library_filename`], with any `-` in `name` replaced by `_`.
+///
+/// # Examples
+///
+/// ```
+/// use dylint::{library_filename, parse_library_filename};
+/// use std::path::Path;
+///
+/// #[cfg(target_os = "linux")]
+/// assert_eq!(
+///     library_filename("foo", "stable-x86_64-unknown-linux-gnu"),
+///     "libfoo@stable-x86_64-unknown-linux-gnu.so"
+/// );
+///
+/// let filename = library_filename("foo", "stable-x86_64-unknown-linux-gnu");
+/// assert_eq!(
+///     parse_library_filename(Path::new(&filename)),
+///     Some((
+///         String::from("foo"),
+///         String::from("stable-x86_64-unknown-linux-gnu")
+///     ))
+/// );
+/// ```
+#[must_use]
+pub fn library_filename(name: &str, toolchain: &str) -> String {
+    dylint_internal::library_filename(name, toolchain)
+}
+
+/// Parses the filename of a Dylint library path into a tuple of (name, toolchain).
+///
+/// The filename must have the form `"<DLL_PREFIX>name@toolchain<DLL_SUFFIX>"`, where
+/// `<DLL_PREFIX>` and `<DLL_SUFFIX>` are the values of [`std::env::consts::DLL_PREFIX`] and
+/// [`std::env::consts::DLL_SUFFIX`] for the current platform. Returns `None` if the filename does
+/// not have this form.
+///
+/// # Examples
+///
+/// ```
+/// use dylint::parse_library_filename;
+/// use std::path::Path;
+///
+/// #[cfg(target_os = "linux")]
+/// assert_eq!(
+///     parse_library_filename(Path::new("libfoo@stable-x86_64-unknown-linux-gnu.so")),
+///     Some((
+///         String::from("foo"),
+///         String::from("stable-x86_64-unknown-linux-gnu")
+///     ))
+/// );
+/// ```
+#[must_use]
+pub fn parse_library_filename(path: &Path) -> Option<(String, String)> {
+    parse_path_filename(path)
 }
 
 pub fn name_as_lib(
@@ -256,16 +649,45 @@ pub fn name_as_lib(
         return Ok(None);
     }
 
+    // A name of the form `name@toolchain` selects the library built with `toolchain`.
+    let (name, toolchain) = name
+        .split_once('@')
+        .map_or((name, None), |(name, toolchain)| (name, Some(toolchain)));
+
+    // Initializing the map can require fetching and building every library named in the
+    // workspace metadata. If `--lib` names a library of which there is exactly one copy in
+    // `DYLINT_LIBRARY_PATH`, use that copy without initializing the map.
+    if as_lib_only {
+        if let Some(toolchain_maybe_library) =
+            name_toolchain_map.find_in_library_path(name, toolchain)?
+        {
+            return Ok(Some(toolchain_maybe_library));
+        }
+    }
+
     let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
 
     if let Some(toolchain_map) = name_toolchain_map.get(name) {
         let mut toolchain_maybe_libraries = flatten_toolchain_map(toolchain_map);
 
+        if let Some(toolchain) = toolchain {
+            toolchain_maybe_libraries.retain(|(other, _)| other == toolchain);
+            if toolchain_maybe_libraries.is_empty() {
+                return Err(ErrorKind::LibraryNotFound.attach(anyhow!(
+                    "Found no library matching `{}` for toolchain `{}`; toolchains found: {:?}",
+                    name,
+                    toolchain,
+                    toolchain_map.keys().collect::<Vec<_>>()
+                )));
+            }
+        }
+
         return match toolchain_maybe_libraries.len() {
             0 => Ok(None),
             1 => Ok(Some(toolchain_maybe_libraries.remove(0))),
             _ => Err(anyhow!(
-                "Found multiple libraries matching `{}`: {:?}",
+                "Found multiple libraries matching `{}` (use `{}@TOOLCHAIN` to select one): {:?}",
+                name,
                 name,
                 toolchain_maybe_libraries
                     .iter()
@@ -275,7 +697,9 @@ pub fn name_as_lib(
         };
     }
 
-    ensure!(!as_lib_only, "Could not find `--lib {}`", name);
+    if as_lib_only {
+        return Err(ErrorKind::LibraryNotFound.attach(anyhow!("Could not find `--lib {}`", name)));
+    }
 
     Ok(None)
 }
@@ -329,16 +753,72 @@ fn name_as_path(name: &str, as_path_only: bool) -> Result<Option<(String, PathBu
         );
     }
 
-    ensure!(!as_path_only, "Could not find `--path {}`", name);
+    if as_path_only {
+        return Err(ErrorKind::LibraryNotFound.attach(anyhow!("Could not find `--path {}`", name)));
+    }
 
     Ok(None)
 }
 
-fn list_lints(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<()> {
+// `libraries_in_dir` searches `dir` recursively, so that, e.g., libraries kept in
+// per-toolchain subdirectories are found. Files whose names do not have the required form are
+// skipped, and reported in a single warning.
+fn libraries_in_dir(opts: &opts::Dylint, dir: &str) -> Result<Vec<(String, PathBuf)>> {
+    let dir_path = PathBuf::from(dir)
+        .canonicalize()
+        .with_context(|| format!("Could not canonicalize `{dir}`"))?;
+    ensure!(
+        dir_path.is_dir(),
+        "`--lib-dir {}` was used, but it is not a directory",
+        dir
+    );
+
+    let mut libraries = Vec::new();
+    let mut n_skipped = 0;
+
+    for entry in WalkDir::new(&dir_path)
+        .follow_links(true)
+        .sort_by_file_name()
+    {
+        let entry = entry.with_context(|| format!("Could not read an entry of `{dir}`"))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.into_path();
+        if let Some((_, toolchain)) = parse_path_filename(&path) {
+            libraries.push((toolchain, path));
+        } else {
+            n_skipped += 1;
+        }
+    }
+
+    if n_skipped > 0 {
+        warn(
+            opts,
+            &format!(
+                "Skipped {n_skipped} file(s) in `{dir}` whose names do not have the required \
+                 form: {}",
+                *REQUIRED_FORM
+            ),
+        );
+    }
+
+    if libraries.is_empty() {
+        return Err(ErrorKind::LibraryNotFound.attach(anyhow!("Found no libraries in `{dir}`")));
+    }
+
+    Ok(libraries)
+}
+
+fn list_lints(opts: &opts::Dylint, list_opts: &opts::List, resolved: &ToolchainMap) -> Result<()> {
+    ensure!(
+        !list_opts.configs || list_opts.assert_lint_count.is_none(),
+        "`--configs` cannot be used with `--assert-lint-count`"
+    );
+
     for (toolchain, paths) in resolved {
         for path in paths {
             let driver = driver_builder::get(opts, toolchain)?;
-            let dylint_libs = serde_json::to_string(&[path])?;
             let (name, _) =
                 parse_path_filename(path).ok_or_else(|| anyhow!("Could not parse path"))?;
 
@@ -352,16 +832,37 @@ fn list_lints(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<()> {
             }
             println!();
 
-            // smoelius: `-W help` is the normal way to list lints, so we can be sure it
-            // gets the lints loaded. However, we don't actually use it to list the lints.
-            let mut command = dylint_driver(toolchain, &driver)?;
-            command
-                .envs([
-                    (env::DYLINT_LIBS, dylint_libs.as_str()),
-                    (env::DYLINT_LIST, "1"),
-                ])
-                .args(["rustc", "-W", "help"])
-                .success()?;
+            let mut command = list_lints_command(toolchain, &driver, path)?;
+            command.envs([
+                (
+                    env::DYLINT_LIST_CONFIGS,
+                    if list_opts.configs { "1" } else { "0" },
+                ),
+                (
+                    env::DYLINT_LIST_WITH_DOCS,
+                    if list_opts.with_docs { "1" } else { "0" },
+                ),
+            ]);
+
+            if let Some(expected_lint_count) = list_opts.assert_lint_count {
+                // The driver prints one line per newly registered lint. With
+                // `--with-docs`, each such line is followed by the lint's description, indented
+                // further.
+                let output = command.logged_output(true)?;
+                let stdout = String::from_utf8(output.stdout)?;
+                print!("{stdout}");
+                let lint_count = stdout
+                    .lines()
+                    .filter(|line| !line.is_empty() && !line.starts_with("        "))
+                    .count();
+                ensure!(
+                    lint_count == expected_lint_count,
+                    "`{name}` registers {lint_count} lint(s), but `--assert-lint-count \
+                     {expected_lint_count}` was used"
+                );
+            } else {
+                command.success()?;
+            }
 
             println!();
         }
@@ -370,6 +871,210 @@ fn list_lints(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<()> {
     Ok(())
 }
 
+fn list_lints_command(toolchain: &str, driver: &Path, path: &Path) -> Result<Command> {
+    let dylint_libs = serde_json::to_string(&[path])?;
+
+    // `-W help` is the normal way to list lints, so we can be sure it gets the lints
+    // loaded. However, we don't actually use it to list the lints.
+    let mut command = dylint_driver(toolchain, driver)?;
+    command
+        .envs([
+            (env::DYLINT_LIBS, dylint_libs.as_str()),
+            (env::DYLINT_LIST, "1"),
+        ])
+        .args(["rustc", "-W", "help"]);
+
+    Ok(command)
+}
+
+/// A lint registered by a library, as returned by [`library_lints`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct LintInfo {
+    /// The lint's name, in lowercase
+    pub name: String,
+    /// The lint's default level, e.g., `warn`
+    pub level: String,
+    /// The lint's description
+    pub desc: String,
+}
+
+/// Returns the lints registered by the library at `path`, which must have been built with
+/// `toolchain`.
+///
+/// The driver for `toolchain` is built if necessary, and is run once to list the lints.
+pub fn library_lints(path: &Path, toolchain: &str) -> Result<Vec<LintInfo>> {
+    let opts = opts::Dylint {
+        quiet: true,
+        ..Default::default()
+    };
+    library_lints_with_opts(&opts, path, toolchain)
+}
+
+/// A diagnostic produced by a Dylint lint, as returned by [`check_and_collect`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintDiagnostic {
+    /// The name of the lint that produced the diagnostic, in lowercase
+    pub lint_name: String,
+    /// The diagnostic's level, e.g., `warning`
+    pub level: String,
+    /// The diagnostic's message
+    pub message: String,
+    /// The file containing the diagnostic's primary span
+    pub file: String,
+    /// The line on which the diagnostic's primary span starts (1-based)
+    pub line: usize,
+    /// The byte offsets of the diagnostic's primary span within `file`
+    pub span: Range<usize>,
+    /// The toolchain of the library that produced the diagnostic
+    pub toolchain: String,
+}
+
+/// The diagnostics collected by [`check_and_collect`], along with the outcome of the check
+#[derive(Debug)]
+pub struct CollectedDiagnostics {
+    /// The diagnostics produced by the selected libraries' lints
+    pub diagnostics: Vec<LintDiagnostic>,
+    /// The check's result, which is an error if, e.g., a `deny`-level lint fired or a package
+    /// failed to compile
+    pub result: Result<()>,
+}
+
+/// Checks packages as `cargo dylint` would, and returns the diagnostics produced by the selected
+/// libraries' lints.
+///
+/// Diagnostics produced by `rustc`, Clippy, etc., i.e., by lints that the selected libraries do
+/// not register, are not returned. Rendered diagnostics are still written to standard error.
+///
+/// An error is returned only if the check could not be run, e.g., because a library could not be
+/// built. If the check itself fails, the diagnostics collected up to that point are returned, and
+/// the failure is stored in [`CollectedDiagnostics::result`].
+pub fn check_and_collect(check_opts: &opts::Check) -> Result<CollectedDiagnostics> {
+    let opts = preprocess_opts(&opts::Dylint {
+        operation: opts::Operation::Check(check_opts.clone()),
+        ..Default::default()
+    })?;
+    let opts::Operation::Check(check_opts) = &opts.operation else {
+        unreachable!();
+    };
+
+    let lib_sel = opts.library_selection();
+    ensure!(
+        !lib_sel.libs.is_empty()
+            || !lib_sel.lib_dirs.is_empty()
+            || !lib_sel.lib_paths.is_empty()
+            || lib_sel.all,
+        "No libraries were selected"
+    );
+
+    let name_toolchain_map = NameToolchainMap::new(&opts);
+    let resolved = resolve(&opts, &name_toolchain_map)?;
+
+    // As with `--summary`, the lint names are needed to distinguish diagnostics produced
+    // by Dylint lints from those produced by rustc or Clippy.
+    let lint_names = lint_names(&opts, &resolved)?;
+
+    let mut diagnostics = Vec::new();
+    let result = check_or_fix(&opts, check_opts, &resolved, None, Some(&mut diagnostics));
+
+    let diagnostics = diagnostics
+        .into_iter()
+        .filter_map(|(toolchain, diagnostic)| {
+            let lint_name = diagnostic.code.as_ref()?.code.clone();
+            if !lint_names.contains(&lint_name) {
+                return None;
+            }
+            let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+            Some((|| {
+                let level = serde_json::to_value(diagnostic.level)?
+                    .as_str()
+                    .map(ToOwned::to_owned)
+                    .ok_or_else(|| anyhow!("Could not serialize diagnostic level"))?;
+                Ok(LintDiagnostic {
+                    lint_name,
+                    level,
+                    message: diagnostic.message.clone(),
+                    file: span.file_name.clone(),
+                    line: span.line_start,
+                    span: span.byte_start as usize..span.byte_end as usize,
+                    toolchain,
+                })
+            })())
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(CollectedDiagnostics {
+        diagnostics,
+        result,
+    })
+}
+
+fn library_lints_with_opts(
+    opts: &opts::Dylint,
+    path: &Path,
+    toolchain: &str,
+) -> Result<Vec<LintInfo>> {
+    let driver = driver_builder::get(opts, toolchain)?;
+    let mut command = list_lints_command(toolchain, &driver, path)?;
+    command.envs([(env::DYLINT_LIST_JSON, "1")]);
+    let output = command.logged_output(true)?;
+    serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "Could not parse lints listed for `{}`",
+            path.to_string_lossy()
+        )
+    })
+}
+
+fn lint_names(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<BTreeSet<String>> {
+    let mut lint_names = BTreeSet::new();
+    for (toolchain, paths) in resolved {
+        for path in paths {
+            let lints = library_lints_with_opts(opts, path, toolchain)?;
+            lint_names.extend(lints.into_iter().map(|lint| lint.name));
+        }
+    }
+    Ok(lint_names)
+}
+
+// Excluded lints are validated here, rather than in the driver, so that a warning about a
+// nonexistent lint is issued once, rather than once per checked crate. Only the lints that exist
+// are passed to the driver, so that `rustc` does not also warn about them.
+fn exclude_lints(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    resolved: &ToolchainMap,
+) -> Result<Vec<String>> {
+    if check_opts.exclude_lints.is_empty() {
+        return Ok(Vec::new());
+    }
+    let lint_names = lint_names(opts, resolved)?;
+    let mut exclude_lints = Vec::new();
+    for name in &check_opts.exclude_lints {
+        let normalized = name.to_lowercase().replace('-', "_");
+        if lint_names.contains(&normalized) {
+            exclude_lints.push(normalized);
+        } else {
+            warn(
+                opts,
+                &format!("Excluded lint `{name}` is not registered by any selected library"),
+            );
+        }
+    }
+    Ok(exclude_lints)
+}
+
+fn print_driver_paths(opts: &opts::Dylint, toolchains: &[String]) -> Result<()> {
+    for toolchain in toolchains {
+        let driver = driver_builder::get(opts, toolchain)?;
+        let driver = driver
+            .canonicalize()
+            .with_context(|| format!("Could not canonicalize `{}`", driver.to_string_lossy()))?;
+        println!("{}", driver.to_string_lossy());
+    }
+
+    Ok(())
+}
+
 fn display_location(path: &Path) -> Result<String> {
     let current_dir = current_dir().with_context(|| "Could not get current directory")?;
     let Ok(path_buf) = path.canonicalize() else {
@@ -385,20 +1090,75 @@ fn display_location(path: &Path) -> Result<String> {
         .to_string())
 }
 
+// `print_sysroots` computes each sysroot the same way the driver does, i.e., from
+// `RUSTUP_HOME` and the toolchain. So the paths printed are the ones the driver would use.
+fn print_sysroots(toolchains: &[String]) -> Result<()> {
+    let rustup_home = env::var(env::RUSTUP_HOME)?;
+    for toolchain in toolchains {
+        let sysroot = Path::new(&rustup_home).join("toolchains").join(toolchain);
+        println!("{}", sysroot.to_string_lossy());
+    }
+
+    Ok(())
+}
+
 fn check_or_fix(
     opts: &opts::Dylint,
     check_opts: &opts::Check,
     resolved: &ToolchainMap,
+    mut stats: Option<&mut Stats>,
+    mut diagnostics: Option<&mut Vec<(String, Diagnostic)>>,
 ) -> Result<()> {
     let clippy_disable_docs_links = clippy_disable_docs_links()?;
 
+    let metadata = workspace_metadata(&check_opts.lib_sel)?;
+
+    // The workspace's `dylint.toml` file is read here rather than through `config::get`, whose
+    // table is global. With `--workspace-dir`, each workspace has its own file.
+    let dylint_toml = config::table_with_metadata(&metadata).error_kind(ErrorKind::Config)?;
+
+    let primary_packages = if check_opts.deps_first {
+        Some(primary_packages(check_opts, &metadata))
+    } else {
+        None
+    };
+
+    let dylint_levels =
+        serde_json::to_string(&levels(dylint_toml.as_ref()).error_kind(ErrorKind::Config)?)?;
+
+    let dylint_exclude_lints = serde_json::to_string(&exclude_lints(opts, check_opts, resolved)?)?;
+
+    let file_filter = FileFilter::from_dylint_toml(&metadata, dylint_toml.as_ref())
+        .error_kind(ErrorKind::Config)?;
+
+    let skip_generated = skip_generated(dylint_toml.as_ref()).error_kind(ErrorKind::Config)?;
+
+    // As with `--summary`, the lint names are needed to distinguish diagnostics produced
+    // by Dylint lints from those produced by rustc or Clippy.
+    let json_lines_lint_names = match check_opts.message_format.as_deref() {
+        None => None,
+        Some("json-lines") => Some(lint_names(opts, resolved)?),
+        Some(format) => bail!("Unknown message format `{format}`"),
+    };
+
+    let envs = check_opts
+        .envs
+        .iter()
+        .map(|spec| parse_env(spec))
+        .collect::<Result<Vec<_>>>()?;
+
+    let user_json_messages = message_format_is_json(&check_opts.args);
+
     let mut failures = Vec::new();
 
+    // With `--patch`, the suggestions are collected across all cargo invocations, and the
+    // patch is written once at the end.
+    let mut patch_suggestions = Vec::new();
+
     for (toolchain, paths) in resolved {
-        let target_dir = target_dir(opts, toolchain)?;
+        let target_dir = target_dir(&metadata, toolchain);
         let target_dir_str = target_dir.to_string_lossy();
         let driver = driver_builder::get(opts, toolchain)?;
-        let dylint_libs = serde_json::to_string(&paths)?;
         #[cfg(not(__library_packages))]
         let dylint_metadata = None;
         #[cfg(__library_packages)]
@@ -407,108 +1167,690 @@ fn check_or_fix(
             .map(|object: &Object| serde_json::Value::from(object.clone()))
             .unwrap_or_default()
             .to_string();
-        let description = format!("with toolchain `{toolchain}`");
-        let mut command = if check_opts.fix {
-            dylint_internal::cargo::fix(&description)
-        } else {
-            dylint_internal::cargo::check(&description)
-        }
-        .build();
-        let mut args = vec!["--target-dir", &target_dir_str];
-        if let Some(path) = &check_opts.lib_sel.manifest_path {
-            args.extend(["--manifest-path", path]);
-        }
-        for spec in &check_opts.packages {
-            args.extend(["-p", spec]);
-        }
-        if check_opts.workspace {
-            args.extend(["--workspace"]);
-        }
-        args.extend(check_opts.args.iter().map(String::as_str));
-
-        // smoelius: Set CLIPPY_DISABLE_DOCS_LINKS to prevent lints from accidentally linking to the
-        // Clippy repository. But set it to the JSON-encoded original value so that the Clippy
-        // library can unset the variable.
-        // smoelius: This doesn't work if another library is loaded alongside Clippy.
-        // smoelius: This was fixed in `clippy_utils`:
-        // https://github.com/rust-lang/rust-clippy/commit/1a206fc4abae0b57a3f393481367cf3efca23586
-        // But I am going to continue to set CLIPPY_DISABLE_DOCS_LINKS because it doesn't seem to
-        // hurt and it provides a small amount of backward compatibility.
-        command
-            .sanitize_environment()
-            .envs([
-                (
-                    env::CLIPPY_DISABLE_DOCS_LINKS,
-                    clippy_disable_docs_links.as_str(),
-                ),
-                (env::DYLINT_LIBS, &dylint_libs),
-                (env::DYLINT_METADATA, &dylint_metadata_str),
-                (
-                    env::DYLINT_NO_DEPS,
-                    if check_opts.no_deps { "1" } else { "0" },
-                ),
-                (env::RUSTC_WORKSPACE_WRAPPER, &*driver.to_string_lossy()),
-                (env::RUSTUP_TOOLCHAIN, toolchain),
-            ])
-            .args(args);
 
-        // smoelius:: See: https://github.com/rust-lang/rustup/pull/3703 and
-        // https://github.com/rust-lang/rustup/issues/3825
-        #[cfg(windows)]
-        {
-            let new_path = dylint_internal::prepend_toolchain_path(toolchain)?;
-            command.envs(vec![(crate::env::PATH, new_path)]);
-        }
+        // With `--isolate-libraries`, each library gets its own cargo invocation, so that
+        // a library that crashes does not prevent the other libraries from running.
+        let path_groups = if check_opts.isolate_libraries {
+            paths.iter().map(|path| vec![path]).collect::<Vec<_>>()
+        } else {
+            vec![paths.iter().collect()]
+        };
 
-        if let Some(stderr_path) = &opts.pipe_stderr {
-            let file = OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(stderr_path)
-                .with_context(|| format!("Failed to open `{stderr_path}` for stderr usage"))?;
-            command.stderr(file);
-        }
+        for path_group in path_groups {
+            let dylint_libs = serde_json::to_string(&path_group)?;
+            let library = if check_opts.isolate_libraries {
+                let (name, _) = parse_path_filename(path_group[0])
+                    .ok_or_else(|| anyhow!("Could not parse path"))?;
+                Some(name)
+            } else {
+                None
+            };
+            let description = if let Some(name) = &library {
+                format!("`{name}` with toolchain `{toolchain}`")
+            } else {
+                format!("with toolchain `{toolchain}`")
+            };
+            // With `--fix-noconflict` or `--patch`, `cargo check` is run rather than
+            // `cargo fix`, and Dylint applies the suggestions itself or writes them to the patch.
+            let collect_suggestions = check_opts.fix_noconflict || check_opts.patch.is_some();
+            let mut command = if check_opts.fix && !collect_suggestions {
+                dylint_internal::cargo::fix(&description)
+            } else {
+                dylint_internal::cargo::check(&description)
+            }
+            .build();
+            let mut args = vec!["--target-dir", &target_dir_str];
+            args.extend(package_args(check_opts));
+            let json_messages = stats.is_some()
+                || diagnostics.is_some()
+                || primary_packages.is_some()
+                || collect_suggestions
+                || json_lines_lint_names.is_some()
+                || file_filter.is_some();
+            if json_messages {
+                args.extend([if std::io::stderr().is_terminal() {
+                    "--message-format=json-diagnostic-rendered-ansi"
+                } else {
+                    "--message-format=json"
+                }]);
+            }
+            args.extend(check_opts.args.iter().map(String::as_str));
+
+            // smoelius: Set CLIPPY_DISABLE_DOCS_LINKS to prevent lints from accidentally linking to
+            // the Clippy repository. But set it to the JSON-encoded original value so that the
+            // Clippy library can unset the variable.
+            // smoelius: This doesn't work if another library is loaded alongside Clippy.
+            // smoelius: This was fixed in `clippy_utils`:
+            // https://github.com/rust-lang/rust-clippy/commit/1a206fc4abae0b57a3f393481367cf3efca23586
+            // But I am going to continue to set CLIPPY_DISABLE_DOCS_LINKS because it doesn't seem
+            // to hurt and it provides a small amount of backward compatibility.
+            command
+                // `sanitize_environment` deliberately leaves `RUSTFLAGS` as is. Cargo passes its
+                // flags to the driver as ordinary arguments, and the driver places Dylint's own
+                // flags around them: the `dylint_lib` cfgs, `DYLINT_CFGS`, and the `[levels]`
+                // table before, and `DYLINT_RUSTFLAGS` after. So a flag in `DYLINT_RUSTFLAGS`
+                // takes precedence over a conflicting flag in `RUSTFLAGS`.
+                .sanitize_environment()
+                // The `--env` variables are set before Dylint's own, so that the latter cannot be
+                // overridden accidentally.
+                .envs(envs.iter().filter_map(|&(key, value, other)| {
+                    other
+                        .map_or(true, |other| other == toolchain)
+                        .then_some((key, value))
+                }))
+                .envs([
+                    (
+                        env::CLIPPY_DISABLE_DOCS_LINKS,
+                        clippy_disable_docs_links.as_str(),
+                    ),
+                    (env::DYLINT_EXCLUDE_LINTS, &dylint_exclude_lints),
+                    (env::DYLINT_LEVELS, &dylint_levels),
+                    (env::DYLINT_LIBS, &dylint_libs),
+                    (env::DYLINT_METADATA, &dylint_metadata_str),
+                    (
+                        env::DYLINT_NO_DEPS,
+                        if check_opts.no_deps { "1" } else { "0" },
+                    ),
+                    (
+                        env::DYLINT_SKIP_GENERATED,
+                        if skip_generated { "1" } else { "0" },
+                    ),
+                    (env::DYLINT_TARGET_DIR, &*target_dir_str),
+                    (
+                        env::DYLINT_VERBOSE,
+                        if check_opts.verbose { "1" } else { "0" },
+                    ),
+                    (env::RUSTC_WORKSPACE_WRAPPER, &*driver.to_string_lossy()),
+                    (env::RUSTUP_TOOLCHAIN, toolchain),
+                ])
+                .args(args);
 
-        if let Some(stdout_path) = &opts.pipe_stdout {
-            let file = OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(stdout_path)
-                .with_context(|| format!("Failed to open `{stdout_path}` for stdout usage"))?;
-            command.stdout(file);
-        }
+            // The driver logs through the `log` facade, so its messages are shown only if
+            // `RUST_LOG` enables them. A user-provided `RUST_LOG` is respected.
+            if check_opts.verbose && std::env::var_os(env::RUST_LOG).is_none() {
+                command.envs([(env::RUST_LOG, "dylint_driver=info")]);
+            }
 
-        let result = command.success();
-        if result.is_err() {
-            if !check_opts.keep_going {
-                return result
-                    .with_context(|| format!("Compilation failed with toolchain `{toolchain}`"));
+            // smoelius:: See: https://github.com/rust-lang/rustup/pull/3703 and
+            // https://github.com/rust-lang/rustup/issues/3825
+            #[cfg(windows)]
+            {
+                let new_path = dylint_internal::prepend_toolchain_path(toolchain)?;
+                command.envs(vec![(crate::env::PATH, new_path)]);
+            }
+
+            if let Some(stderr_path) = &opts.pipe_stderr {
+                let file = OpenOptions::new()
+                    .append(true)
+                    .create(true)
+                    .open(stderr_path)
+                    .with_context(|| format!("Failed to open `{stderr_path}` for stderr usage"))?;
+                command.stderr(file);
+            }
+
+            if let Some(stdout_path) = &opts.pipe_stdout {
+                let file = OpenOptions::new()
+                    .append(true)
+                    .create(true)
+                    .open(stdout_path)
+                    .with_context(|| format!("Failed to open `{stdout_path}` for stdout usage"))?;
+                command.stdout(file);
+            }
+
+            let mut suggestions = Vec::new();
+            let mut toolchain_diagnostics = Vec::new();
+            let mut json_lines = json_lines_lint_names
+                .as_ref()
+                .map(|lint_names| {
+                    messages::JsonLines::new(
+                        lint_names,
+                        toolchain,
+                        primary_packages.as_ref(),
+                        opts.pipe_stdout.as_deref(),
+                    )
+                })
+                .transpose()?;
+            let result = if json_messages {
+                messages::run(
+                    &mut command,
+                    messages::Sinks {
+                        pipe_stderr: opts.pipe_stderr.as_deref(),
+                        pipe_stdout: opts.pipe_stdout.as_deref(),
+                        stats: stats.as_deref_mut(),
+                        diagnostics: diagnostics.is_some().then_some(&mut toolchain_diagnostics),
+                        primary_packages: primary_packages.as_ref(),
+                        suggestions: collect_suggestions.then_some(&mut suggestions),
+                        json_lines: json_lines.as_mut(),
+                        file_filter: file_filter.as_ref(),
+                    },
+                )
+            } else if user_json_messages {
+                messages::run_tagged(&mut command, opts.pipe_stdout.as_deref(), toolchain)
+            } else {
+                command.success()
             };
-            failures.push(toolchain);
+            if let Some(diagnostics) = diagnostics.as_deref_mut() {
+                diagnostics.extend(
+                    toolchain_diagnostics
+                        .into_iter()
+                        .map(|diagnostic| (toolchain.clone(), diagnostic)),
+                );
+            }
+            let result = result.and_then(|()| {
+                if check_opts.patch.is_some() {
+                    patch_suggestions.extend(suggestions);
+                    Ok(())
+                } else if check_opts.fix_noconflict {
+                    apply_suggestions(opts, check_opts, &metadata, suggestions)
+                } else {
+                    Ok(())
+                }
+            });
+            if result.is_err() {
+                if !check_opts.keep_going {
+                    return result.with_context(|| {
+                        if let Some(name) = &library {
+                            format!("Compilation failed for `{name}` with toolchain `{toolchain}`")
+                        } else {
+                            format!("Compilation failed with toolchain `{toolchain}`")
+                        }
+                    });
+                };
+                failures.push(if let Some(name) = &library {
+                    format!("{name}@{toolchain}")
+                } else {
+                    toolchain.clone()
+                });
+            }
         }
     }
 
+    if let Some(path) = &check_opts.patch {
+        write_patch(opts, &metadata, path, patch_suggestions)?;
+    }
+
     if failures.is_empty() {
         Ok(())
     } else {
-        Err(anyhow!(
-            "Compilation failed with the following toolchains: {:?}",
+        Err(ErrorKind::CompilationFailed.attach(anyhow!(
+            "Compilation failed with the following {}: {:?}",
+            if check_opts.isolate_libraries {
+                "libraries"
+            } else {
+                "toolchains"
+            },
             failures
-        ))
+        )))
+    }
+}
+
+/// Returns the arguments that select the packages and features to check
+fn package_args(check_opts: &opts::Check) -> Vec<&str> {
+    let mut args = Vec::new();
+    if let Some(path) = &check_opts.lib_sel.manifest_path {
+        args.extend(["--manifest-path", path]);
+    }
+    for spec in &check_opts.packages {
+        args.extend(["-p", spec]);
+    }
+    if check_opts.workspace {
+        args.extend(["--workspace"]);
+    }
+    for features in &check_opts.features {
+        args.extend(["--features", features]);
+    }
+    if check_opts.all_features {
+        args.extend(["--all-features"]);
+    }
+    if check_opts.no_default_features {
+        args.extend(["--no-default-features"]);
+    }
+    args
+}
+
+// Cargo accepts both `--message-format=FMT` and `--message-format FMT`, and `FMT` can be
+// a comma-separated list, e.g., `json,json-diagnostic-short`.
+fn message_format_is_json(args: &[String]) -> bool {
+    args.iter().enumerate().any(|(i, arg)| {
+        let format = if arg == "--message-format" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix("--message-format=")
+        };
+        format.is_some_and(|format| format.split(',').any(|format| format.starts_with("json")))
+    })
+}
+
+// `check_config` runs the driver once per library, as `list_lints` does. The driver has
+// no source file from which to find the workspace, so the `dylint.toml` file's contents are passed
+// in `DYLINT_TOML`.
+fn check_config(
+    opts: &opts::Dylint,
+    check_config_opts: &opts::CheckConfig,
+    resolved: &ToolchainMap,
+) -> Result<()> {
+    let metadata = workspace_metadata(&check_config_opts.lib_sel)?;
+    let dylint_toml = metadata.workspace_root.join("dylint.toml");
+    if !dylint_toml
+        .try_exists()
+        .with_context(|| format!("`try_exists` failed for `{dylint_toml}`"))?
+    {
+        warn(opts, &format!("Could not find `{dylint_toml}`"));
+        return Ok(());
+    }
+    let value = read_to_string(&dylint_toml)
+        .with_context(|| format!("`read_to_string` failed for `{dylint_toml}`"))?;
+
+    let mut invalid = Vec::new();
+
+    for (toolchain, paths) in resolved {
+        for path in paths {
+            let driver = driver_builder::get(opts, toolchain)?;
+            let (name, _) =
+                parse_path_filename(path).ok_or_else(|| anyhow!("Could not parse path"))?;
+
+            print!("{name}");
+            if resolved.keys().len() >= 2 {
+                print!("@{toolchain}");
+            }
+            if paths.len() >= 2 {
+                let location = display_location(path)?;
+                print!(" ({location})");
+            }
+            println!();
+
+            let dylint_libs = serde_json::to_string(&[path])?;
+            let mut command = dylint_driver(toolchain, &driver)?;
+            command
+                .envs([
+                    (env::DYLINT_LIBS, dylint_libs.as_str()),
+                    (env::DYLINT_TOML, value.as_str()),
+                    (env::DYLINT_VALIDATE_CONFIG, "1"),
+                ])
+                .args(["rustc", "-W", "help"]);
+            if command.success().is_err() {
+                invalid.push(name);
+            }
+
+            println!();
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(ErrorKind::Config.attach(anyhow!(
+            "`{dylint_toml}` is invalid for: {}",
+            invalid.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+// `check_file` runs the driver on a single file, much as `dylint_testing` does, without
+// involving Cargo.
+fn check_file(
+    opts: &opts::Dylint,
+    check_file_opts: &opts::CheckFile,
+    resolved: &ToolchainMap,
+) -> Result<()> {
+    let tempdir = tempdir().with_context(|| "`tempdir` failed")?;
+    let out_dir = tempdir.path().to_string_lossy();
+
+    for (toolchain, paths) in resolved {
+        let driver = driver_builder::get(opts, toolchain)?;
+        let dylint_libs = serde_json::to_string(&paths)?;
+        let mut command = dylint_driver(toolchain, &driver)?;
+        command
+            .envs([(env::DYLINT_LIBS, dylint_libs.as_str())])
+            .args([
+                "rustc",
+                &check_file_opts.path,
+                "--emit=metadata",
+                "--out-dir",
+                &out_dir,
+            ])
+            .args(&check_file_opts.args);
+        command
+            .success()
+            .with_context(|| {
+                format!(
+                    "Checking `{}` failed with toolchain `{toolchain}`",
+                    check_file_opts.path
+                )
+            })
+            .error_kind(ErrorKind::CompilationFailed)?;
+    }
+
+    Ok(())
+}
+
+fn apply_suggestions(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    metadata: &Metadata,
+    suggestions: Vec<fix_noconflict::Suggestion>,
+) -> Result<()> {
+    let workspace_root = metadata.workspace_root.as_std_path();
+    // The suggestions are cloned so that, with `--allow-downgrade`, each lint's
+    // suggestions can be reapplied on their own.
+    let (mut fixes, mut n_skipped, snapshot) =
+        fix_noconflict::apply(workspace_root, suggestions.clone())?;
+    if check_opts.allow_downgrade
+        && !fixes.is_empty()
+        && check_compiles(check_opts, "that the fixed code compiles").is_err()
+    {
+        fix_noconflict::restore(workspace_root, &snapshot)?;
+        let culprits = culprits(check_opts, workspace_root, &fixes, &suggestions)?
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<BTreeSet<_>>();
+        ensure!(
+            !culprits.is_empty(),
+            "`cargo check` failed after applying suggestions, so the files to which they were \
+             applied were restored. No lint's suggestions cause `cargo check` to fail on their \
+             own; the failure results from suggestions for different lints interacting."
+        );
+        warn(
+            opts,
+            &format!(
+                "The suggestions for the following lints cause `cargo check` to fail, so they \
+                 were not applied: {}",
+                culprits
+                    .iter()
+                    .map(|lint| format!("`{lint}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+        // The other lints' suggestions are reapplied. They could still fail to compile together,
+        // in which case they are restored as well.
+        let snapshot;
+        (fixes, n_skipped, snapshot) = fix_noconflict::apply(
+            workspace_root,
+            suggestions
+                .into_iter()
+                .filter(|suggestion| !culprits.contains(suggestion.lint()))
+                .collect(),
+        )?;
+        if !fixes.is_empty()
+            && check_compiles(check_opts, "that the remaining fixed code compiles").is_err()
+        {
+            fix_noconflict::restore(workspace_root, &snapshot)?;
+            bail!(
+                "`cargo check` failed after applying the suggestions for the remaining lints, so \
+                 the files to which they were applied were restored"
+            );
+        }
+    }
+    if !opts.quiet {
+        for line in fix_noconflict::summary(&fixes) {
+            eprintln!("{line}");
+        }
+    }
+    if n_skipped != 0 {
+        let n_applied = fixes.values().map(|fixes| fixes.n_applied).sum::<usize>();
+        warn(
+            opts,
+            &format!(
+                "Applied {n_applied} suggestion(s); skipped {n_skipped} overlapping \
+                 suggestion(s), which a subsequent run may apply"
+            ),
+        );
     }
+    if check_opts.fmt_after_fix {
+        let files = fixes
+            .values()
+            .flat_map(|fixes| &fixes.files)
+            .map(|file| metadata.workspace_root.as_std_path().join(file))
+            .collect::<BTreeSet<_>>();
+        format_files(opts, metadata, &files)?;
+    }
+    Ok(())
+}
+
+// `check_compiles` runs an ordinary `cargo check`, i.e., one that does not involve the
+// driver, on the packages being checked.
+fn check_compiles(check_opts: &opts::Check, description: &str) -> Result<()> {
+    let mut command = dylint_internal::cargo::check(description).build();
+    command
+        .sanitize_environment()
+        .args(package_args(check_opts))
+        .args(&check_opts.args);
+    command.success()
+}
+
+// To determine which lints' suggestions cause `cargo check` to fail, each lint's
+// suggestions are applied on their own, checked, and then reverted. This is slow, but it happens
+// only when `cargo check` fails with all of the suggestions applied.
+fn culprits<'a>(
+    check_opts: &opts::Check,
+    workspace_root: &Path,
+    fixes: &'a BTreeMap<String, fix_noconflict::Fixes>,
+    suggestions: &[fix_noconflict::Suggestion],
+) -> Result<Vec<&'a str>> {
+    let mut culprits = Vec::new();
+    for lint in fixes.keys() {
+        let (_, _, snapshot) = fix_noconflict::apply(
+            workspace_root,
+            suggestions
+                .iter()
+                .filter(|suggestion| suggestion.lint() == lint)
+                .cloned()
+                .collect(),
+        )?;
+        let result = check_compiles(
+            check_opts,
+            &format!("that the suggestions for `{lint}` compile"),
+        );
+        fix_noconflict::restore(workspace_root, &snapshot)?;
+        if result.is_err() {
+            culprits.push(lint.as_str());
+        }
+    }
+    Ok(culprits)
+}
+
+// Unlike `cargo fmt`, `rustfmt` does not read a package's edition from its manifest. So
+// each file is formatted with the edition of the package whose directory contains it.
+//
+// Each file is passed to `rustfmt` on standard input. Given a path, `rustfmt` would also format
+// the file's out-of-line modules, which Dylint may not have modified. `rustfmt` is run from the
+// file's directory so that it finds the same `rustfmt.toml` it would otherwise.
+fn format_files(opts: &opts::Dylint, metadata: &Metadata, files: &BTreeSet<PathBuf>) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    if Command::new("rustfmt")
+        .arg("--version")
+        .logged_output(true)
+        .is_err()
+    {
+        warn(
+            opts,
+            "`rustfmt` is not installed; the files to which suggestions were applied were not \
+             formatted",
+        );
+        return Ok(());
+    }
+
+    for file in files {
+        let edition = metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|package| {
+                package
+                    .manifest_path
+                    .parent()
+                    .is_some_and(|dir| file.starts_with(dir))
+            })
+            .max_by_key(|package| package.manifest_path.as_str().len())
+            .map(|package| package.edition.as_str());
+        format_file(edition, file)?;
+    }
+
+    Ok(())
+}
+
+fn format_file(edition: Option<&str>, file: &Path) -> Result<()> {
+    let contents =
+        read_to_string(file).with_context(|| format!("`read_to_string` failed for {file:?}"))?;
+
+    let mut command = Command::new("rustfmt");
+    if let Some(edition) = edition {
+        command.args(["--edition", edition]);
+    }
+    if let Some(parent) = file.parent() {
+        command.current_dir(parent);
+    }
+    let mut child = command
+        .args(["--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not spawn `rustfmt` for {file:?}"))?;
+
+    // Writing happens on a separate thread so that `rustfmt` cannot block on a full stdout pipe
+    // while Dylint is blocked writing to its stdin.
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Could not get `rustfmt`'s stdin"))?;
+    let writer = std::thread::spawn(move || stdin.write_all(contents.as_bytes()));
+    let output = child.wait_with_output()?;
+    writer
+        .join()
+        .map_err(|_| anyhow!("Writing to `rustfmt`'s stdin panicked"))??;
+
+    ensure!(
+        output.status.success(),
+        "`rustfmt` failed for {file:?}: {}",
+        String::from_utf8_lossy(&output.stderr).trim_end()
+    );
+
+    write(file, output.stdout).with_context(|| format!("`write` failed for {file:?}"))
+}
+
+fn write_patch(
+    opts: &opts::Dylint,
+    metadata: &Metadata,
+    path: &str,
+    suggestions: Vec<fix_noconflict::Suggestion>,
+) -> Result<()> {
+    let (patch, n_skipped) =
+        fix_noconflict::patch(metadata.workspace_root.as_std_path(), suggestions)?;
+    write(path, patch).with_context(|| format!("`write` failed for `{path}`"))?;
+    if n_skipped != 0 {
+        warn(
+            opts,
+            &format!(
+                "Skipped {n_skipped} overlapping suggestion(s), which are not included in \
+                 `{path}`"
+            ),
+        );
+    }
+    Ok(())
 }
 
-fn target_dir(opts: &opts::Dylint, toolchain: &str) -> Result<PathBuf> {
+// Cargo sets `CARGO_PRIMARY_PACKAGE` for the packages selected on its command line, and
+// the driver uses that variable to distinguish primary packages from dependencies. The variable is
+// not visible in Cargo's JSON messages, so approximate Cargo's package selection here.
+fn primary_packages(check_opts: &opts::Check, metadata: &Metadata) -> BTreeSet<PackageId> {
+    let packages = if !check_opts.packages.is_empty() {
+        metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|package| {
+                check_opts
+                    .packages
+                    .iter()
+                    .any(|spec| spec.split('@').next() == Some(package.name.as_str()))
+            })
+            .collect()
+    } else if check_opts.workspace {
+        metadata.workspace_packages()
+    } else {
+        metadata
+            .root_package()
+            .map_or_else(|| metadata.workspace_packages(), |package| vec![package])
+    };
+    packages
+        .into_iter()
+        .map(|package| package.id.clone())
+        .collect()
+}
+
+// The levels themselves (e.g., `deny`) are validated by the driver.
+fn levels(dylint_toml: Option<&toml::Table>) -> Result<BTreeMap<String, String>> {
+    let Some(value) = dylint_toml.and_then(|table| table.get("levels")) else {
+        return Ok(BTreeMap::new());
+    };
+    let table = value
+        .as_table()
+        .ok_or_else(|| anyhow!("`levels` value must be a table"))?;
+    table
+        .iter()
+        .map(|(name, level)| {
+            let level = level
+                .as_str()
+                .ok_or_else(|| anyhow!("Level for lint `{name}` must be a string"))?;
+            Ok((name.clone(), level.to_owned()))
+        })
+        .collect()
+}
+
+// Skipping generated files can be enabled with the `DYLINT_SKIP_GENERATED` environment
+// variable or with the `skip_generated` key in the workspace's `dylint.toml` file. The skipping
+// itself is done by the driver, which compares each diagnostic's file against `OUT_DIR` and
+// `DYLINT_TARGET_DIR`.
+fn skip_generated(dylint_toml: Option<&toml::Table>) -> Result<bool> {
+    if env::enabled(env::DYLINT_SKIP_GENERATED) {
+        return Ok(true);
+    }
+    let Some(value) = dylint_toml.and_then(|table| table.get("skip_generated")) else {
+        return Ok(false);
+    };
+    value
+        .as_bool()
+        .ok_or_else(|| anyhow!("`skip_generated` value must be a boolean"))
+}
+
+// The metadata of the workspace being checked is computed once per check and passed to the
+// functions that need it, rather than each running `cargo metadata` itself.
+fn workspace_metadata(lib_sel: &opts::LibrarySelection) -> Result<Metadata> {
     let mut command = MetadataCommand::new();
-    if let Some(path) = &opts.library_selection().manifest_path {
+    if let Some(path) = &lib_sel.manifest_path {
         command.manifest_path(path);
     }
-    let metadata = command.no_deps().exec()?;
-    Ok(metadata
+    command.no_deps().exec().map_err(Into::into)
+}
+
+fn target_dir(metadata: &Metadata, toolchain: &str) -> PathBuf {
+    metadata
         .target_directory
         .join("dylint/target")
         .join(toolchain)
-        .into())
+        .into()
+}
+
+/// Parses a `--env` argument of the form `KEY=VALUE[@TOOLCHAIN]` into a tuple of (key, value,
+/// toolchain). The text after the last `@`, if any, is the toolchain. An empty toolchain, i.e., a
+/// trailing `@`, means every toolchain.
+fn parse_env(spec: &str) -> Result<(&str, &str, Option<&str>)> {
+    let (key, value) = spec
+        .split_once('=')
+        .ok_or_else(|| anyhow!("`--env {spec}` is not of the form `KEY=VALUE[@TOOLCHAIN]`"))?;
+    ensure!(!key.is_empty(), "`--env {spec}` has an empty key");
+    let (value, toolchain) = value
+        .rsplit_once('@')
+        .map_or((value, None), |(value, toolchain)| {
+            (
+                value,
+                Some(toolchain).filter(|toolchain| !toolchain.is_empty()),
+            )
+        });
+    Ok((key, value, toolchain))
 }
 
 fn clippy_disable_docs_links() -> Result<String> {
@@ -576,6 +1918,164 @@ mod test {
         run_with_name_toolchain_map(&opts, &name_toolchain_map).unwrap();
     }
 
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn lib_with_toolchain() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let name_toolchain_map = name_toolchain_map();
+
+        let inited = name_toolchain_map.get_or_try_init().unwrap();
+
+        let straggler = inited.get("straggler").unwrap();
+        let toolchain = straggler.keys().next().unwrap();
+
+        let (other, _) = name_as_lib(&name_toolchain_map, &format!("straggler@{toolchain}"), true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(toolchain, &other);
+
+        let error = name_as_lib(&name_toolchain_map, "straggler@nonexistent", true).unwrap_err();
+        let message = error.to_string();
+        assert!(
+            message
+                .starts_with("Found no library matching `straggler` for toolchain `nonexistent`"),
+            "{message}"
+        );
+
+        // Attaching the error kind must not change how the error is displayed.
+        let error = DylintError::new(error.context("Could not resolve libraries"));
+        assert!(
+            matches!(error, DylintError::LibraryNotFound(_)),
+            "{error:?}"
+        );
+        assert_eq!(
+            format!("Could not resolve libraries: {message}"),
+            format!("{error:#}")
+        );
+    }
+
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn lib_in_library_path() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let tempdir = tempdir().unwrap();
+        let path = tempdir
+            .path()
+            .join(library_filename("foo", "nightly-2024-11-28"));
+        write(&path, "").unwrap();
+
+        let dylint_library_path = std::env::var_os(env::DYLINT_LIBRARY_PATH);
+        std::env::set_var(env::DYLINT_LIBRARY_PATH, tempdir.path());
+
+        let name_toolchain_map = name_toolchain_map();
+
+        let result = name_as_lib(&name_toolchain_map, "foo", true);
+
+        if let Some(dylint_library_path) = dylint_library_path {
+            std::env::set_var(env::DYLINT_LIBRARY_PATH, dylint_library_path);
+        } else {
+            std::env::remove_var(env::DYLINT_LIBRARY_PATH);
+        }
+
+        let (toolchain, maybe_library) = result.unwrap().unwrap();
+        assert_eq!("nightly-2024-11-28", toolchain);
+        assert_eq!(path, maybe_library.path());
+    }
+
+    #[test]
+    fn library_filename_round_trip() {
+        for (name, toolchain) in [
+            ("foo", "stable-x86_64-unknown-linux-gnu"),
+            ("foo_bar", "nightly-2024-11-28-x86_64-apple-darwin"),
+            ("foo", "nightly-2024-11-28-x86_64-pc-windows-msvc"),
+        ] {
+            let filename = library_filename(name, toolchain);
+            assert_eq!(
+                Some((name.to_owned(), toolchain.to_owned())),
+                parse_library_filename(Path::new(&filename))
+            );
+        }
+    }
+
+    #[test]
+    fn message_format_is_json() {
+        for (args, expected) in [
+            (&["--message-format=json"][..], true),
+            (&["--message-format", "json-diagnostic-short"][..], true),
+            (
+                &["--message-format=short,json-render-diagnostics"][..],
+                true,
+            ),
+            (&["--message-format=human"][..], false),
+            (&["--message-format"][..], false),
+            (&["--all-targets"][..], false),
+        ] {
+            let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(expected, super::message_format_is_json(&args), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn parse_env() {
+        assert_eq!(
+            ("KEY", "VALUE", None),
+            super::parse_env("KEY=VALUE").unwrap()
+        );
+        assert_eq!(
+            ("KEY", "VALUE", Some("nightly-2024-01-01")),
+            super::parse_env("KEY=VALUE@nightly-2024-01-01").unwrap()
+        );
+        assert_eq!(
+            ("KEY", "user@example.com", Some("stable")),
+            super::parse_env("KEY=user@example.com@stable").unwrap()
+        );
+        assert_eq!(
+            ("KEY", "user@example.com", None),
+            super::parse_env("KEY=user@example.com@").unwrap()
+        );
+        assert_eq!(("KEY", "", None), super::parse_env("KEY=").unwrap());
+        assert!(super::parse_env("KEY").is_err());
+        assert!(super::parse_env("=VALUE").is_err());
+    }
+
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn require_single_toolchain() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let name_toolchain_map = name_toolchain_map();
+
+        let opts = opts::Dylint {
+            operation: opts::Operation::Check(opts::Check {
+                lib_sel: opts::LibrarySelection {
+                    libs: vec![
+                        "question_mark_in_expression".to_owned(),
+                        "straggler".to_owned(),
+                    ],
+                    require_single_toolchain: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let error = run_with_name_toolchain_map(&opts, &name_toolchain_map).unwrap_err();
+
+        let message = error.to_string();
+        assert!(
+            message.starts_with("`--require-single-toolchain` was used"),
+            "{message}"
+        );
+        assert!(
+            message.contains("question_mark_in_expression@"),
+            "{message}"
+        );
+        assert!(message.contains("straggler@"), "{message}");
+    }
+
     // smoelius: Check that loading multiple libraries with the same Rust toolchain works. At one
     // point, I was getting this error from `libloading`:
     //
@@ -627,4 +2127,128 @@ mod test {
 
         run_with_name_toolchain_map(&opts, &name_toolchain_map).unwrap();
     }
+
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn library_lints() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let name_toolchain_map = name_toolchain_map();
+
+        let (toolchain, maybe_library) =
+            name_as_lib(&name_toolchain_map, "question_mark_in_expression", true)
+                .unwrap()
+                .unwrap();
+        let path = maybe_library.build(&OPTS).unwrap();
+
+        let lints = super::library_lints(&path, &toolchain).unwrap();
+
+        assert_eq!(
+            lints,
+            vec![LintInfo {
+                name: "question_mark_in_expression".to_owned(),
+                level: "warn".to_owned(),
+                desc: "`?` operators embedded within an expression".to_owned(),
+            }]
+        );
+    }
+
+    // The driver should refuse to load a library whose filename names a toolchain other than the
+    // one the driver was built with.
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn library_lints_toolchain_mismatch() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let name_toolchain_map = name_toolchain_map();
+
+        let (toolchain, maybe_library) =
+            name_as_lib(&name_toolchain_map, "question_mark_in_expression", true)
+                .unwrap()
+                .unwrap();
+        let path = maybe_library.build(&OPTS).unwrap();
+
+        let tempdir = tempdir().unwrap();
+        let other_path = tempdir.path().join(library_filename(
+            "question_mark_in_expression",
+            "nightly-1970-01-01",
+        ));
+        std::fs::copy(path, &other_path).unwrap();
+
+        let error = super::library_lints(&other_path, &toolchain).unwrap_err();
+
+        assert!(
+            error.to_string().contains(&format!(
+                "was built with toolchain `nightly-1970-01-01`, but the driver uses toolchain \
+                 `{toolchain}`"
+            )),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn check_and_collect() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let collected = collect_question_mark_in_expression("warn");
+
+        collected.result.unwrap();
+
+        // The `unused_variables` warning is produced by rustc and so is not returned.
+        assert_eq!(
+            collected.diagnostics,
+            vec![question_mark_in_expression_diagnostic(
+                "warning",
+                3,
+                91..129
+            )]
+        );
+    }
+
+    #[test]
+    fn check_and_collect_deny() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let collected = collect_question_mark_in_expression("deny");
+
+        // The check fails, but the diagnostic that caused the failure is still returned.
+        assert!(collected.result.is_err());
+        assert_eq!(
+            collected.diagnostics,
+            vec![question_mark_in_expression_diagnostic("error", 5, 130..168)]
+        );
+    }
+
+    fn collect_question_mark_in_expression(fixture: &str) -> CollectedDiagnostics {
+        super::check_and_collect(&opts::Check {
+            lib_sel: opts::LibrarySelection {
+                manifest_path: Some(format!(
+                    "../fixtures/check_and_collect/{fixture}/Cargo.toml"
+                )),
+                paths: vec!["../examples/restriction/question_mark_in_expression".to_owned()],
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn question_mark_in_expression_diagnostic(
+        level: &str,
+        line: usize,
+        span: Range<usize>,
+    ) -> LintDiagnostic {
+        LintDiagnostic {
+            lint_name: "question_mark_in_expression".to_owned(),
+            level: level.to_owned(),
+            message: "using the `?` operator within an expression".to_owned(),
+            file: "src/lib.rs".to_owned(),
+            line,
+            span,
+            toolchain: dylint_internal::rustup::active_toolchain(Path::new(
+                "../examples/restriction/question_mark_in_expression",
+            ))
+            .unwrap(),
+        }
+    }
 }
@@ -74,6 +74,10 @@ impl PackageId {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub const fn version(&self) -> &Version {
+        &self.version
+    }
 }
 
 pub fn dependency_source_id_and_root(
@@ -7,7 +7,12 @@ use glob::glob;
 use if_chain::if_chain;
 use once_cell::sync::OnceCell;
 use serde::{de::IntoDeserializer, Deserialize};
-use std::path::{Path, PathBuf};
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::Duration,
+};
 
 // smoelius: If both `__cargo_cli` and `__cargo_lib` are enabled, assume the user built
 // `cargo-dylint` with `--features=cargo-lib` and forgot `--no-default-features`.
@@ -23,6 +28,8 @@ use impl_::{dependency_source_id_and_root, GlobalContext, PackageId, SourceId};
 
 type Object = serde_json::Map<String, serde_json::Value>;
 
+const DYLINT_LOCK: &str = "dylint.lock";
+
 #[derive(Clone, Debug)]
 pub struct Package {
     metadata: &'static Metadata,
@@ -81,6 +88,20 @@ struct Library {
     details: TomlDetailedDependency,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DylintLock {
+    #[serde(default)]
+    libraries: Vec<PinnedLibrary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PinnedLibrary {
+    version: String,
+    #[serde(flatten)]
+    library: Library,
+}
+
 pub fn from_opts(opts: &opts::Dylint) -> Result<Vec<Package>> {
     let lib_sel = opts.library_selection();
 
@@ -265,6 +286,85 @@ fn library_packages_from_dylint_toml(
     Ok(libraries.into_iter().flatten().collect())
 }
 
+pub fn from_dylint_lock(opts: &opts::Dylint) -> Result<Vec<Package>> {
+    let Some(metadata) = cargo_metadata(opts)? else {
+        return Ok(vec![]);
+    };
+
+    let path = metadata
+        .workspace_root
+        .join(DYLINT_LOCK)
+        .into_std_path_buf();
+
+    if !path
+        .try_exists()
+        .with_context(|| format!("Could not determine whether {path:?} exists"))?
+    {
+        return Ok(vec![]);
+    }
+
+    let contents =
+        read_to_string(&path).with_context(|| format!("`read_to_string` failed for {path:?}"))?;
+    let dylint_lock = toml::from_str::<DylintLock>(&contents)
+        .with_context(|| format!("Could not parse {path:?}"))?;
+
+    library_packages_from_dylint_lock(opts, metadata, &dylint_lock.libraries)
+}
+
+// Because each git entry in `dylint.lock` names a full commit hash, Cargo can reuse an
+// existing checkout of that commit rather than fetching. In particular, Cargo's offline setting
+// (e.g., `CARGO_NET_OFFLINE=true`) is respected, and pinned libraries that were fetched previously
+// can be built without network access.
+fn library_packages_from_dylint_lock(
+    opts: &opts::Dylint,
+    metadata: &'static Metadata,
+    libraries: &[PinnedLibrary],
+) -> Result<Vec<Package>> {
+    let gctx = GlobalContext::default()?;
+
+    let packages = libraries
+        .iter()
+        .map(|pinned| {
+            ensure_pinned(&pinned.library)?;
+            let packages = library_package(opts, metadata, &gctx, &pinned.library)?;
+            for package in &packages {
+                let version = package.id.version().to_string();
+                ensure!(
+                    version == pinned.version,
+                    "`{}` has version {version}, but `{DYLINT_LOCK}` pins version {}",
+                    package.lib_name,
+                    pinned.version
+                );
+            }
+            Ok(packages)
+        })
+        .collect::<Result<Vec<_>>>()
+        .with_context(|| format!("Could not build `{DYLINT_LOCK}` entries"))?;
+
+    Ok(packages.into_iter().flatten().collect())
+}
+
+fn ensure_pinned(library: &Library) -> Result<()> {
+    let details = &library.details;
+
+    let Some(url) = &details.git else {
+        return Ok(());
+    };
+
+    ensure!(
+        details.branch.is_none() && details.tag.is_none(),
+        "`{url}` must be pinned with `rev`, not `branch` or `tag`"
+    );
+
+    let rev = details.rev.as_deref().unwrap_or_default();
+    ensure!(
+        rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit()),
+        "`{url}` must be pinned to a full commit hash with `rev`"
+    );
+
+    Ok(())
+}
+
 fn library_packages(
     opts: &opts::Dylint,
     metadata: &'static Metadata,
@@ -292,7 +392,7 @@ fn library_package(
     // smoelius: The dependency root cannot be canonicalized here. It could contain a `glob` pattern
     // (e.g., `*`), because Dylint allows `path` entries to contain `glob` patterns.
     let (source_id, dependency_root) =
-        dependency_source_id_and_root(opts, metadata, gctx, details)?;
+        dependency_source_id_and_root_with_retries(opts, metadata, gctx, details)?;
 
     let patterns = if let Some(StringOrVec(patterns)) = &library.pattern {
         patterns.clone()
@@ -381,6 +481,64 @@ fn library_package(
     Ok(packages.into_iter().flatten().collect())
 }
 
+const INITIAL_GIT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+fn dependency_source_id_and_root_with_retries(
+    opts: &opts::Dylint,
+    metadata: &'static Metadata,
+    gctx: &GlobalContext,
+    details: &TomlDetailedDependency,
+) -> Result<(SourceId, PathBuf)> {
+    let Some(url) = &details.git else {
+        return dependency_source_id_and_root(opts, metadata, gctx, details);
+    };
+
+    let git_retries = opts.library_selection().git_retries;
+    let mut delay = INITIAL_GIT_RETRY_DELAY;
+    let mut attempt = 0;
+
+    loop {
+        match dependency_source_id_and_root(opts, metadata, gctx, details) {
+            Err(error) if attempt < git_retries && is_network_error(&error) => {
+                attempt += 1;
+                warn(
+                    opts,
+                    &format!(
+                        "Fetching `{url}` failed due to a network error; retrying in {}s \
+                         (attempt {attempt} of {git_retries}): {error}",
+                        delay.as_secs()
+                    ),
+                );
+                sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+}
+
+// Neither Cargo's CLI nor its library exposes a way to classify errors. So look for
+// messages that indicate network problems. Errors that merely mention fetching (e.g., a nonexistent
+// revision) are not retried.
+fn is_network_error(error: &anyhow::Error) -> bool {
+    const NETWORK_ERROR_MESSAGES: &[&str] = &[
+        "could not resolve host",
+        "connection refused",
+        "connection reset",
+        "failed to connect",
+        "network failure",
+        "spurious network error",
+        "ssl connect error",
+        "timed out",
+    ];
+
+    let message = format!("{error:?}").to_lowercase();
+
+    NETWORK_ERROR_MESSAGES
+        .iter()
+        .any(|network_error_message| message.contains(network_error_message))
+}
+
 fn toml_detailed_dependency(library: &Library) -> Result<&TomlDetailedDependency> {
     let mut unused_keys = library
         .details
@@ -449,16 +607,27 @@ pub fn build_library(opts: &opts::Dylint, package: &Package) -> Result<PathBuf>
     let path = package.path();
 
     if !opts.library_selection().no_build {
+        let mut command = dylint_internal::cargo::build(&format!(
+            "workspace metadata entry `{}`",
+            package.id.name()
+        ))
+        .quiet(opts.quiet)
+        .build();
         // smoelius: Clear `RUSTFLAGS` so that changes to it do not cause workspace metadata entries
         // to be rebuilt.
-        dylint_internal::cargo::build(&format!("workspace metadata entry `{}`", package.id.name()))
-            .quiet(opts.quiet)
-            .build()
+        command
             .sanitize_environment()
             .env_remove(env::RUSTFLAGS)
             .current_dir(&package.root)
-            .args(["--release", "--target-dir", &target_dir.to_string_lossy()])
-            .success()?;
+            .args(["--release", "--target-dir", &target_dir.to_string_lossy()]);
+        // Let `rustc` do the stripping rather than running a `strip` tool on the built
+        // library. This works on every platform `rustc` supports (it is effectively a no-op where
+        // debug info is stored separately, e.g., on Windows), and it leaves the dynamic symbol
+        // table, and hence `dylint_version`, intact.
+        if opts.library_selection().strip {
+            command.args(["--config", "profile.release.strip=\"debuginfo\""]);
+        }
+        command.success()?;
 
         let exists = path
             .try_exists()
@@ -0,0 +1,301 @@
+use crate::{
+    file_filter::FileFilter,
+    fix_noconflict::{self, Suggestion},
+    stats::Stats,
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use cargo_metadata::{diagnostic::Diagnostic, CompilerMessage, Message, PackageId};
+use std::{
+    collections::BTreeSet,
+    fs::OpenOptions,
+    io::{stderr, stdout, BufRead, BufReader, Write},
+    process::{Command, Stdio},
+};
+
+/// Writes the messages for diagnostics produced by Dylint lints as they arrive, one JSON object per
+/// line
+pub struct JsonLines<'a> {
+    lint_names: &'a BTreeSet<String>,
+    toolchain: &'a str,
+    primary_packages: Option<&'a BTreeSet<PackageId>>,
+    held_back: Vec<String>,
+    sink: Box<dyn Write>,
+}
+
+impl<'a> JsonLines<'a> {
+    /// Creates a `JsonLines` that writes to `pipe_stdout`, if provided, and to stdout otherwise.
+    /// `lint_names` are the names of the lints registered by the libraries being run with
+    /// `toolchain`.
+    ///
+    /// If `primary_packages` is provided, a `dylint_primary` field is added to each message, and
+    /// the messages for those packages are held back until [`JsonLines::finish`] is called.
+    pub fn new(
+        lint_names: &'a BTreeSet<String>,
+        toolchain: &'a str,
+        primary_packages: Option<&'a BTreeSet<PackageId>>,
+        pipe_stdout: Option<&str>,
+    ) -> Result<Self> {
+        Ok(Self {
+            lint_names,
+            toolchain,
+            primary_packages,
+            held_back: Vec::new(),
+            sink: sink(pipe_stdout, "stdout", stdout())?,
+        })
+    }
+
+    // `CompilerMessage` does not include the `reason` field that Cargo writes, so it is
+    // added back. As with `run_tagged`, a `dylint_toolchain` field is added as well. Each line is
+    // written with a single call, and stdout is line buffered, so a consumer sees each message as
+    // soon as it is written.
+    fn write(&mut self, compiler_message: &CompilerMessage) -> Result<()> {
+        if !compiler_message
+            .message
+            .code
+            .as_ref()
+            .is_some_and(|code| self.lint_names.contains(&code.code))
+        {
+            return Ok(());
+        }
+        let primary = self
+            .primary_packages
+            .map(|packages| packages.contains(&compiler_message.package_id));
+        let mut value = serde_json::to_value(compiler_message)?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert(
+                String::from("reason"),
+                serde_json::Value::from("compiler-message"),
+            );
+            object.insert(
+                String::from("dylint_toolchain"),
+                serde_json::Value::from(self.toolchain),
+            );
+            if let Some(primary) = primary {
+                object.insert(
+                    String::from("dylint_primary"),
+                    serde_json::Value::from(primary),
+                );
+            }
+        }
+        if primary == Some(true) {
+            self.held_back.push(value.to_string());
+            return Ok(());
+        }
+        writeln!(self.sink, "{value}").with_context(|| "Could not write message")
+    }
+
+    /// Writes the messages that were held back because they are for primary packages.
+    fn finish(&mut self) -> Result<()> {
+        for line in self.held_back.drain(..) {
+            writeln!(self.sink, "{line}").with_context(|| "Could not write message")?;
+        }
+        Ok(())
+    }
+}
+
+/// Where [`run`] sends the diagnostics it receives, and how it filters and orders them. Each field
+/// that is `None` is ignored.
+pub struct Sinks<'a, 'b> {
+    /// The file to which rendered diagnostics are written, instead of stderr
+    pub pipe_stderr: Option<&'a str>,
+    /// The file to which lines that are not JSON messages are written, instead of stdout
+    pub pipe_stdout: Option<&'a str>,
+    /// Tallies the diagnostics
+    pub stats: Option<&'a mut Stats>,
+    /// Collects the diagnostics
+    pub diagnostics: Option<&'a mut Vec<Diagnostic>>,
+    /// Packages whose diagnostics are held back and written after all others, i.e., after the
+    /// diagnostics for dependencies
+    pub primary_packages: Option<&'a BTreeSet<PackageId>>,
+    /// Collects the diagnostics' machine-applicable suggestions
+    pub suggestions: Option<&'a mut Vec<Suggestion>>,
+    /// Receives the messages for diagnostics produced by Dylint lints as they arrive
+    pub json_lines: Option<&'a mut JsonLines<'b>>,
+    /// Discards the diagnostics it does not allow before they reach any of the above
+    pub file_filter: Option<&'a FileFilter>,
+}
+
+/// Runs `command`, which must produce JSON messages, and sends the diagnostics to `sinks`.
+/// Rendered diagnostics are written to stderr, unless `sinks.pipe_stderr` is provided. Lines that
+/// are not JSON messages are written to stdout, unless `sinks.pipe_stdout` is provided.
+pub fn run(command: &mut Command, sinks: Sinks<'_, '_>) -> Result<()> {
+    let Sinks {
+        pipe_stderr,
+        pipe_stdout,
+        mut stats,
+        mut diagnostics,
+        primary_packages,
+        mut suggestions,
+        mut json_lines,
+        file_filter,
+    } = sinks;
+
+    log::debug!("{:?}", command.get_envs().collect::<Vec<_>>());
+    log::debug!("{:?}", command.get_current_dir());
+    log::debug!("{:?}", command);
+
+    let mut rendered_sink = sink(pipe_stderr, "stderr", stderr())?;
+    let mut text_sink = sink(pipe_stdout, "stdout", stdout())?;
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not spawn `{command:?}`"))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("Could not get stdout of `{command:?}`"))?;
+
+    let mut held_back = Vec::new();
+
+    for message in Message::parse_stream(BufReader::new(stdout)) {
+        let message = message.with_context(|| "Could not read message")?;
+        match message {
+            Message::CompilerMessage(compiler_message) => {
+                if file_filter.is_some_and(|filter| !filter.allows(&compiler_message.message)) {
+                    continue;
+                }
+                if let Some(stats) = stats.as_deref_mut() {
+                    stats.tally(&compiler_message.message)?;
+                }
+                if let Some(diagnostics) = diagnostics.as_deref_mut() {
+                    diagnostics.push(compiler_message.message.clone());
+                }
+                if let Some(suggestions) = suggestions.as_deref_mut() {
+                    fix_noconflict::collect(&compiler_message.message, suggestions);
+                }
+                if let Some(json_lines) = json_lines.as_deref_mut() {
+                    json_lines.write(&compiler_message)?;
+                }
+                let Some(rendered) = compiler_message.message.rendered else {
+                    continue;
+                };
+                if primary_packages
+                    .is_some_and(|packages| packages.contains(&compiler_message.package_id))
+                {
+                    held_back.push(rendered);
+                } else {
+                    rendered_sink
+                        .write_all(rendered.as_bytes())
+                        .with_context(|| "Could not write rendered diagnostic")?;
+                }
+            }
+            Message::TextLine(line) => {
+                writeln!(text_sink, "{line}").with_context(|| "Could not write line")?;
+            }
+            _ => {}
+        }
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("Could not get status of `{command:?}`"))?;
+
+    for rendered in held_back {
+        rendered_sink
+            .write_all(rendered.as_bytes())
+            .with_context(|| "Could not write rendered diagnostic")?;
+    }
+
+    if let Some(json_lines) = json_lines {
+        json_lines.finish()?;
+    }
+
+    ensure!(status.success(), "command failed: {:?}", command);
+
+    Ok(())
+}
+
+/// Runs `command`, which must produce JSON messages, and writes each message to `pipe_stdout`, if
+/// provided, and to stdout otherwise. A `dylint_toolchain` field set to `toolchain` is added to
+/// each message, so that messages from different toolchains can be told apart. Lines that are not
+/// JSON objects are written unchanged.
+pub fn run_tagged(command: &mut Command, pipe_stdout: Option<&str>, toolchain: &str) -> Result<()> {
+    log::debug!("{:?}", command.get_envs().collect::<Vec<_>>());
+    log::debug!("{:?}", command.get_current_dir());
+    log::debug!("{:?}", command);
+
+    let mut sink = sink(pipe_stdout, "stdout", stdout())?;
+
+    let mut child = command
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not spawn `{command:?}`"))?;
+    let child_stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("Could not get stdout of `{command:?}`"))?;
+
+    for line in BufReader::new(child_stdout).lines() {
+        let line = line.with_context(|| "Could not read line")?;
+        let line = match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(serde_json::Value::Object(mut object)) => {
+                object.insert(
+                    String::from("dylint_toolchain"),
+                    serde_json::Value::from(toolchain),
+                );
+                serde_json::Value::Object(object).to_string()
+            }
+            _ => line,
+        };
+        writeln!(sink, "{line}").with_context(|| "Could not write message")?;
+    }
+
+    let status = child
+        .wait()
+        .with_context(|| format!("Could not get status of `{command:?}`"))?;
+
+    ensure!(status.success(), "command failed: {:?}", command);
+
+    Ok(())
+}
+
+/// Returns a writer for the file at `path`, if provided, and `default` otherwise. The file is
+/// opened for appending. `stream` names the stream that the file replaces, e.g., `stdout`.
+fn sink(path: Option<&str>, stream: &str, default: impl Write + 'static) -> Result<Box<dyn Write>> {
+    let Some(path) = path else {
+        return Ok(Box::new(default));
+    };
+    let file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .with_context(|| format!("Failed to open `{path}` for {stream} usage"))?;
+    Ok(Box::new(file))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::read_to_string;
+    use tempfile::tempdir;
+
+    // `cargo --version` writes a line that is not a JSON message, which should go to the
+    // `--pipe-stdout` file rather than to stdout.
+    #[test]
+    fn text_line_to_pipe_stdout() {
+        let tempdir = tempdir().unwrap();
+        let path = tempdir.path().join("stdout.txt");
+
+        let mut command = Command::new("cargo");
+        command.arg("--version");
+        run(
+            &mut command,
+            Sinks {
+                pipe_stderr: None,
+                pipe_stdout: path.to_str(),
+                stats: None,
+                diagnostics: None,
+                primary_packages: None,
+                suggestions: None,
+                json_lines: None,
+                file_filter: None,
+            },
+        )
+        .unwrap();
+
+        let contents = read_to_string(path).unwrap();
+        assert!(contents.starts_with("cargo "), "{contents:?}");
+    }
+}
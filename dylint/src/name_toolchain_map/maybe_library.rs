@@ -11,6 +11,12 @@ impl MaybeLibrary {
         self.inner.path()
     }
 
+    /// Returns true if the library is built from a package, i.e., if `build` does more than return
+    /// the library's path.
+    pub const fn is_package(&self) -> bool {
+        self.inner.is_package()
+    }
+
     pub fn build(&self, opts: &crate::opts::Dylint) -> Result<PathBuf> {
         self.inner.build(opts)
     }
@@ -51,6 +57,15 @@ impl Inner {
         }
     }
 
+    const fn is_package(&self) -> bool {
+        match self {
+            Self::Path(_) => false,
+
+            #[cfg(__library_packages)]
+            Self::Package(_) => true,
+        }
+    }
+
     #[cfg_attr(not(__library_packages), allow(unused_variables))]
     fn build(&self, opts: &crate::opts::Dylint) -> Result<PathBuf> {
         match self {
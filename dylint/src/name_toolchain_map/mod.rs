@@ -1,3 +1,5 @@
+#[cfg(__library_packages)]
+use crate::error::{ErrorKind, ResultExt};
 use anyhow::{ensure, Context, Result};
 use dylint_internal::{env, parse_path_filename};
 use once_cell::sync::OnceCell;
@@ -52,15 +54,30 @@ impl<'opts> Lazy<'opts> {
                         crate::library_packages::from_opts(self.inner.opts)?
                     } else {
                         let mut library_packages =
-                            crate::library_packages::from_workspace_metadata(self.inner.opts)?;
+                            crate::library_packages::from_workspace_metadata(self.inner.opts)
+                                .error_kind(ErrorKind::Config)?;
                         let library_packages_other =
-                            crate::library_packages::from_dylint_toml(self.inner.opts)?;
-                        ensure!(
-                            library_packages.is_empty() || library_packages_other.is_empty(),
-                            "`workspace.metadata.dylint.libraries` cannot appear in both \
-                             Cargo.toml and dylint.toml"
-                        );
+                            crate::library_packages::from_dylint_toml(self.inner.opts)
+                                .error_kind(ErrorKind::Config)?;
+                        if !library_packages.is_empty() && !library_packages_other.is_empty() {
+                            return Err(ErrorKind::Config.attach(anyhow::anyhow!(
+                                "`workspace.metadata.dylint.libraries` cannot appear in both \
+                                 Cargo.toml and dylint.toml"
+                            )));
+                        }
                         library_packages.extend(library_packages_other);
+                        let library_packages_locked =
+                            crate::library_packages::from_dylint_lock(self.inner.opts)
+                                .error_kind(ErrorKind::Config)?;
+                        if !library_packages_locked.is_empty() {
+                            if !library_packages.is_empty() {
+                                return Err(ErrorKind::Config.attach(anyhow::anyhow!(
+                                    "`workspace.metadata.dylint.libraries` cannot be used with \
+                                     dylint.lock"
+                                )));
+                            }
+                            library_packages = library_packages_locked;
+                        }
                         library_packages
                     };
 
@@ -95,6 +112,39 @@ impl<'opts> Lazy<'opts> {
                 Ok(name_toolchain_map)
             })
     }
+
+    /// Looks for a library named `name` in the directories listed in `DYLINT_LIBRARY_PATH`, without
+    /// initializing the map. If `toolchain` is `Some`, only libraries built with that toolchain are
+    /// considered. Returns `Ok(None)` if the map is already initialized, if `--git` or `--path` was
+    /// passed, or if the number of such libraries found is not exactly one.
+    pub fn find_in_library_path(
+        &self,
+        name: &str,
+        toolchain: Option<&str>,
+    ) -> Result<Option<(String, MaybeLibrary)>> {
+        if self.inner.name_toolchain_map.get().is_some() || self.inner.opts.git_or_path() {
+            return Ok(None);
+        }
+
+        let mut toolchain_maybe_libraries = Vec::new();
+
+        for path in dylint_library_paths()? {
+            for entry in dylint_libraries_in(&path)? {
+                let (other_name, other_toolchain, path) = entry?;
+                if other_name == name
+                    && toolchain.map_or(true, |toolchain| toolchain == other_toolchain)
+                {
+                    toolchain_maybe_libraries.push((other_toolchain, MaybeLibrary::from(path)));
+                }
+            }
+        }
+
+        if toolchain_maybe_libraries.len() == 1 {
+            Ok(toolchain_maybe_libraries.pop())
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 fn dylint_library_paths() -> Result<Vec<PathBuf>> {
@@ -31,14 +31,21 @@ pub struct Dylint {
     pub operation: Operation,
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Default)]
 pub struct LibrarySelection {
     pub all: bool,
 
     pub branch: Option<String>,
 
+    pub frozen_driver: bool,
+
     pub git: Option<String>,
 
+    pub git_retries: usize,
+
+    pub lib_dirs: Vec<String>,
+
     pub lib_paths: Vec<String>,
 
     pub libs: Vec<String>,
@@ -53,8 +60,14 @@ pub struct LibrarySelection {
 
     pub pattern: Option<String>,
 
+    pub require_single_toolchain: bool,
+
     pub rev: Option<String>,
 
+    pub skip_unbuildable: bool,
+
+    pub strip: bool,
+
     pub tag: Option<String>,
 }
 
@@ -62,6 +75,8 @@ pub struct LibrarySelection {
 #[non_exhaustive]
 pub enum Operation {
     Check(Check),
+    CheckConfig(CheckConfig),
+    CheckFile(CheckFile),
     List(List),
     #[cfg(feature = "package_options")]
     New(New),
@@ -74,22 +89,86 @@ pub enum Operation {
 pub struct Check {
     pub lib_sel: LibrarySelection,
 
+    pub all_features: bool,
+
+    pub allow_downgrade: bool,
+
+    pub collect_only: bool,
+
+    pub deps_first: bool,
+
+    pub envs: Vec<String>,
+
+    pub exclude_lints: Vec<String>,
+
+    pub features: Vec<String>,
+
     pub fix: bool,
 
+    pub fix_noconflict: bool,
+
+    pub fmt_after_fix: bool,
+
+    pub isolate_libraries: bool,
+
     pub keep_going: bool,
 
+    pub message_format: Option<String>,
+
+    pub no_default_features: bool,
+
     pub no_deps: bool,
 
     pub packages: Vec<String>,
 
+    pub patch: Option<String>,
+
+    pub print_driver_path: bool,
+
+    pub print_libs_json: bool,
+
+    pub print_sysroot: bool,
+
+    pub stats: Option<String>,
+
+    pub summary: bool,
+
+    pub verbose: bool,
+
     pub workspace: bool,
 
+    pub workspace_dirs: Vec<String>,
+
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CheckConfig {
+    pub lib_sel: LibrarySelection,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CheckFile {
+    pub lib_sel: LibrarySelection,
+
+    pub path: String,
+
     pub args: Vec<String>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct List {
     pub lib_sel: LibrarySelection,
+
+    pub assert_lint_count: Option<usize>,
+
+    pub configs: bool,
+
+    pub message_format: Option<String>,
+
+    pub toolchains: bool,
+
+    pub with_docs: bool,
 }
 
 #[cfg(feature = "package_options")]
@@ -144,7 +223,7 @@ static LIBRARY_SELECTION: Lazy<LibrarySelection> = Lazy::new(LibrarySelection::d
 impl Operation {
     const fn has_library_selection(&self) -> bool {
         match self {
-            Self::Check(_) | Self::List(_) => true,
+            Self::Check(_) | Self::CheckConfig(_) | Self::CheckFile(_) | Self::List(_) => true,
             #[cfg(feature = "package_options")]
             Self::New(_) | Self::Upgrade(_) => false,
         }
@@ -153,6 +232,8 @@ impl Operation {
     fn library_selection(&self) -> &LibrarySelection {
         match self {
             Self::Check(check) => &check.lib_sel,
+            Self::CheckConfig(check_config) => &check_config.lib_sel,
+            Self::CheckFile(check_file) => &check_file.lib_sel,
             Self::List(list) => &list.lib_sel,
             #[cfg(feature = "package_options")]
             Self::New(_) | Self::Upgrade(_) => {
@@ -175,6 +256,8 @@ impl Operation {
     fn library_selection_mut(&mut self) -> &mut LibrarySelection {
         match self {
             Self::Check(check) => &mut check.lib_sel,
+            Self::CheckConfig(check_config) => &mut check_config.lib_sel,
+            Self::CheckFile(check_file) => &mut check_file.lib_sel,
             Self::List(list) => &mut list.lib_sel,
             #[cfg(feature = "package_options")]
             Self::New(_) | Self::Upgrade(_) => {
@@ -0,0 +1,38 @@
+use ansi_term::Style;
+use is_terminal::IsTerminal;
+use std::io::Write;
+
+/// Reports progress building libraries. Nothing is reported if `--quiet` was used or if stderr is
+/// not a terminal.
+pub struct Progress {
+    enabled: bool,
+    current: usize,
+    total: usize,
+}
+
+impl Progress {
+    pub fn new(opts: &crate::opts::Dylint, total: usize) -> Self {
+        Self {
+            enabled: !opts.quiet && std::io::stderr().is_terminal(),
+            current: 0,
+            total,
+        }
+    }
+
+    #[allow(clippy::expect_used)]
+    pub fn building(&mut self, name: &str) {
+        self.current += 1;
+        if self.enabled {
+            // Writing directly to `stderr` prevents capture by `libtest`, and keeps the
+            // progress out of `--pipe-stdout` output.
+            std::io::stderr()
+                .write_fmt(format_args!(
+                    "{} library {}/{}: {name}\n",
+                    Style::new().bold().paint("Building"),
+                    self.current,
+                    self.total
+                ))
+                .expect("Could not write to stderr");
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::ToolchainMap;
+use anyhow::{anyhow, Context, Result};
+use cargo_metadata::diagnostic::Diagnostic;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::write,
+    time::Instant,
+};
+
+/// Summary of a check run written by `--stats`
+#[derive(Serialize)]
+pub struct Stats {
+    /// Number of libraries resolved
+    libraries: usize,
+    /// Toolchains used
+    toolchains: Vec<String>,
+    /// Number of diagnostics of each level
+    diagnostics: BTreeMap<String, usize>,
+    /// Number of diagnostics of each level, by lint name
+    lints: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Wall time in seconds
+    wall_time_secs: f64,
+    #[serde(skip)]
+    start: Instant,
+}
+
+impl Stats {
+    pub fn new(resolved: &ToolchainMap) -> Self {
+        Self {
+            libraries: resolved.values().map(|paths| paths.len()).sum(),
+            toolchains: resolved.keys().cloned().collect(),
+            diagnostics: BTreeMap::new(),
+            lints: BTreeMap::new(),
+            wall_time_secs: 0.0,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn write(mut self, path: &str) -> Result<()> {
+        self.wall_time_secs = self.start.elapsed().as_secs_f64();
+        let json = serde_json::to_string_pretty(&self)?;
+        write(path, json).with_context(|| format!("`write` failed for `{path}`"))
+    }
+
+    /// Returns a line like "Dylint: 12 warnings across 3 libraries", counting only diagnostics
+    /// produced by the lints in `lint_names`.
+    pub fn summary(&self, lint_names: &BTreeSet<String>) -> String {
+        let mut counts = BTreeMap::<&str, usize>::new();
+        for (lint, levels) in &self.lints {
+            if !lint_names.contains(lint) {
+                continue;
+            }
+            for (level, count) in levels {
+                *counts.entry(level).or_default() += count;
+            }
+        }
+        let diagnostics = if counts.is_empty() {
+            String::from("0 warnings")
+        } else {
+            counts
+                .iter()
+                .map(|(level, count)| format!("{count} {level}{}", plural(*count)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        format!(
+            "Dylint: {diagnostics} across {} librar{}",
+            self.libraries,
+            if self.libraries == 1 { "y" } else { "ies" }
+        )
+    }
+
+    pub fn tally(&mut self, diagnostic: &Diagnostic) -> Result<()> {
+        // Skip summary messages like "N warnings emitted".
+        if diagnostic.spans.is_empty() && diagnostic.code.is_none() {
+            return Ok(());
+        }
+        let level = serde_json::to_value(diagnostic.level)?
+            .as_str()
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| anyhow!("Could not serialize diagnostic level"))?;
+        if let Some(code) = &diagnostic.code {
+            *self
+                .lints
+                .entry(code.code.clone())
+                .or_default()
+                .entry(level.clone())
+                .or_default() += 1;
+        }
+        *self.diagnostics.entry(level).or_default() += 1;
+        Ok(())
+    }
+}
+
+const fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
@@ -21,12 +21,15 @@ use once_cell::sync::OnceCell;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::Applicability;
 use rustc_hir::{def_id::DefId, Item, ItemKind};
-use rustc_lint::{LateContext, LateLintPass, LintStore};
+use rustc_lint::{LateContext, LateLintPass, LintContext, LintStore};
 use rustc_middle::ty::{self, Upcast};
 use rustc_session::{declare_lint, impl_lint_pass, Session};
 use rustc_span::{sym, ExpnKind, MacroKind, Symbol};
 use serde::Deserialize;
-use std::{cell::RefCell, iter};
+use std::{
+    cell::{Cell, RefCell},
+    iter,
+};
 
 declare_lint! {
     /// ### What it does
@@ -36,8 +39,8 @@ declare_lint! {
     /// Not deriving the additional traits could be a missed opportunity.
     ///
     /// ### Known problems
-    /// - This lint is noisy! The `at_least_one_field` and `ignore` options (see below) can be used
-    ///   to make the lint less noisy.
+    /// - This lint is noisy! The `at_least_one_field`, `ignore`, and `ignore_modules` options (see
+    ///   below) can be used to make the lint less noisy.
     /// - Currently does not support traits with type or constant parameters (e.g., `PartialEq`), or
     ///   traits with supertraits with type or constant parameters (e.g., `Eq`).
     ///
@@ -63,6 +66,9 @@ declare_lint! {
     ///   trait.
     /// - `ignore: Vec<String>` (default `[]`): A list of macro paths the lint should not suggest to
     ///   derive.
+    /// - `ignore_modules: Vec<String>` (default `[]`): A list of module paths (e.g.,
+    ///   `my_crate::generated`) whose data structures the lint should not check. Submodules of a
+    ///   listed module are ignored as well.
     pub DERIVE_OPPORTUNITY,
     Warn,
     "data structures that could derive additional traits"
@@ -85,6 +91,9 @@ struct Config {
 
     #[serde(default)]
     ignore: Vec<String>,
+
+    #[serde(default)]
+    ignore_modules: Vec<String>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -97,6 +106,8 @@ struct DeriveOpportunity<'tcx> {
     config: Config,
     derivable_traits_map: OnceCell<FxHashMap<Macro, FxHashSet<DefId>>>,
     transitively_applicable_macros_map: RefCell<FxHashMap<ty::Ty<'tcx>, FxHashSet<Macro>>>,
+    implements_trait_map: RefCell<FxHashMap<(ty::Ty<'tcx>, DefId), bool>>,
+    implements_trait_queries: Cell<usize>,
 }
 
 impl DeriveOpportunity<'_> {
@@ -105,6 +116,8 @@ impl DeriveOpportunity<'_> {
             config: dylint_linting::config_or_default(env!("CARGO_PKG_NAME")),
             derivable_traits_map: OnceCell::default(),
             transitively_applicable_macros_map: RefCell::new(FxHashMap::default()),
+            implements_trait_map: RefCell::new(FxHashMap::default()),
+            implements_trait_queries: Cell::new(0),
         }
     }
 }
@@ -114,6 +127,9 @@ impl<'tcx> LateLintPass<'tcx> for DeriveOpportunity<'tcx> {
         if !matches!(item.kind, ItemKind::Enum(..) | ItemKind::Struct(..),) {
             return;
         }
+        if self.in_ignored_module(cx, item) {
+            return;
+        }
         let ty = cx.tcx.type_of(item.owner_id).skip_binder();
         let macros = self
             .transitively_applicable_macros(cx, ty)
@@ -143,9 +159,37 @@ impl<'tcx> LateLintPass<'tcx> for DeriveOpportunity<'tcx> {
             );
         }
     }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        if dylint_linting::instrumentation_enabled("DERIVE_OPPORTUNITY_DEBUG_CACHE") {
+            cx.sess().dcx().note(format!(
+                "`implements_trait_with_bounds` was queried {} times and computed {} times",
+                self.implements_trait_queries.get(),
+                self.implements_trait_map.borrow().len()
+            ));
+        }
+    }
 }
 
 impl<'tcx> DeriveOpportunity<'tcx> {
+    fn in_ignored_module(&self, cx: &LateContext<'tcx>, item: &Item<'tcx>) -> bool {
+        let def_path = cx.get_def_path(item.owner_id.to_def_id());
+        let Some((_, module_path)) = def_path.split_last() else {
+            return false;
+        };
+        let module_path = module_path
+            .iter()
+            .map(Symbol::as_str)
+            .collect::<Vec<_>>()
+            .join("::");
+        self.config.ignore_modules.iter().any(|module| {
+            module_path == *module
+                || module_path
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    }
+
     fn transitively_applicable_macros(
         &self,
         cx: &LateContext<'tcx>,
@@ -229,7 +273,27 @@ impl<'tcx> DeriveOpportunity<'tcx> {
             .get(&mac)
             .unwrap()
             .iter()
-            .any(|&trait_id| implements_trait_with_bounds(cx, ty, trait_id))
+            .any(|&trait_id| self.implements_trait_with_bounds(cx, ty, trait_id))
+    }
+
+    // `macro_applied` is called for every field of every data structure, and the fields
+    // of a crate's data structures tend to have the same types. So the results are cached.
+    fn implements_trait_with_bounds(
+        &self,
+        cx: &LateContext<'tcx>,
+        ty: ty::Ty<'tcx>,
+        trait_id: DefId,
+    ) -> bool {
+        self.implements_trait_queries
+            .set(self.implements_trait_queries.get() + 1);
+        if let Some(&implements) = self.implements_trait_map.borrow().get(&(ty, trait_id)) {
+            return implements;
+        }
+        let implements = implements_trait_with_bounds(cx, ty, trait_id);
+        self.implements_trait_map
+            .borrow_mut()
+            .insert((ty, trait_id), implements);
+        implements
     }
 
     fn derivable_traits(&self, cx: &LateContext<'tcx>) -> &FxHashMap<Macro, FxHashSet<DefId>> {
@@ -440,6 +504,15 @@ fn ui_at_least_one_field() {
         .run();
 }
 
+// Every field in `ui_cache/main.rs` has type `u32` or `Base`, so most of the queries in
+// the note at the end of `main.stderr` are answered from the cache.
+#[test]
+fn ui_cache() {
+    dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "ui_cache")
+        .dylint_toml("instrumentation.derive_opportunity_debug_cache = true")
+        .run();
+}
+
 #[test]
 fn ui_ignore() {
     dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "ui_ignore")
@@ -447,12 +520,28 @@ fn ui_ignore() {
         .run();
 }
 
+// The data structures in `generated` and its submodule `nested` are ignored. The ones at the crate
+// root and in `generated_other` are not.
+#[test]
+fn ui_ignore_modules() {
+    dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "ui_ignore_modules")
+        .dylint_toml(r#"derive_opportunity.ignore_modules = ["ui_ignore_modules::generated"]"#)
+        .run();
+}
+
+// `ui_cache/main.rs` and `ui_ignore_modules/main.rs` are the only examples that are not copies of
+// `ui/main.rs`.
 #[test]
 fn ui_main_rs_equal() {
     let ui_main_rs = std::fs::read_to_string("ui/main.rs").unwrap();
-    let ui_at_least_one_field_main_rs =
-        std::fs::read_to_string("ui_at_least_one_field/main.rs").unwrap();
-    let ui_ignore_main_rs = std::fs::read_to_string("ui_ignore/main.rs").unwrap();
-    assert_eq!(ui_main_rs, ui_at_least_one_field_main_rs);
-    assert_eq!(ui_main_rs, ui_ignore_main_rs);
+    for example in dylint_testing::example_names(env!("CARGO_PKG_NAME")).unwrap() {
+        if example == "ui_cache" || example == "ui_ignore_modules" {
+            continue;
+        }
+        let main_rs = std::fs::read_to_string(format!("{example}/main.rs")).unwrap();
+        assert_eq!(
+            ui_main_rs, main_rs,
+            "`{example}/main.rs` differs from `ui/main.rs`"
+        );
+    }
 }
@@ -0,0 +1,31 @@
+// run-rustfix
+#![expect(dead_code)]
+
+fn main() {}
+
+#[derive(Debug)]
+struct Base;
+
+struct A0 {
+    a: u32,
+    b: u32,
+    c: Base,
+}
+
+struct A1 {
+    a: u32,
+    b: u32,
+    c: Base,
+}
+
+struct A2 {
+    a: u32,
+    b: u32,
+    c: Base,
+}
+
+struct A3 {
+    a: u32,
+    b: u32,
+    c: Base,
+}
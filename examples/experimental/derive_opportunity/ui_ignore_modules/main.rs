@@ -0,0 +1,36 @@
+// run-rustfix
+#![expect(dead_code)]
+
+fn main() {}
+
+#[derive(Default, serde::Deserialize)]
+struct Derived;
+
+struct Linted {
+    foo: Derived,
+}
+
+mod generated {
+    use super::Derived;
+
+    struct Ignored {
+        foo: Derived,
+    }
+
+    mod nested {
+        use super::Derived;
+
+        struct AlsoIgnored {
+            foo: Derived,
+        }
+    }
+}
+
+// `generated_other`'s name starts with `generated`, but it is not a submodule of `generated`.
+mod generated_other {
+    use super::Derived;
+
+    struct NotIgnored {
+        foo: Derived,
+    }
+}
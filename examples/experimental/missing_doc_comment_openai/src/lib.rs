@@ -286,7 +286,7 @@ fn send_request(api_key: &str, request: &openai::Request) -> Result<openai::Resp
 }
 
 fn debug(label: &str, data: &[u8]) {
-    if enabled("DEBUG") {
+    if dylint_linting::instrumentation_enabled("MISSING_DOC_COMMENT_OPENAI_DEBUG") {
         let s = match std::str::from_utf8(data) {
             Ok(s) => s.to_owned(),
             Err(error) => error.to_string(),
@@ -384,12 +384,6 @@ fn skip_preceding_line_comments(cx: &LateContext<'_>, mut span: Span) -> Span {
     span
 }
 
-#[must_use]
-fn enabled(name: &str) -> bool {
-    let key = env!("CARGO_PKG_NAME").to_uppercase() + "_" + name;
-    std::env::var(key).is_ok_and(|value| value != "0")
-}
-
 #[must_use]
 fn testing() -> bool {
     std::env::var(OPENAI_API_KEY).is_ok_and(|value| value.to_lowercase().contains("test"))
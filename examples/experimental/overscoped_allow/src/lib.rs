@@ -27,18 +27,22 @@ use rustc_hir::{
 use rustc_lint::{LateContext, LateLintPass, LintContext, LintStore};
 use rustc_session::{declare_lint, impl_lint_pass, Session};
 use rustc_span::{sym, BytePos, CharPos, FileLines, FileName, RealFileName, Span, Symbol};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
+    env::split_paths,
     fs::OpenOptions,
+    io::Write,
     path::{absolute, Path, PathBuf},
 };
 
+const OVERSCOPED_ALLOW_AUDIT: &str = "OVERSCOPED_ALLOW_AUDIT";
 const OVERSCOPED_ALLOW_PATH: &str = "OVERSCOPED_ALLOW_PATH";
 
 declare_lint! {
     /// ### What it does
-    /// Checks for `allow` attributes whose scope could be reduced.
+    /// Checks for `allow` attributes whose scope could be reduced. Optionally, checks `deny` and
+    /// `forbid` attributes as well (see "Configuration" below).
     ///
     /// ### Why is this bad?
     /// An `allow` attribute whose scope is too large could suppress warnings/errors and cause them
@@ -79,7 +83,46 @@ declare_lint! {
     ///    generated in 1.
     ///
     /// To use a file other than `warnings.json`, store that file's path in the environment variable
-    /// variable `OVERSCOPED_ALLOW_PATH`.
+    /// variable `OVERSCOPED_ALLOW_PATH`. To use several files, e.g., one per lint, separate their
+    /// paths as in the `PATH` environment variable (i.e., with `:` on Unix and `;` on Windows).
+    /// Relative paths, including the default `warnings.json`, are resolved against the workspace
+    /// root, not the current directory.
+    ///
+    /// The same steps apply to `deny` and `forbid` attributes. Running the lint at the
+    /// `force-warn` level in step 1 causes the diagnostics that a `deny` or `forbid` attribute
+    /// would otherwise turn into errors to be emitted as warnings, so that the build succeeds and
+    /// the diagnostics are recorded in `warnings.json`.
+    ///
+    /// ### Audit mode
+    /// To also produce a report of every lint-level attribute and the diagnostics it covers, store
+    /// a file path in the environment variable `OVERSCOPED_ALLOW_AUDIT`. For each lint named in an
+    /// `allow`, `expect`, `warn`, `deny`, or `forbid` attribute, one JSON object is appended to the
+    /// file. For example (shown here on multiple lines):
+    ///
+    /// ```json
+    /// {
+    ///   "level": "allow",
+    ///   "lint": "clippy::unwrap_used",
+    ///   "file": "src/main.rs",
+    ///   "line": 29,
+    ///   "column": 9,
+    ///   "diagnostics": [
+    ///     {
+    ///       "message": "used `unwrap()` on an `Option` value",
+    ///       "file": "src/main.rs",
+    ///       "line": 31,
+    ///       "column": 5
+    ///     }
+    ///   ],
+    ///   "suppresses_nothing": false
+    /// }
+    /// ```
+    ///
+    /// The diagnostics are those read in step 2 above. So an attribute has `"suppresses_nothing":
+    /// true` if none of the diagnostics in `warnings.json` (or the files named in
+    /// `OVERSCOPED_ALLOW_PATH`) fall within its scope. As with `OVERSCOPED_ALLOW_PATH`, a relative
+    /// path is resolved against the workspace root. The file is appended to, one line per lint, for
+    /// each crate checked. So remove it before rerunning the lint.
     ///
     /// ### Example
     /// ```rust
@@ -96,17 +139,44 @@ declare_lint! {
     /// }
     /// ```
     ///
+    /// ### Configuration
+    /// - `levels: Vec<String>` (default `["allow"]`): The lint levels whose attributes the lint
+    ///   should try to narrow. Valid levels are `"allow"`, `"deny"`, and `"forbid"`.
+    ///
     /// [`force-warn`]: https://doc.rust-lang.org/rustc/lints/levels.html#force-warn
     pub OVERSCOPED_ALLOW,
     Warn,
     "`allow` attributes whose scope could be reduced"
 }
 
-#[derive(Default)]
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default = "default_levels")]
+    levels: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            levels: default_levels(),
+        }
+    }
+}
+
+fn default_levels() -> Vec<String> {
+    vec![String::from("allow")]
+}
+
+const NARROWABLE_LEVELS: [&str; 3] = ["allow", "deny", "forbid"];
+
 struct OverscopedAllow {
+    config: Config,
     metadata: OnceCell<Metadata>,
     diagnostics: OnceCell<Vec<Diagnostic>>,
-    ancestor_meta_item_span_map: FxHashMap<HirId, FxHashMap<Span, FxHashSet<Option<Span>>>>,
+    ancestor_meta_item_span_map:
+        FxHashMap<HirId, FxHashMap<Span, (Symbol, FxHashSet<Option<Span>>)>>,
+    audit_path: Option<PathBuf>,
+    audit_entries: FxHashMap<Span, AuditEntry>,
 }
 
 impl_lint_pass!(OverscopedAllow => [OVERSCOPED_ALLOW]);
@@ -117,25 +187,63 @@ struct Message {
     message: Option<Diagnostic>,
 }
 
+#[derive(Serialize)]
+struct AuditEntry {
+    level: String,
+    lint: String,
+    #[serde(flatten)]
+    location: Location,
+    diagnostics: Vec<AuditDiagnostic>,
+    suppresses_nothing: bool,
+}
+
+#[derive(Serialize)]
+struct AuditDiagnostic {
+    message: String,
+    #[serde(flatten)]
+    location: Option<Location>,
+}
+
+#[derive(Eq, Ord, PartialEq, PartialOrd, Serialize)]
+struct Location {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
 #[allow(clippy::no_mangle_with_rust_abi)]
 #[no_mangle]
-pub fn register_lints(_sess: &Session, lint_store: &mut LintStore) {
+pub fn register_lints(sess: &Session, lint_store: &mut LintStore) {
+    dylint_linting::init_config(sess);
     lint_store.register_lints(&[OVERSCOPED_ALLOW]);
     lint_store.register_late_pass(move |_| Box::new(OverscopedAllow::new()));
 }
 
-fn read_diagnostics() -> Result<Vec<Diagnostic>> {
-    let path = var(OVERSCOPED_ALLOW_PATH)
+// `OVERSCOPED_ALLOW_PATH` can hold several paths, separated as in `PATH`. The
+// diagnostics from all of the files are merged.
+fn read_diagnostics(workspace_root: Option<&Path>) -> Result<Vec<Diagnostic>> {
+    let paths = var(OVERSCOPED_ALLOW_PATH)
         .ok()
         .unwrap_or_else(|| "warnings.json".to_owned());
-    let canonical_path = Path::new(&path)
+    let mut diagnostics = Vec::new();
+    for path in split_paths(&paths) {
+        let path = match workspace_root {
+            Some(workspace_root) if path.is_relative() => workspace_root.join(path),
+            _ => path,
+        };
+        read_diagnostics_from(&path, &mut diagnostics)?;
+    }
+    Ok(diagnostics)
+}
+
+fn read_diagnostics_from(path: &Path, diagnostics: &mut Vec<Diagnostic>) -> Result<()> {
+    let canonical_path = path
         .canonicalize()
         .with_context(|| format!("Could not canonicalize {path:?}"))?;
     let file = OpenOptions::new()
         .read(true)
-        .open(&path)
+        .open(path)
         .with_context(|| format!("Could not open {canonical_path:?}"))?;
-    let mut diagnostics = Vec::new();
     for result in serde_json::Deserializer::from_reader(file).into_iter::<Message>() {
         let message = result?;
         if message.reason == "compiler-message"
@@ -144,15 +252,26 @@ fn read_diagnostics() -> Result<Vec<Diagnostic>> {
             diagnostics.push(diagnostic);
         }
     }
-    Ok(diagnostics)
+    Ok(())
 }
 
 impl OverscopedAllow {
     fn new() -> Self {
+        let config: Config = dylint_linting::config_or_default(env!("CARGO_PKG_NAME"));
+        for level in &config.levels {
+            assert!(
+                NARROWABLE_LEVELS.contains(&level.as_str()),
+                "`overscoped_allow` cannot narrow `{level}` attributes; valid levels are: \
+                 {NARROWABLE_LEVELS:?}"
+            );
+        }
         Self {
+            config,
             metadata: OnceCell::new(),
             diagnostics: OnceCell::new(),
             ancestor_meta_item_span_map: FxHashMap::default(),
+            audit_path: var(OVERSCOPED_ALLOW_AUDIT).ok().map(PathBuf::from),
+            audit_entries: FxHashMap::default(),
         }
     }
 
@@ -172,9 +291,15 @@ impl OverscopedAllow {
         })
     }
 
-    fn diagnostics(&self, cx: &LateContext<'_>) -> &Vec<Diagnostic> {
+    // The diagnostics are read lazily because relative paths are resolved against the
+    // workspace root, and `metadata` needs a source path to find the workspace. That path is
+    // obtained from `span`. If `span` is not from a local file, relative paths are resolved against
+    // the current directory.
+    fn diagnostics(&self, cx: &LateContext<'_>, span: Span) -> &Vec<Diagnostic> {
         self.diagnostics.get_or_init(|| {
-            read_diagnostics().unwrap_or_else(|error| {
+            let workspace_root = local_path_from_span(cx, span)
+                .map(|local_path| self.metadata(&local_path).workspace_root.as_std_path());
+            read_diagnostics(workspace_root).unwrap_or_else(|error| {
                 cx.sess()
                     .dcx()
                     .warn(format!("`overscoped_allow` is disabled: {error:?}"));
@@ -183,15 +308,32 @@ impl OverscopedAllow {
         })
     }
 
-    fn diagnostics_mut(&mut self, cx: &LateContext<'_>) -> &mut Vec<Diagnostic> {
-        let _: &Vec<Diagnostic> = self.diagnostics(cx);
+    fn diagnostics_mut(&mut self, cx: &LateContext<'_>, span: Span) -> &mut Vec<Diagnostic> {
+        let _: &Vec<Diagnostic> = self.diagnostics(cx, span);
         self.diagnostics.get_mut().unwrap()
     }
 }
 
 impl<'tcx> LateLintPass<'tcx> for OverscopedAllow {
     fn check_crate(&mut self, cx: &LateContext<'tcx>) {
-        let _: &Vec<Diagnostic> = self.diagnostics(cx);
+        let _: &Vec<Diagnostic> = self.diagnostics(cx, cx.tcx.hir().span(CRATE_HIR_ID));
+    }
+
+    // In audit mode, every lint-level attribute is recorded, including those that cover
+    // no diagnostics.
+    fn check_attribute(&mut self, cx: &LateContext<'tcx>, attr: &'tcx Attribute) {
+        if self.audit_path.is_none() || !is_lint_attr(attr) || attr.span.from_expansion() {
+            return;
+        }
+        for meta_item in attr
+            .meta_item_list()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(MetaItemInner::meta_item)
+            .filter(|meta_item| meta_item.is_word())
+        {
+            self.audit(cx, attr, meta_item, None);
+        }
     }
 
     fn check_item_post(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'tcx>) {
@@ -225,6 +367,12 @@ impl<'tcx> LateLintPass<'tcx> for OverscopedAllow {
 
     fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
         self.emit(cx, CRATE_HIR_ID);
+
+        if let Err(error) = self.write_audit(cx) {
+            cx.sess().dcx().warn(format!(
+                "`overscoped_allow` could not write audit report: {error:?}"
+            ));
+        }
     }
 }
 
@@ -237,10 +385,10 @@ impl OverscopedAllow {
     fn check(&mut self, cx: &LateContext<'_>, hir_id: HirId) {
         let span = include_trailing_semicolons(cx, cx.tcx.hir().span(hir_id));
         let mut i = 0;
-        while i < self.diagnostics(cx).len() {
-            let diagnostic = &self.diagnostics(cx)[i];
+        while i < self.diagnostics(cx, span).len() {
+            let diagnostic = &self.diagnostics(cx, span)[i];
             if self.span_contains_diagnostic(cx, span, diagnostic) {
-                let diagnostic = self.diagnostics_mut(cx).swap_remove(i);
+                let diagnostic = self.diagnostics_mut(cx, span).swap_remove(i);
                 self.check_ancestor_lint_attrs(cx, hir_id, &diagnostic);
             } else {
                 i += 1;
@@ -273,7 +421,9 @@ impl OverscopedAllow {
                     continue;
                 }
                 if let Some(meta_item) = meta_item_for_diagnostic(attr, diagnostic) {
-                    if attr.has_name(sym::allow) {
+                    self.audit(cx, attr, &meta_item, Some(diagnostic));
+                    let level = attr.name_or_empty();
+                    if self.is_narrowable(level) {
                         let target_span = target_hir_id.and_then(|target_hir_id| {
                             if target_hir_id == ancestor_hir_id {
                                 None
@@ -285,14 +435,20 @@ impl OverscopedAllow {
                             .ancestor_meta_item_span_map
                             .entry(ancestor_hir_id)
                             .or_default();
-                        let spans = meta_item_span_map.entry(meta_item.span).or_default();
+                        let (_, spans) = meta_item_span_map
+                            .entry(meta_item.span)
+                            .or_insert_with(|| (level, FxHashSet::default()));
                         spans.insert(target_span);
                     } else {
                         // smoelius: Don't alert if we started in a test. The `allow` could have
                         // appeared inside the test, and `overscoped_allow` currently cannot see
                         // inside tests.
+                        // A level that can be narrowed but was not selected in the
+                        // configuration is not an error.
                         assert!(
-                            started_in_test || attr.has_name(sym::expect),
+                            started_in_test
+                                || attr.has_name(sym::expect)
+                                || NARROWABLE_LEVELS.contains(&level.as_str()),
                             "Could not find `allow` for diagnostic: {diagnostic:?}"
                         );
                     }
@@ -304,7 +460,7 @@ impl OverscopedAllow {
 
     fn emit(&mut self, cx: &LateContext<'_>, hir_id: HirId) {
         if let Some(meta_item_span_map) = self.ancestor_meta_item_span_map.remove(&hir_id) {
-            for (meta_item_span, spans) in meta_item_span_map {
+            for (meta_item_span, (level, spans)) in meta_item_span_map {
                 // smoelius: Don't warn about `allow`s spanning multiple diagnostics.
                 // smoelius: If a span is `None`, it means we could not find a `Node` satisfying
                 // `can_have_attrs` between the diagnostic source (inclusive) and the `allow`
@@ -315,15 +471,105 @@ impl OverscopedAllow {
                         cx,
                         OVERSCOPED_ALLOW,
                         meta_item_span,
-                        "`allow` could be moved closer to diagnostic source",
+                        format!("`{level}` could be moved closer to diagnostic source"),
                         Some(span),
-                        "`allow` could be moved here",
+                        format!("`{level}` could be moved here"),
                     );
                 }
             }
         }
     }
 
+    fn audit(
+        &mut self,
+        cx: &LateContext<'_>,
+        attr: &Attribute,
+        meta_item: &MetaItem,
+        diagnostic: Option<&Diagnostic>,
+    ) {
+        if self.audit_path.is_none() {
+            return;
+        }
+        let location = self.location(cx, meta_item.span);
+        let entry = self
+            .audit_entries
+            .entry(meta_item.span)
+            .or_insert_with(|| AuditEntry {
+                level: attr.name_or_empty().to_string(),
+                lint: meta_item_path(meta_item),
+                location,
+                diagnostics: Vec::new(),
+                suppresses_nothing: false,
+            });
+        if let Some(diagnostic) = diagnostic {
+            entry.diagnostics.push(audit_diagnostic(diagnostic));
+        }
+    }
+
+    // Like `OVERSCOPED_ALLOW_PATH`, a relative `OVERSCOPED_ALLOW_AUDIT` path is resolved
+    // against the workspace root. The file is appended to because the lint runs once per crate.
+    fn write_audit(&mut self, cx: &LateContext<'_>) -> Result<()> {
+        let Some(path) = self.audit_path.clone() else {
+            return Ok(());
+        };
+        let path = match local_path_from_span(cx, cx.tcx.hir().span(CRATE_HIR_ID)) {
+            Some(local_path) if path.is_relative() => self
+                .metadata(&local_path)
+                .workspace_root
+                .as_std_path()
+                .join(path),
+            _ => path,
+        };
+        let mut entries = self
+            .audit_entries
+            .drain()
+            .map(|(_, entry)| entry)
+            .collect::<Vec<_>>();
+        entries.sort_by(|x, y| x.location.cmp(&y.location));
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Could not open {path:?}"))?;
+        for mut entry in entries {
+            entry.suppresses_nothing = entry.diagnostics.is_empty();
+            // Write each line with a single call, so that lines written by crates checked
+            // in parallel are not interleaved.
+            let mut line = serde_json::to_string(&entry)?;
+            line.push('\n');
+            file.write_all(line.as_bytes())
+                .with_context(|| format!("Could not write to {path:?}"))?;
+        }
+        Ok(())
+    }
+
+    fn location(&self, cx: &LateContext<'_>, span: Span) -> Location {
+        let source_map = cx.sess().source_map();
+        let loc = source_map.lookup_char_pos(span.lo());
+        let file = if let Some(local_path) = local_path_from_span(cx, span) {
+            let workspace_root = self.metadata(&local_path).workspace_root.as_std_path();
+            local_path
+                .strip_prefix(workspace_root)
+                .unwrap_or(&local_path)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            source_map.span_to_filename(span).prefer_local().to_string()
+        };
+        Location {
+            file,
+            line: loc.line,
+            column: loc.col.0 + 1,
+        }
+    }
+
+    fn is_narrowable(&self, level: Symbol) -> bool {
+        self.config
+            .levels
+            .iter()
+            .any(|configured| configured == level.as_str())
+    }
+
     fn span_contains_diagnostic(
         &self,
         cx: &LateContext<'_>,
@@ -476,31 +722,84 @@ fn meta_item_for_diagnostic(attr: &Attribute, diagnostic: &Diagnostic) -> Option
         items
             .iter()
             .filter_map(MetaItemInner::meta_item)
-            .find(|meta_item| {
-                meta_item
-                    .path
-                    .segments
-                    .iter()
-                    .map(|path_segment| path_segment.ident.as_str())
-                    .collect::<Vec<_>>()
-                    .join("::")
-                    == code.code
-            })
+            .find(|meta_item| meta_item_path(meta_item) == code.code)
             .cloned()
     } else {
         None
     }
 }
 
+fn meta_item_path(meta_item: &MetaItem) -> String {
+    meta_item
+        .path
+        .segments
+        .iter()
+        .map(|path_segment| path_segment.ident.as_str())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn audit_diagnostic(diagnostic: &Diagnostic) -> AuditDiagnostic {
+    let span = diagnostic
+        .spans
+        .iter()
+        .find(|span| span.is_primary)
+        .or_else(|| diagnostic.spans.first());
+    AuditDiagnostic {
+        message: diagnostic.message.clone(),
+        location: span.map(|span| Location {
+            file: span.file_name.clone(),
+            line: span.line_start,
+            column: span.column_start,
+        }),
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::OVERSCOPED_ALLOW_PATH;
+    use super::{OVERSCOPED_ALLOW_AUDIT, OVERSCOPED_ALLOW_PATH};
     use assert_cmd::prelude::*;
-    use std::{env::set_var, process::Command, sync::Mutex};
-    use tempfile::NamedTempFile;
+    use serde_json::Value;
+    use std::{
+        env::{current_dir, join_paths, remove_var, set_current_dir, set_var},
+        fs::{read_to_string, File},
+        path::Path,
+        process::Command,
+        sync::Mutex,
+    };
+    use tempfile::{tempdir_in, NamedTempFile};
 
     static MUTEX: Mutex<()> = Mutex::new(());
 
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn ui_deny() {
+        let _lock = MUTEX.lock().unwrap();
+
+        install_clippy();
+
+        let (file, temp_path) = NamedTempFile::new().unwrap().into_parts();
+        Command::new("cargo")
+            .args([
+                "clippy",
+                "--example=ui_deny",
+                "--message-format=json",
+                "--",
+                "--force-warn=clippy::unwrap-used",
+            ])
+            .stdout(file)
+            .assert()
+            .success();
+        set_var(
+            OVERSCOPED_ALLOW_PATH,
+            temp_path.to_string_lossy().to_string(),
+        );
+        // See the comment in `ui_general` regarding `dylint_testing::ui_test_example`.
+        dylint_testing::ui::Test::src_base(env!("CARGO_PKG_NAME"), "ui_deny")
+            .dylint_toml(r#"overscoped_allow.levels = ["allow", "deny", "forbid"]"#)
+            .run();
+    }
+
     #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
     #[test]
     fn ui_general() {
@@ -533,6 +832,139 @@ mod test {
         dylint_testing::ui_test(env!("CARGO_PKG_NAME"), "ui_general");
     }
 
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn ui_general_audit() {
+        let _lock = MUTEX.lock().unwrap();
+
+        install_clippy();
+
+        let (file, temp_path) = NamedTempFile::new().unwrap().into_parts();
+        Command::new("cargo")
+            .args([
+                "clippy",
+                "--example=ui_general",
+                "--message-format=json",
+                "--",
+                "--force-warn=clippy::module-name-repetitions",
+                "--force-warn=clippy::unused-self",
+                "--force-warn=clippy::unwrap-used",
+                "--force-warn=clippy::wrong-self-convention",
+            ])
+            .stdout(file)
+            .assert()
+            .success();
+        set_var(
+            OVERSCOPED_ALLOW_PATH,
+            temp_path.to_string_lossy().to_string(),
+        );
+        let audit_path = NamedTempFile::new().unwrap().into_temp_path();
+        set_var(
+            OVERSCOPED_ALLOW_AUDIT,
+            audit_path.to_string_lossy().to_string(),
+        );
+        dylint_testing::ui_test(env!("CARGO_PKG_NAME"), "ui_general");
+        remove_var(OVERSCOPED_ALLOW_AUDIT);
+
+        let entries = read_to_string(&audit_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        let entry = |line: u64| {
+            entries
+                .iter()
+                .find(|entry| entry["line"] == line)
+                .unwrap_or_else(|| panic!("found no entry for line {line}: {entries:#?}"))
+        };
+
+        let dead_code = entry(1);
+        assert_eq!("dead_code", dead_code["lint"]);
+        assert_eq!(Value::Bool(true), dead_code["suppresses_nothing"]);
+
+        let unwrap_used = entry(29);
+        assert_eq!("clippy::unwrap_used", unwrap_used["lint"]);
+        assert_eq!(Value::Bool(false), unwrap_used["suppresses_nothing"]);
+        assert_eq!(31, unwrap_used["diagnostics"][0]["line"]);
+    }
+
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn ui_general_multiple_files() {
+        let _lock = MUTEX.lock().unwrap();
+
+        install_clippy();
+
+        let mut temp_paths = Vec::new();
+        for lints in [
+            [
+                "--force-warn=clippy::module-name-repetitions",
+                "--force-warn=clippy::unused-self",
+            ],
+            [
+                "--force-warn=clippy::unwrap-used",
+                "--force-warn=clippy::wrong-self-convention",
+            ],
+        ] {
+            let (file, temp_path) = NamedTempFile::new().unwrap().into_parts();
+            Command::new("cargo")
+                .args([
+                    "clippy",
+                    "--example=ui_general",
+                    "--message-format=json",
+                    "--",
+                ])
+                .args(lints)
+                .stdout(file)
+                .assert()
+                .success();
+            temp_paths.push(temp_path);
+        }
+        set_var(
+            OVERSCOPED_ALLOW_PATH,
+            join_paths(temp_paths.iter().map(|temp_path| &**temp_path)).unwrap(),
+        );
+        dylint_testing::ui_test(env!("CARGO_PKG_NAME"), "ui_general");
+    }
+
+    // `OVERSCOPED_ALLOW_PATH` is relative to the workspace root, but the test is run from
+    // a subdirectory.
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn ui_general_from_subdirectory() {
+        let _lock = MUTEX.lock().unwrap();
+
+        install_clippy();
+
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let tempdir = tempdir_in(manifest_dir).unwrap();
+        let warnings_json = tempdir.path().join("warnings.json");
+        let file = File::create(&warnings_json).unwrap();
+        Command::new("cargo")
+            .args([
+                "clippy",
+                "--example=ui_general",
+                "--message-format=json",
+                "--",
+                "--force-warn=clippy::module-name-repetitions",
+                "--force-warn=clippy::unused-self",
+                "--force-warn=clippy::unwrap-used",
+                "--force-warn=clippy::wrong-self-convention",
+            ])
+            .stdout(file)
+            .assert()
+            .success();
+        set_var(
+            OVERSCOPED_ALLOW_PATH,
+            warnings_json.strip_prefix(manifest_dir).unwrap(),
+        );
+
+        let cwd = current_dir().unwrap();
+        set_current_dir(manifest_dir.join("ui_general")).unwrap();
+        dylint_testing::ui_test(env!("CARGO_PKG_NAME"), manifest_dir.join("ui_general"));
+        set_current_dir(cwd).unwrap();
+    }
+
     #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
     #[test]
     fn ui_test() {
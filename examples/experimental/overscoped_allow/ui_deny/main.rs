@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+
+fn main() {}
+
+#[deny(clippy::unwrap_used)]
+fn deny_stmt() {
+    Some(()).unwrap();
+}
+
+#[forbid(clippy::unwrap_used)]
+fn forbid_block_expr() {
+    Some(()).unwrap()
+}
+
+#[allow(clippy::unwrap_used)]
+fn allow_stmt() {
+    Some(()).unwrap();
+}
+
+fn negative_deny_stmt() {
+    #[deny(clippy::unwrap_used)]
+    Some(()).unwrap();
+}
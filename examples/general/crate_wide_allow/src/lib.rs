@@ -98,6 +98,18 @@ mod test {
         dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ui");
     }
 
+    #[test]
+    fn span() {
+        let _lock = mutex::<maybe_return::Yes>();
+
+        dylint_testing::assert_span_snippet(
+            env!("CARGO_PKG_NAME"),
+            "#![allow(dead_code)]\n",
+            "crate_wide_allow",
+            "#![allow(dead_code)]",
+        );
+    }
+
     #[test]
     fn premise_warn() {
         test("--warn=clippy::assertions-on-constants", Assert::success);
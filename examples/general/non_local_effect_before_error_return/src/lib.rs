@@ -11,10 +11,14 @@ extern crate rustc_span;
 extern crate rustc_target;
 
 use clippy_utils::{diagnostics::span_lint_and_then, match_def_path};
+use dylint_linting::Profile;
 use rustc_errors::Diag;
-use rustc_hir::{def_id::LocalDefId, intravisit::FnKind};
+use rustc_hir::{
+    def_id::{DefId, LocalDefId},
+    intravisit::FnKind,
+};
 use rustc_index::bit_set::BitSet;
-use rustc_lint::{LateContext, LateLintPass};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
 use rustc_middle::{
     mir::{
         pretty::{write_mir_fn, PrettyPrintMirOptions},
@@ -23,7 +27,7 @@ use rustc_middle::{
     },
     ty,
 };
-use rustc_span::{sym, Span};
+use rustc_span::{sym, Span, Symbol};
 use serde::Deserialize;
 
 mod visit_error_paths;
@@ -78,18 +82,46 @@ dylint_linting::impl_late_lint! {
     /// ```
     ///
     /// ### Configuration
+    /// - `interior_mut_paths: Vec<String>` (default: the mutating methods of `Cell` and `RefCell`,
+    ///   and `RwLock::write`): Paths of methods that mutate through a shared reference, e.g.,
+    ///   `"core::cell::Cell::set"`. A call to one of these methods on a value reachable from a
+    ///   reference argument is reported like a call with a mutable reference. (`Mutex::lock` need
+    ///   not be listed; mutations through the guard it returns are already reported.)
     /// - `public_only: bool` (default `true`): Whether to check only publicly accessible functions.
     /// - `work_limit: u64` (default 500000): When exploring a function body, the maximum number of
     ///   times the search path is extended. Setting this to a higher number allows more bodies to
     ///   be explored exhaustively, but at the expense of greater runtime.
+    ///   If `work_limit` is not set, the `[profile]` table's `name` determines the default: 50000
+    ///   for `"fast"` and 5000000 for `"thorough"`.
+    ///   To see which functions reached the limit, set the environment variable
+    ///   `NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN_DEBUG_WORK_LIMIT=1`, or set
+    ///   `non_local_effect_before_error_return_debug_work_limit = true` in the `[instrumentation]`
+    ///   table. The lint then emits a note listing those functions once the crate has been checked.
     pub NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN,
     Warn,
     "non-local effects before return of an error",
     NonLocalEffectBeforeErrorReturn::new()
 }
 
+const DEFAULT_WORK_LIMIT: u64 = 500_000;
+
+const DEFAULT_INTERIOR_MUT_PATHS: &[&str] = &[
+    "core::cell::Cell::replace",
+    "core::cell::Cell::set",
+    "core::cell::Cell::swap",
+    "core::cell::Cell::take",
+    "core::cell::RefCell::borrow_mut",
+    "core::cell::RefCell::replace",
+    "core::cell::RefCell::replace_with",
+    "core::cell::RefCell::swap",
+    "core::cell::RefCell::take",
+    "std::sync::rwlock::RwLock::write",
+];
+
 #[derive(Deserialize)]
 struct Config {
+    #[serde(default = "default_interior_mut_paths")]
+    interior_mut_paths: Vec<String>,
     public_only: Option<bool>,
     work_limit: Option<u64>,
 }
@@ -97,20 +129,45 @@ struct Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            interior_mut_paths: default_interior_mut_paths(),
             public_only: Some(true),
-            work_limit: Some(500_000),
+            work_limit: None,
         }
     }
 }
 
+dylint_linting::dylint_validate_config!(env!("CARGO_PKG_NAME") => Config);
+
+fn default_interior_mut_paths() -> Vec<String> {
+    DEFAULT_INTERIOR_MUT_PATHS
+        .iter()
+        .copied()
+        .map(String::from)
+        .collect()
+}
+
 struct NonLocalEffectBeforeErrorReturn {
     config: Config,
+    work_limit: u64,
+    work_limit_reached: Vec<String>,
 }
 
 impl NonLocalEffectBeforeErrorReturn {
     pub fn new() -> Self {
+        let config: Config = dylint_linting::config_or_default(env!("CARGO_PKG_NAME"));
+        // An explicitly configured `work_limit` takes precedence over the profile.
+        let work_limit =
+            config
+                .work_limit
+                .unwrap_or_else(|| match dylint_linting::config_profile() {
+                    Some(Profile::Fast) => 50_000,
+                    Some(Profile::Thorough) => 5_000_000,
+                    None => DEFAULT_WORK_LIMIT,
+                });
         Self {
-            config: dylint_linting::config_or_default(env!("CARGO_PKG_NAME")),
+            config,
+            work_limit,
+            work_limit_reached: Vec::new(),
         }
     }
 }
@@ -151,7 +208,8 @@ impl<'tcx> LateLintPass<'tcx> for NonLocalEffectBeforeErrorReturn {
 
         let mir = cx.tcx.optimized_mir(local_def_id.to_def_id());
 
-        if enabled("DEBUG_MIR") {
+        if dylint_linting::instrumentation_enabled("NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN_DEBUG_MIR")
+        {
             let options = PrettyPrintMirOptions::from_cli(cx.tcx);
             write_mir_fn(
                 cx.tcx,
@@ -163,10 +221,8 @@ impl<'tcx> LateLintPass<'tcx> for NonLocalEffectBeforeErrorReturn {
             .unwrap();
         }
 
-        visit_error_paths(
-            self.config
-                .work_limit
-                .unwrap_or_else(|| Config::default().work_limit.unwrap()),
+        let work_limit_reached = visit_error_paths(
+            self.work_limit,
             cx,
             fn_kind,
             mir,
@@ -187,6 +243,25 @@ impl<'tcx> LateLintPass<'tcx> for NonLocalEffectBeforeErrorReturn {
                         );
                     }
 
+                    if !contributing_calls.contains(index)
+                        && let Some((func, func_span)) = is_call_with_interior_mut(
+                            cx,
+                            mir,
+                            &path[i..],
+                            &self.config.interior_mut_paths,
+                        )
+                    {
+                        span_lint_and_then(
+                            cx,
+                            NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN,
+                            func_span,
+                            format!(
+                                "call to `{func:?}` with interior mutability before error return"
+                            ),
+                            error_note(span),
+                        );
+                    }
+
                     let basic_block = &mir.basic_blocks[index];
                     for statement in basic_block.statements.iter().rev() {
                         if let Some(assign_span) = is_deref_assign(statement) {
@@ -202,6 +277,31 @@ impl<'tcx> LateLintPass<'tcx> for NonLocalEffectBeforeErrorReturn {
                 }
             },
         );
+
+        if work_limit_reached
+            && dylint_linting::instrumentation_enabled(
+                "NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN_DEBUG_WORK_LIMIT",
+            )
+        {
+            self.work_limit_reached
+                .push(cx.tcx.def_path_str(local_def_id.to_def_id()));
+        }
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        if self.work_limit_reached.is_empty() {
+            return;
+        }
+        cx.sess().dcx().note(format!(
+            "reached work limit ({}) while checking the following functions; they may have been \
+             analyzed incompletely:\n{}",
+            self.work_limit,
+            self.work_limit_reached
+                .iter()
+                .map(|name| format!("    `{name}`"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
     }
 }
 
@@ -250,6 +350,60 @@ fn is_call_with_mut_ref<'tcx>(
     }
 }
 
+// Interior mutation does not involve a mutable reference. So the receiver is followed
+// "widely" (see below), and the call is reported if the receiver could refer to memory reachable
+// from a reference argument or a constant.
+fn is_call_with_interior_mut<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir: &'tcx Body<'tcx>,
+    path: &[BasicBlock],
+    interior_mut_paths: &[String],
+) -> Option<(&'tcx Operand<'tcx>, Span)> {
+    let index = path[0];
+    let basic_block = &mir[index];
+    let terminator = basic_block.terminator();
+    if let TerminatorKind::Call {
+        func,
+        args,
+        fn_span,
+        ..
+    } = &terminator.kind
+        && let Some((def_id, _)) = func.const_fn_def()
+        && is_interior_mut_method(cx, def_id, interior_mut_paths)
+        && let Some(receiver_place) = args.first().and_then(|arg| arg.node.place())
+        && let (locals, constants) = follow_locals_and_constants(
+            cx,
+            mir,
+            path,
+            BitSet::new_empty(mir.local_decls.len()),
+            singleton(mir, receiver_place.local),
+        )
+        && (locals.iter().any(|local| is_ref_arg(mir, local))
+            || constants.iter().any(|constant| is_const_ref(constant)))
+    {
+        Some((func, *fn_span))
+    } else {
+        None
+    }
+}
+
+fn is_interior_mut_method(
+    cx: &LateContext<'_>,
+    def_id: DefId,
+    interior_mut_paths: &[String],
+) -> bool {
+    let def_path = cx.get_def_path(def_id);
+    interior_mut_paths
+        .iter()
+        .any(|path| def_path.iter().map(Symbol::as_str).eq(path.split("::")))
+}
+
+fn singleton(mir: &Body<'_>, local: Local) -> BitSet<Local> {
+    let mut locals = BitSet::new_empty(mir.local_decls.len());
+    locals.insert(local);
+    locals
+}
+
 // smoelius: Roughly, a "followed" local is assumed to refer to mutable memory. Locals are followed
 // "narrowly" or "widely," and functions are "narrowing," "width preserving," or "widening." If a
 // function outputs to a followed local, then the function's inputs are followed according to the
@@ -286,8 +440,6 @@ fn collect_locals_and_constants<'tcx>(
     args: impl Iterator<Item = &'tcx Operand<'tcx>>,
 ) -> (BitSet<Local>, Vec<&'tcx ConstOperand<'tcx>>) {
     let mut locals_narrowly = BitSet::new_empty(mir.local_decls.len());
-    let mut locals_widely = BitSet::new_empty(mir.local_decls.len());
-    let mut constants = Vec::new();
 
     for arg in args {
         if let Some(arg_place) = mut_ref_operand_place(cx, mir, arg) {
@@ -295,7 +447,25 @@ fn collect_locals_and_constants<'tcx>(
         }
     }
 
-    if locals_narrowly.is_empty() {
+    follow_locals_and_constants(
+        cx,
+        mir,
+        path,
+        locals_narrowly,
+        BitSet::new_empty(mir.local_decls.len()),
+    )
+}
+
+fn follow_locals_and_constants<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir: &'tcx Body<'tcx>,
+    path: &[BasicBlock],
+    mut locals_narrowly: BitSet<Local>,
+    mut locals_widely: BitSet<Local>,
+) -> (BitSet<Local>, Vec<&'tcx ConstOperand<'tcx>>) {
+    let mut constants = Vec::new();
+
+    if locals_narrowly.is_empty() && locals_widely.is_empty() {
         return (locals_narrowly, constants);
     }
 
@@ -387,6 +557,11 @@ fn is_mut_ref_arg<'tcx>(mir: &'tcx Body<'tcx>, local: Local) -> bool {
     (1..=mir.arg_count).contains(&local.into()) && is_mut_ref(mir.local_decls[local].ty)
 }
 
+// See the comment preceding `is_mut_ref_arg`.
+fn is_ref_arg<'tcx>(mir: &'tcx Body<'tcx>, local: Local) -> bool {
+    (1..=mir.arg_count).contains(&local.into()) && mir.local_decls[local].ty.is_ref()
+}
+
 fn is_const_ref(constant: &ConstOperand<'_>) -> bool {
     constant.ty().is_ref()
 }
@@ -427,12 +602,6 @@ fn error_note(span: Option<Span>) -> impl FnOnce(&mut Diag<'_, ()>) {
     }
 }
 
-#[must_use]
-fn enabled(opt: &str) -> bool {
-    let key = env!("CARGO_PKG_NAME").to_uppercase() + "_" + opt;
-    std::env::var(key).is_ok_and(|value| value != "0")
-}
-
 #[test]
 fn ui() {
     dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ui");
@@ -445,6 +614,23 @@ fn ui_public_only() {
         .run();
 }
 
+#[test]
+fn ui_fast_profile() {
+    dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "ui_fast_profile")
+        .dylint_toml(r#"profile.name = "fast""#)
+        .run();
+}
+
+#[test]
+fn ui_work_limit() {
+    dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "ui_work_limit")
+        .dylint_toml(
+            "non_local_effect_before_error_return.work_limit = 1\n\
+             instrumentation.non_local_effect_before_error_return_debug_work_limit = true",
+        )
+        .run();
+}
+
 #[test]
 fn ui_main_rs_equal() {
     let ui_main_rs = std::fs::read_to_string("ui/main.rs").unwrap();
@@ -32,21 +32,25 @@ use rustc_target::abi::VariantIdx;
 // contributing calls at each block, which appears exponential in the number of blocks. Thus, it is
 // not clear that this idea would be more efficient than simply considering all error paths.
 
+// Returns true if the work limit was reached while exploring the paths to any return.
 pub fn visit_error_paths<'tcx>(
     work_limit: u64,
     cx: &LateContext<'tcx>,
     fn_kind: FnKind<'tcx>,
     mir: &'tcx Body<'tcx>,
     visitor: impl Fn(&[BasicBlock], &BitSet<BasicBlock>, Option<Span>),
-) {
+) -> bool {
+    let mut work_limit_reached = false;
     for (index, basic_block) in mir.basic_blocks.iter_enumerated() {
         let terminator = basic_block.terminator();
         if terminator.kind == TerminatorKind::Return {
             let mut guide = Guide::new(work_limit, cx, fn_kind, mir, &visitor);
             let state = State::new();
             guide.visit_error_paths_to_block_terminator(&state, index);
+            work_limit_reached |= guide.work_limit_reached;
         }
     }
+    work_limit_reached
 }
 
 #[derive(Clone, Debug)]
@@ -119,6 +123,7 @@ struct Guide<'cx, 'tcx, V> {
     block_path: Vec<BasicBlock>,
     contributing_calls: BitSet<BasicBlock>,
     work: u64,
+    work_limit_reached: bool,
 }
 
 impl<'cx, 'tcx, V> Guide<'cx, 'tcx, V>
@@ -142,6 +147,7 @@ where
             block_path: Vec::with_capacity(mir.basic_blocks.len()),
             contributing_calls: BitSet::new_empty(mir.basic_blocks.len()),
             work: 0,
+            work_limit_reached: false,
         }
     }
 
@@ -253,6 +259,10 @@ where
     // Emits a warning and returns true if work limit has been reached.
     fn work(&mut self) -> bool {
         if self.work >= self.work_limit {
+            if self.work_limit_reached {
+                return true;
+            }
+            self.work_limit_reached = true;
             let name = match self.fn_kind {
                 FnKind::ItemFn(ident, _, _) | FnKind::Method(ident, _) => format!("`{ident}`"),
                 FnKind::Closure => "closure".to_owned(),
@@ -0,0 +1,70 @@
+fn main() {}
+
+// The number of paths through `many_branches` doubles with each `if`, so exploring them exceeds the
+// `fast` profile's work limit of 50000.
+pub fn many_branches(flags: [bool; 20]) -> Result<(), ()> {
+    if flags[0] {
+        tick();
+    }
+    if flags[1] {
+        tick();
+    }
+    if flags[2] {
+        tick();
+    }
+    if flags[3] {
+        tick();
+    }
+    if flags[4] {
+        tick();
+    }
+    if flags[5] {
+        tick();
+    }
+    if flags[6] {
+        tick();
+    }
+    if flags[7] {
+        tick();
+    }
+    if flags[8] {
+        tick();
+    }
+    if flags[9] {
+        tick();
+    }
+    if flags[10] {
+        tick();
+    }
+    if flags[11] {
+        tick();
+    }
+    if flags[12] {
+        tick();
+    }
+    if flags[13] {
+        tick();
+    }
+    if flags[14] {
+        tick();
+    }
+    if flags[15] {
+        tick();
+    }
+    if flags[16] {
+        tick();
+    }
+    if flags[17] {
+        tick();
+    }
+    if flags[18] {
+        tick();
+    }
+    if flags[19] {
+        tick();
+    }
+    Err(())
+}
+
+#[inline(never)]
+fn tick() {}
@@ -276,3 +276,39 @@ pub mod public_only {
         Err(VarError::NotPresent)
     }
 }
+
+pub mod interior_mut {
+    use std::cell::Cell;
+
+    pub struct Account {
+        balance: Cell<i64>,
+    }
+
+    pub struct InsufficientBalance;
+
+    impl Account {
+        pub fn withdraw(&self, amount: i64) -> Result<i64, InsufficientBalance> {
+            self.balance.set(self.balance.get() - amount);
+            if self.balance.get() < 0 {
+                return Err(InsufficientBalance);
+            }
+            Ok(self.balance.get())
+        }
+
+        pub fn safe_withdraw(&self, amount: i64) -> Result<i64, InsufficientBalance> {
+            let new_balance = self.balance.get() - amount;
+            if new_balance < 0 {
+                return Err(InsufficientBalance);
+            }
+            self.balance.set(new_balance);
+            Ok(new_balance)
+        }
+    }
+
+    // Should not lint, because the `Cell` is local.
+    pub fn local_cell() -> Result<(), InsufficientBalance> {
+        let cell = Cell::new(0);
+        cell.set(1);
+        Err(InsufficientBalance)
+    }
+}
@@ -0,0 +1,14 @@
+fn main() {}
+
+// With a work limit of 1, exploring any path longer than one block reaches the limit.
+pub fn error_on_branch(flag: bool) -> Result<(), ()> {
+    if flag {
+        return Err(());
+    }
+    Ok(())
+}
+
+// The only path ends in `Ok`, so it is not explored, and the limit is not reached.
+pub fn no_error() -> Result<(), ()> {
+    Ok(())
+}
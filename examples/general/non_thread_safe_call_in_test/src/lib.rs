@@ -14,6 +14,8 @@ extern crate rustc_session;
 mod blacklist;
 mod late;
 
+pub use late::NON_THREAD_SAFE_CALL_IN_TEST;
+
 #[cfg_attr(not(feature = "rlib"), no_mangle)]
 pub fn register_lints(_sess: &rustc_session::Session, lint_store: &mut rustc_lint::LintStore) {
     lint_store.register_lints(&[late::NON_THREAD_SAFE_CALL_IN_TEST]);
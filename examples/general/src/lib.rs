@@ -18,4 +18,20 @@ pub fn register_lints(sess: &rustc_session::Session, lint_store: &mut rustc_lint
     non_local_effect_before_error_return::register_lints(sess, lint_store);
     non_thread_safe_call_in_test::register_lints(sess, lint_store);
     wrong_serialize_struct_arg::register_lints(sess, lint_store);
+
+    // Please keep the following lints sorted by crate name.
+    dylint_linting::register_group(
+        lint_store,
+        "general",
+        &[
+            abs_home_path::ABS_HOME_PATH,
+            await_holding_span_guard::AWAIT_HOLDING_SPAN_GUARD,
+            basic_dead_store::BASIC_DEAD_STORE,
+            crate_wide_allow::CRATE_WIDE_ALLOW,
+            incorrect_matches_operation::INCORRECT_MATCHES_OPERATION,
+            non_local_effect_before_error_return::NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN,
+            non_thread_safe_call_in_test::NON_THREAD_SAFE_CALL_IN_TEST,
+            wrong_serialize_struct_arg::WRONG_SERIALIZE_STRUCT_ARG,
+        ],
+    );
 }
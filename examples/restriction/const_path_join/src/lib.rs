@@ -166,3 +166,12 @@ fn is_lit_string(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<String> {
 fn ui() {
     dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ui");
 }
+
+// `ui_manifest_path/main.rs` is not an example target, so `camino` is available to it only through
+// `manifest_path_fixture`'s dependencies.
+#[test]
+fn ui_manifest_path() {
+    dylint_testing::ui::Test::src_base(env!("CARGO_PKG_NAME"), "ui_manifest_path")
+        .manifest_path("manifest_path_fixture/Cargo.toml")
+        .run();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = camino::Utf8Path::new("..").join("target");
+}
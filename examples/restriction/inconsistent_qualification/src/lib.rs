@@ -2,11 +2,13 @@
 #![feature(let_chains)]
 #![warn(unused_extern_crates)]
 
+extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
-use clippy_utils::diagnostics::span_lint_and_note;
+use clippy_utils::diagnostics::span_lint_and_then;
+use rustc_errors::Applicability;
 use rustc_hir::{
     def::{DefKind, Res},
     def_id::{DefId, CRATE_DEF_ID},
@@ -217,13 +219,25 @@ impl<'tcx> Visitor<'tcx> for UseVisitor<'_, 'tcx, '_> {
                     )
                 }
             };
-            span_lint_and_note(
+            span_lint_and_then(
                 self.cx,
                 INCONSISTENT_QUALIFICATION,
                 span,
                 "inconsistent qualification",
-                Some(item.span),
-                msg,
+                |diag| {
+                    diag.span_note(item.span, msg);
+                    // The import brings `item.ident` into scope, so the qualified path can be
+                    // replaced with it. When only other items from the module were imported, no
+                    // suggestion is made: removing the import would break the paths that use it.
+                    if let PathMatch::Prefix(_) = path_match {
+                        diag.span_suggestion(
+                            span,
+                            "use the imported name",
+                            item.ident.to_string(),
+                            Applicability::MachineApplicable,
+                        );
+                    }
+                },
             );
             self.diagnostic_emitted = true;
         }
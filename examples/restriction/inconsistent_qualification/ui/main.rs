@@ -1,3 +1,4 @@
+// run-rustfix
 #![expect(dead_code)]
 
 use std::env::var;
@@ -100,10 +101,10 @@ mod trait_path {
 }
 
 mod relative_module_path {
-    #[expect(unused_imports)]
     use bar::baz;
 
     fn foo() {
+        baz::qux();
         bar::baz::qux()
     }
 
@@ -24,7 +24,10 @@ use clippy_utils::usage::{local_used_after_expr, local_used_in};
 use clippy_utils::{higher, is_adjusted, path_to_local, path_to_local_id};
 use rustc_errors::Applicability;
 use rustc_hir::def_id::DefId;
-use rustc_hir::{BindingMode, Expr, ExprKind, FnRetTy, Param, PatKind, QPath, Safety, TyKind};
+use rustc_hir::{
+    BindingMode, BorrowKind, Expr, ExprKind, FnRetTy, HirId, Mutability, Param, PatKind, QPath,
+    Safety, TyKind, UnOp,
+};
 use rustc_infer::infer::TyCtxtInferExt;
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::{
@@ -33,9 +36,10 @@ use rustc_middle::ty::{
     TypeckResults,
 };
 use rustc_session::declare_lint_pass;
-use rustc_span::symbol::sym;
+use rustc_span::{symbol::sym, Span, Symbol};
 use rustc_target::spec::abi::Abi;
 use rustc_trait_selection::error_reporting::InferCtxtErrorExt as _;
+use serde::Deserialize;
 
 use clippy_utils::{
     get_parent_expr,
@@ -47,14 +51,16 @@ use rustc_middle::ty::adjustment::{
     Adjust, Adjustment, AutoBorrow, AutoBorrowMutability, OverloadedDeref,
 };
 
-dylint_linting::declare_late_lint! {
+dylint_linting::impl_late_lint! {
     /// ### What it does
     /// This is essentially a ref-aware fork of Clippy's [`redundant_closure_for_method_calls`]
     /// lint. It suggests to remove a closure when made possible by a use of `as_ref`, `as_mut`,
     /// `as_deref`, or `as_deref_mut`.
     ///
     /// ### Known problems
-    /// Currently works only for [`Option`]s.
+    /// - Currently works only for [`Option`]s.
+    /// - For function calls (see "Configuration" below), only calls to function items and function
+    ///   pointers are considered.
     ///
     /// ### Example
     /// ```rust
@@ -67,11 +73,27 @@ dylint_linting::declare_late_lint! {
     /// Some(String::from("a")).as_deref().map(str::to_uppercase);
     /// ```
     ///
+    /// ### Configuration
+    /// - `include_function_calls: bool` (default `false`): Whether to also check closures that
+    ///   call a function, e.g., `Some(String::from("a")).map(|s| f(&s))`, which could be written
+    ///   `Some(String::from("a")).as_ref().map(f)`.
+    ///
     /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
     /// [`redundant_closure_for_method_calls`]: https://rust-lang.github.io/rust-clippy/master/#redundant_closure_for_method_calls
     pub REF_AWARE_REDUNDANT_CLOSURE_FOR_METHOD_CALLS,
     Warn,
-    "a ref-aware fork of `redundant_closure_for_method_calls`"
+    "a ref-aware fork of `redundant_closure_for_method_calls`",
+    @config = Config,
+    |config| RefAwareRedundantClosureForMethodCalls { config }
+}
+
+#[derive(Default, Deserialize)]
+struct Config {
+    include_function_calls: bool,
+}
+
+struct RefAwareRedundantClosureForMethodCalls {
+    config: Config,
 }
 
 impl<'tcx> LateLintPass<'tcx> for RefAwareRedundantClosureForMethodCalls {
@@ -121,81 +143,56 @@ impl<'tcx> LateLintPass<'tcx> for RefAwareRedundantClosureForMethodCalls {
             return;
         }
 
-        #[allow(clippy::single_match)]
         match body.value.kind {
-            /* ExprKind::Call(callee, args)
-                if matches!(
-                    callee.kind,
-                    ExprKind::Path(QPath::Resolved(..) | QPath::TypeRelative(..))
-                ) =>
+            ExprKind::Call(callee, args)
+                if self.config.include_function_calls
+                    && matches!(
+                        callee.kind,
+                        ExprKind::Path(QPath::Resolved(..) | QPath::TypeRelative(..))
+                    ) =>
             {
                 let callee_ty = typeck.expr_ty(callee).peel_refs();
-                if matches!(type_diagnostic_name(cx, callee_ty), Some(sym::Arc | sym::Rc))
-                    || !check_inputs(typeck, body.params, None, args)
-                {
-                    return;
-                }
                 let callee_ty_adjusted = typeck
                     .expr_adjustments(callee)
                     .last()
                     .map_or(callee_ty, |a| a.target.peel_refs());
 
+                // Unlike Clippy's `redundant_closure`, consider only function items and
+                // function pointers. Both are `Copy`, so replacing the closure with the callee
+                // never moves the callee.
                 let sig = match callee_ty_adjusted.kind() {
-                    ty::FnDef(def, _) => cx.tcx.fn_sig(def).skip_binder().skip_binder(),
-                    ty::FnPtr(sig) => sig.skip_binder(),
-                    ty::Closure(_, subs) => cx
-                        .tcx
-                        .signature_unclosure(subs.as_closure().sig(), Unsafety::Normal)
-                        .skip_binder(),
-                    _ => {
-                        if typeck.type_dependent_def_id(body.value.hir_id).is_some()
-                            && let subs = typeck.node_args(body.value.hir_id)
-                            && let output = typeck.expr_ty(body.value)
-                            && let ty::Tuple(tys) = *subs.type_at(1).kind()
-                        {
-                            cx.tcx.mk_fn_sig(tys, output, false, Unsafety::Normal, Abi::Rust)
-                        } else {
-                            return;
-                        }
-                    },
+                    ty::FnDef(def_id, _) => cx.tcx.fn_sig(*def_id).skip_binder().skip_binder(),
+                    ty::FnPtr(..) => callee_ty_adjusted.fn_sig(cx.tcx).skip_binder(),
+                    _ => return,
                 };
-                if check_sig(cx, closure, sig)
+
+                if let Some(method_name) = check_inputs(cx, body.params, None, args)
+                    && let Some((parent_method_name, parent_method_call_span)) =
+                        option_method_call(cx, expr)
+                    && check_sig(cx, closure, sig)
                     && let generic_args = typeck.node_args(callee.hir_id)
                     // Given some trait fn `fn f() -> ()` and some type `T: Trait`, `T::f` is not
                     // `'static` unless `T: 'static`. The cast `T::f as fn()` will, however, result
                     // in a type which is `'static`.
                     // For now ignore all callee types which reference a type parameter.
                     && !generic_args.types().any(|t| matches!(t.kind(), ty::Param(_)))
+                    && let Some(snippet) = snippet_opt(cx, callee.span)
                 {
-                    span_lint_and_then(cx, REDUNDANT_CLOSURE, expr.span, "redundant closure", |diag| {
-                        if let Some(mut snippet) = snippet_opt(cx, callee.span) {
-                            if let Ok((ClosureKind::FnMut, _)) = cx.tcx.infer_ctxt().build().type_implements_fn_trait(
-                                cx.param_env,
-                                Binder::bind_with_vars(callee_ty_adjusted, List::empty()),
-                                ImplPolarity::Positive,
-                            ) && path_to_local(callee).map_or(false, |l| {
-                                local_used_in(cx, l, args) || local_used_after_expr(cx, l, expr)
-                            }) {
-                                // Mutable closure is used after current expr; we cannot consume it.
-                                snippet = format!("&mut {snippet}");
-                            }
-                            diag.span_suggestion(
-                                expr.span,
-                                "replace the closure with the function itself",
-                                snippet,
-                                Applicability::MachineApplicable,
-                            );
-                        }
-                    });
+                    span_lint_and_sugg(
+                        cx,
+                        REF_AWARE_REDUNDANT_CLOSURE_FOR_METHOD_CALLS,
+                        parent_method_call_span,
+                        "redundant closure",
+                        "replace the closure with the function itself",
+                        format!(".{method_name}().{parent_method_name}({snippet})"),
+                        Applicability::MachineApplicable,
+                    );
                 }
-            }, */
+            }
             ExprKind::MethodCall(path, self_, args, _) => {
                 if let Some(method_name) = check_inputs(cx, body.params, Some(self_), args)
-                    && let Some(parent_expr) = get_parent_expr(cx, expr)
-                    && let ExprKind::MethodCall(parent_path, parent_receiver, _, span) =
-                        parent_expr.kind
-                    && let parent_receiver_ty = cx.typeck_results().expr_ty(parent_receiver)
-                    && is_type_diagnostic_item(cx, parent_receiver_ty, sym::Option)
+                    && let Some((parent_method_name, parent_method_call_span)) =
+                        option_method_call(cx, expr)
                     && let Some(method_def_id) = typeck.type_dependent_def_id(body.value.hir_id)
                     && check_sig(
                         cx,
@@ -203,10 +200,6 @@ impl<'tcx> LateLintPass<'tcx> for RefAwareRedundantClosureForMethodCalls {
                         cx.tcx.fn_sig(method_def_id).skip_binder().skip_binder(),
                     )
                 {
-                    let parent_method_call_span = trim_span(
-                        cx.sess().source_map(),
-                        span.with_lo(parent_receiver.span.hi()),
-                    );
                     span_lint_and_then(
                         cx,
                         REF_AWARE_REDUNDANT_CLOSURE_FOR_METHOD_CALLS,
@@ -219,8 +212,8 @@ impl<'tcx> LateLintPass<'tcx> for RefAwareRedundantClosureForMethodCalls {
                                 parent_method_call_span,
                                 "replace the closure with the method itself",
                                 format!(
-                                    ".{method_name}().{}({name}::{})",
-                                    parent_path.ident.name, path.ident.name
+                                    ".{method_name}().{parent_method_name}({name}::{})",
+                                    path.ident.name
                                 ),
                                 Applicability::MachineApplicable,
                             );
@@ -233,6 +226,24 @@ impl<'tcx> LateLintPass<'tcx> for RefAwareRedundantClosureForMethodCalls {
     }
 }
 
+/// If `expr` is the argument of a method call on an `Option`, returns the called method's name and
+/// the span from the end of the receiver to the end of the call.
+fn option_method_call(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<(Symbol, Span)> {
+    if let Some(parent_expr) = get_parent_expr(cx, expr)
+        && let ExprKind::MethodCall(parent_path, parent_receiver, _, span) = parent_expr.kind
+        && let parent_receiver_ty = cx.typeck_results().expr_ty(parent_receiver)
+        && is_type_diagnostic_item(cx, parent_receiver_ty, sym::Option)
+    {
+        let parent_method_call_span = trim_span(
+            cx.sess().source_map(),
+            span.with_lo(parent_receiver.span.hi()),
+        );
+        Some((parent_path.ident.name, parent_method_call_span))
+    } else {
+        None
+    }
+}
+
 fn check_inputs(
     cx: &LateContext<'_>,
     params: &[Param<'_>],
@@ -244,12 +255,14 @@ fn check_inputs(
             .iter()
             .zip(self_arg.into_iter().chain(args))
             .map(|(p, arg)| {
-                if matches!(
-                    p.pat.kind,
-                    PatKind::Binding(BindingMode::NONE | BindingMode::MUT, id, _, None)
-                    if path_to_local_id(arg, id)
-                ) {
-                    method_name_from_adjustments(cx, cx.typeck_results().expr_adjustments(arg))
+                if let PatKind::Binding(BindingMode::NONE | BindingMode::MUT, id, _, None) =
+                    p.pat.kind
+                {
+                    if path_to_local_id(arg, id) {
+                        method_name_from_adjustments(cx, cx.typeck_results().expr_adjustments(arg))
+                    } else {
+                        method_name_from_explicit_ref(cx, arg, id)
+                    }
                 } else {
                     None
                 }
@@ -295,6 +308,50 @@ fn method_name_from_adjustments<'tcx>(
     }
 }
 
+// Unlike a method's receiver, a function's argument is never auto-referenced. So in a
+// closure like `|s| f(&s)`, the reference is explicit. The `&s` may still be deref-coerced, e.g.,
+// from `&String` to `&str`.
+fn method_name_from_explicit_ref(
+    cx: &LateContext<'_>,
+    arg: &Expr<'_>,
+    id: HirId,
+) -> Option<&'static str> {
+    let typeck = cx.typeck_results();
+    match arg.kind {
+        ExprKind::AddrOf(BorrowKind::Ref, mutability, inner) if path_to_local_id(inner, id) => {
+            match typeck.expr_adjustments(arg) {
+                [] => Some(match mutability {
+                    Mutability::Mut => "as_mut",
+                    Mutability::Not => "as_ref",
+                }),
+                [Adjustment {
+                    kind: Adjust::Deref(None),
+                    ..
+                }, Adjustment {
+                    kind: Adjust::Deref(Some(OverloadedDeref { .. })),
+                    ..
+                }, Adjustment {
+                    kind: Adjust::Borrow(AutoBorrow::Ref(mutability)),
+                    ..
+                }] => Some(match mutability {
+                    AutoBorrowMutability::Mut { .. } => "as_deref_mut",
+                    AutoBorrowMutability::Not => "as_deref",
+                }),
+                _ => None,
+            }
+        }
+        ExprKind::Unary(UnOp::Deref, inner)
+            if path_to_local_id(inner, id)
+                && matches!(typeck.expr_ty(inner).kind(), ty::Ref(_, _, Mutability::Not))
+                && typeck.expr_adjustments(arg).is_empty()
+                && is_copy(cx, typeck.expr_ty(arg)) =>
+        {
+            Some("copied")
+        }
+        _ => None,
+    }
+}
+
 fn check_sig<'tcx>(
     cx: &LateContext<'tcx>,
     closure: ClosureArgs<TyCtxt<'tcx>>,
@@ -402,6 +459,13 @@ fn ui_eta() {
     dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "eta");
 }
 
+#[test]
+fn ui_function_calls() {
+    dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "function_calls")
+        .dylint_toml("ref_aware_redundant_closure_for_method_calls.include_function_calls = true")
+        .run();
+}
+
 #[test]
 fn ui_ref_aware() {
     dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ref_aware");
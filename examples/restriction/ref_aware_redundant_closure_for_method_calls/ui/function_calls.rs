@@ -0,0 +1,43 @@
+// run-rustfix
+
+fn is_empty(s: &String) -> bool {
+    s.is_empty()
+}
+
+fn to_uppercase(s: &str) -> String {
+    s.to_uppercase()
+}
+
+fn push(s: &mut String) {
+    s.push('a');
+}
+
+fn make_ascii_uppercase(s: &mut str) {
+    s.make_ascii_uppercase();
+}
+
+fn is_uppercase(c: char) -> bool {
+    c.is_uppercase()
+}
+
+fn main() {
+    let _ = Some(String::from("a")).map(|s| is_empty(&s));
+    let _ = Some(String::from("a")).map(|s| to_uppercase(&s));
+    let _ = Some(String::from("a")).map(|mut s| push(&mut s));
+    let _ = Some(String::from("a")).map(|mut s| make_ascii_uppercase(&mut s));
+
+    let _ = "a".chars().peekable().peek().map(|c| is_uppercase(*c));
+
+    let f: fn(&str) -> String = to_uppercase;
+    let _ = Some(String::from("a")).map(|s| f(&s));
+
+    // negative test: no reference
+    let _ = Some('a').map(|c| is_uppercase(c));
+
+    // negative test: closure
+    let g = |s: &str| s.len();
+    let _ = Some(String::from("a")).map(|s| g(&s));
+
+    // negative test: `Iterator`
+    let _ = [String::from("a")].into_iter().map(|s| is_empty(&s));
+}
@@ -2,6 +2,7 @@
 #![feature(let_chains)]
 #![warn(unused_extern_crates)]
 
+extern crate rustc_ast;
 extern crate rustc_data_structures;
 extern crate rustc_errors;
 extern crate rustc_hir;
@@ -12,16 +13,18 @@ use clippy_utils::{
     diagnostics::span_lint_and_sugg, path_to_local_id, peel_middle_ty_refs, source::snippet,
     ty::is_copy,
 };
+use rustc_ast::LitKind;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::Applicability;
 use rustc_hir::{
+    def::CtorKind,
     def_id::LocalDefId,
     intravisit::{walk_expr, FnKind, Visitor},
     BindingMode, Body, ByRef, Expr, ExprKind, FnDecl, HirId, Node, Pat, PatKind, UnOp,
 };
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::{self, adjustment::Adjust};
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 use serde::Deserialize;
 use std::{cmp::min, fmt::Write};
 
@@ -36,8 +39,10 @@ dylint_linting::impl_late_lint! {
     ///
     /// ### Known problems
     /// - Currently only checks closure parameters (not, e.g., match patterns).
-    /// - Currently only suggests destructuring references and tuples (not, e.g., arrays or
-    ///   structs).
+    /// - Currently only suggests destructuring references, tuples, structs, and arrays (not, e.g.,
+    ///   enums or slices).
+    /// - For the lint to suggest destructuring a struct or array, the ident must be used only in
+    ///   field accesses (e.g., `x.field`) or constant indexing expressions (e.g., `x[0]`).
     /// - For the lint to suggest destructuring a reference, the idents involved must not use `ref`
     ///   annotations.
     ///
@@ -56,6 +61,9 @@ dylint_linting::impl_late_lint! {
     /// - `explicit_deref_check: bool` (default `true`): By default, `suboptimal_pattern` will not
     ///   suggest to destructure a reference unless it would eliminate at least one explicit
     ///   dereference. Setting `explicit_deref_check` to `false` disables this check.
+    /// - `copy_only: bool` (default `false`): By default, `suboptimal_pattern` will suggest to
+    ///   destructure a reference regardless of whether the referent type is `Copy`. Setting
+    ///   `copy_only` to `true` restricts such suggestions to `Copy` referent types.
     ///
     /// [pattern-type-mismatch]: https://rust-lang.github.io/rust-clippy/master/#pattern_type_mismatch
     pub SUBOPTIMAL_PATTERN,
@@ -65,18 +73,23 @@ dylint_linting::impl_late_lint! {
 }
 
 #[derive(Deserialize)]
+#[serde(default)]
 struct Config {
+    copy_only: bool,
     explicit_deref_check: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            copy_only: false,
             explicit_deref_check: true,
         }
     }
 }
 
+dylint_linting::dylint_config_keys!("copy_only", "explicit_deref_check");
+
 struct SuboptimalPattern {
     config: Config,
 }
@@ -118,24 +131,31 @@ impl<'tcx> LateLintPass<'tcx> for SuboptimalPattern {
                 let pat_ty = cx.typeck_results().node_type(pat.hir_id);
                 let (referent_ty, n_refs) = peel_middle_ty_refs(pat_ty);
 
-                if let ty::Tuple(tys) = referent_ty.kind()
-                    && let PatKind::Binding(BindingMode(ByRef::No, _), hir_id, ident, None) =
-                        pat.kind
-                    && let Some(projections) = exclusively_projected(cx.tcx, hir_id, body.value)
+                if self.config.copy_only && n_refs > 0 && !is_copy(cx, referent_ty) {
+                    return true;
+                }
+
+                if let PatKind::Binding(BindingMode(ByRef::No, _), hir_id, ident, None) = pat.kind
+                    && let Some(projections) = exclusively_projected(cx, hir_id, body.value)
+                    && let Some((what, aggregate_pattern)) = build_aggregate_pattern(
+                        cx,
+                        pat.hir_id,
+                        referent_ty,
+                        ident.name.as_str(),
+                        &projections,
+                    )
                 {
-                    let tuple_pattern =
-                        build_tuple_pattern(ident.name.as_str(), &projections, tys.len());
                     let pattern = format!(
                         "{:&>width$}{}",
                         "",
-                        tuple_pattern,
+                        aggregate_pattern,
                         width = if is_copy(cx, referent_ty) { n_refs } else { 0 }
                     );
                     span_lint_and_sugg(
                         cx,
                         SUBOPTIMAL_PATTERN,
                         pat.span,
-                        "could destructure tuple",
+                        format!("could destructure {what}"),
                         "use something like",
                         pattern,
                         Applicability::HasPlaceholders,
@@ -178,13 +198,19 @@ impl<'tcx> LateLintPass<'tcx> for SuboptimalPattern {
     }
 }
 
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum Projection {
+    Field(Symbol),
+    Index(u128),
+}
+
 fn exclusively_projected<'tcx>(
-    tcx: ty::TyCtxt<'tcx>,
+    cx: &LateContext<'tcx>,
     hir_id: HirId,
     expr: &'tcx Expr<'tcx>,
-) -> Option<FxHashSet<usize>> {
+) -> Option<FxHashSet<Projection>> {
     let mut visitor = ProjectionVisitor {
-        tcx,
+        cx,
         hir_id,
         projections: Some(FxHashSet::default()),
     };
@@ -192,22 +218,21 @@ fn exclusively_projected<'tcx>(
     visitor.projections
 }
 
-struct ProjectionVisitor<'tcx> {
-    tcx: ty::TyCtxt<'tcx>,
+struct ProjectionVisitor<'cx, 'tcx> {
+    cx: &'cx LateContext<'tcx>,
     hir_id: HirId,
-    projections: Option<FxHashSet<usize>>,
+    projections: Option<FxHashSet<Projection>>,
 }
 
-impl<'tcx> Visitor<'tcx> for ProjectionVisitor<'tcx> {
+impl<'tcx> Visitor<'tcx> for ProjectionVisitor<'_, 'tcx> {
     fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
         if path_to_local_id(expr, self.hir_id) {
-            let node = self.tcx.parent_hir_node(expr.hir_id);
-            if let Node::Expr(Expr {
-                kind: ExprKind::Field(_, ident),
-                ..
-            }) = node
-                && let Ok(projection) = ident.name.as_str().parse::<usize>()
-            {
+            let projection = if only_builtin_derefs(self.cx, expr) {
+                projection(self.cx, expr)
+            } else {
+                None
+            };
+            if let Some(projection) = projection {
                 self.projections
                     .as_mut()
                     .map(|projections| projections.insert(projection));
@@ -219,6 +244,124 @@ impl<'tcx> Visitor<'tcx> for ProjectionVisitor<'tcx> {
     }
 }
 
+fn projection(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<Projection> {
+    let Node::Expr(parent_expr) = cx.tcx.parent_hir_node(expr.hir_id) else {
+        return None;
+    };
+    match parent_expr.kind {
+        ExprKind::Field(_, ident) => Some(Projection::Field(ident.name)),
+        ExprKind::Index(base, index, _) => {
+            if base.hir_id == expr.hir_id
+                && let ExprKind::Lit(lit) = index.kind
+                && let LitKind::Int(index, _) = lit.node
+            {
+                Some(Projection::Index(index.get()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// A destructuring pattern can "see through" references only. So verify that any
+// autoderefs applied to `expr` (e.g., as part of a field access) do not pass through, e.g., a
+// `Box`.
+fn only_builtin_derefs(cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+    let typeck_results = cx.typeck_results();
+    let (unadjusted_ty, _) = peel_middle_ty_refs(typeck_results.expr_ty(expr));
+    let (adjusted_ty, _) = peel_middle_ty_refs(typeck_results.expr_ty_adjusted(expr));
+    unadjusted_ty == adjusted_ty
+}
+
+fn build_aggregate_pattern<'tcx>(
+    cx: &LateContext<'tcx>,
+    hir_id: HirId,
+    ty: ty::Ty<'tcx>,
+    ident: &str,
+    projections: &FxHashSet<Projection>,
+) -> Option<(&'static str, String)> {
+    match ty.kind() {
+        ty::Tuple(tys) => {
+            let indices = field_indices(projections)?;
+            Some(("tuple", build_tuple_pattern(ident, &indices, tys.len())))
+        }
+        ty::Adt(adt_def, _)
+            if adt_def.is_struct() && !adt_def.has_dtor(cx.tcx) && !projections.is_empty() =>
+        {
+            let variant = adt_def.non_enum_variant();
+            // A `#[non_exhaustive]` struct defined in another crate cannot be
+            // destructured without `..`. A simpler rule is to skip such structs entirely.
+            if variant.is_field_list_non_exhaustive() && !adt_def.did().is_local() {
+                return None;
+            }
+            let path = cx.tcx.def_path_str(adt_def.did());
+            if variant.ctor_kind() == Some(CtorKind::Fn) {
+                // A tuple struct pattern must mention every field, so every field must
+                // be visible.
+                let module = cx.tcx.parent_module(hir_id).to_def_id();
+                if !variant
+                    .fields
+                    .iter()
+                    .all(|field| field.vis.is_accessible_from(module, cx.tcx))
+                {
+                    return None;
+                }
+                let indices = field_indices(projections)?;
+                Some((
+                    "struct",
+                    format!(
+                        "{path}{}",
+                        build_tuple_pattern(ident, &indices, variant.fields.len())
+                    ),
+                ))
+            } else {
+                let names = projections
+                    .iter()
+                    .map(|projection| match projection {
+                        Projection::Field(name) => Some(*name),
+                        Projection::Index(_) => None,
+                    })
+                    .collect::<Option<FxHashSet<_>>>()?;
+                let fields = variant
+                    .fields
+                    .iter()
+                    .map(|field| field.name)
+                    .filter(|name| names.contains(name))
+                    .collect::<Vec<_>>();
+                if fields.len() != names.len() {
+                    return None;
+                }
+                Some((
+                    "struct",
+                    build_struct_pattern(&path, ident, &fields, variant.fields.len()),
+                ))
+            }
+        }
+        ty::Array(..) if !projections.is_empty() => {
+            let indices = projections
+                .iter()
+                .map(|projection| match projection {
+                    Projection::Index(index) => usize::try_from(*index).ok(),
+                    Projection::Field(_) => None,
+                })
+                .collect::<Option<FxHashSet<_>>>()?;
+            Some(("array", build_array_pattern(ident, &indices)))
+        }
+        _ => None,
+    }
+}
+
+fn field_indices(projections: &FxHashSet<Projection>) -> Option<FxHashSet<usize>> {
+    projections
+        .iter()
+        .map(|projection| match projection {
+            Projection::Field(name) => name.as_str().parse::<usize>().ok(),
+            Projection::Index(_) => None,
+        })
+        .collect()
+}
+
 fn build_tuple_pattern(ident: &str, projections: &FxHashSet<usize>, size: usize) -> String {
     let mut buf = "(".to_owned();
     for i in 0..size {
@@ -237,6 +380,36 @@ fn build_tuple_pattern(ident: &str, projections: &FxHashSet<usize>, size: usize)
     buf
 }
 
+fn build_struct_pattern(path: &str, ident: &str, fields: &[Symbol], size: usize) -> String {
+    let mut buf = format!("{path} {{ ");
+    for (i, field) in fields.iter().enumerate() {
+        write!(buf, "{field}: {ident}_{field}").unwrap();
+        if i + 1 < fields.len() {
+            write!(buf, ", ").unwrap();
+        }
+    }
+    if fields.len() < size {
+        write!(buf, ", ..").unwrap();
+    }
+    write!(buf, " }}").unwrap();
+    buf
+}
+
+// The array's length is not needed. A trailing `..` matches whatever elements remain.
+fn build_array_pattern(ident: &str, indices: &FxHashSet<usize>) -> String {
+    let size = indices.iter().max().map_or(0, |&max| max + 1);
+    let mut buf = "[".to_owned();
+    for i in 0..size {
+        if indices.contains(&i) {
+            write!(buf, "{ident}_{i}, ").unwrap();
+        } else {
+            write!(buf, "_, ").unwrap();
+        }
+    }
+    write!(buf, "..]").unwrap();
+    buf
+}
+
 fn contains_wild(pat: &Pat<'_>) -> bool {
     let mut found = false;
     pat.walk(|pat| {
@@ -355,6 +528,13 @@ fn ui() {
     dylint_testing::ui_test(env!("CARGO_PKG_NAME"), "ui");
 }
 
+#[test]
+fn ui_copy_only() {
+    dylint_testing::ui::Test::src_base(env!("CARGO_PKG_NAME"), "ui_copy_only")
+        .dylint_toml("suboptimal_pattern.copy_only = true")
+        .run();
+}
+
 #[test]
 fn ui_no_explicit_deref_check() {
     dylint_testing::ui::Test::src_base(env!("CARGO_PKG_NAME"), "ui_no_explicit_deref_check")
@@ -370,4 +550,8 @@ fn ui_main_rs_are_equal() {
         std::fs::read_to_string("ui_no_explicit_deref_check/main.rs").unwrap();
 
     assert_eq!(ui_main_rs, ui_no_explicit_deref_check_main_rs);
+
+    let ui_copy_only_main_rs = std::fs::read_to_string("ui_copy_only/main.rs").unwrap();
+
+    assert_eq!(ui_main_rs, ui_copy_only_main_rs);
 }
@@ -125,3 +125,24 @@ fn tuple_with_wildcard() {
     // smoelius: Does not compile:
     // let _ = ws.split_last().map(|&(w, _)| w);
 }
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Clone, Copy)]
+struct Pair(i32, i32);
+
+fn structs_and_arrays() {
+    let points = [Point { x: 0, y: 0 }];
+    let _ = points.iter().map(|p| p.x + p.y).collect::<Vec<_>>();
+    let _ = points.iter().map(|p| p.x).collect::<Vec<_>>();
+
+    let pairs = [Pair(0, 0)];
+    let _ = pairs.iter().map(|p| p.1).collect::<Vec<_>>();
+
+    let arrays = [[0, 1, 2]];
+    let _ = arrays.iter().map(|a| a[0] + a[2]).collect::<Vec<_>>();
+}
@@ -4,7 +4,7 @@ mod test {
     use dylint_internal::{
         clippy_utils::toolchain_channel, examples::iter, rustup::SanitizeEnvironment, CommandExt,
     };
-    use std::{ffi::OsStr, fs::read_to_string};
+    use std::{ffi::OsStr, fs::read_to_string, path::Path};
     use toml_edit::{DocumentMut, Item, Value};
     use walkdir::WalkDir;
 
@@ -130,6 +130,37 @@ mod test {
         }
     }
 
+    #[test]
+    fn libraries_register_lints_in_order() {
+        for library in ["general", "supplementary"] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(library);
+
+            let contents = read_to_string(path.join("src/lib.rs")).unwrap();
+            let registered_lints = contents
+                .lines()
+                .filter_map(|line| {
+                    line.trim()
+                        .strip_suffix("::register_lints(sess, lint_store);")
+                })
+                .collect::<Vec<_>>();
+
+            for registered_lint in &registered_lints {
+                assert!(
+                    path.join(registered_lint).join("Cargo.toml").exists(),
+                    "`{library}/src/lib.rs`: `{registered_lint}` is not a library in `{library}`"
+                );
+            }
+
+            let mut sorted_lints = registered_lints.clone();
+            sorted_lints.sort_unstable();
+
+            assert_eq!(
+                sorted_lints, registered_lints,
+                "`{library}/src/lib.rs` does not register lints in sorted order"
+            );
+        }
+    }
+
     #[test]
     fn examples_do_not_contain_forbidden_paths() {
         let forbidden_files_general = [".gitignore"];
@@ -3,6 +3,7 @@
 #![warn(unused_extern_crates)]
 
 extern crate rustc_data_structures;
+extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
@@ -11,6 +12,7 @@ use clippy_utils::{
     diagnostics::span_lint_hir_and_then, get_parent_expr, peel_middle_ty_refs, ty::is_copy,
 };
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::Applicability;
 use rustc_hir::{
     def_id::LocalDefId,
     intravisit::{walk_generic_param, walk_lifetime, Visitor},
@@ -100,9 +102,11 @@ impl Default for Config {
     }
 }
 
+// Each subfield access is recorded as a pair of spans: the span of the access (e.g.,
+// `.cx.tcx`), and the span of the subfield alone (e.g., `.tcx`).
 #[derive(Default)]
 struct FieldUse {
-    subfield_accesses: FxHashMap<Ident, (String, FxHashSet<Span>)>,
+    subfield_accesses: FxHashMap<Ident, (String, FxHashSet<(Span, Span)>)>,
     other_use: bool,
 }
 
@@ -138,9 +142,10 @@ impl<'tcx> LateLintPass<'tcx> for RedundantReference {
                     .subfield_accesses
                     .entry(subfield)
                     .or_insert_with(|| (parent_ty.to_string(), HashSet::default()));
-                subfield_access
-                    .1
-                    .insert(subfield.span.with_lo(operand.span.hi()));
+                subfield_access.1.insert((
+                    subfield.span.with_lo(operand.span.hi()),
+                    subfield.span.with_lo(expr.span.hi()),
+                ));
             } else {
                 field_use.other_use = true;
             }
@@ -196,6 +201,10 @@ impl<'tcx> LateLintPass<'tcx> for RedundantReference {
                 };
                 let (subfield, (subfield_ty, access_spans)) =
                     subfield_accesses.iter().next().unwrap();
+                // `access_spans` is a hash set. Sort its elements so that the notes and
+                // suggestion parts are emitted in a deterministic order.
+                let mut access_spans = access_spans.iter().copied().collect::<Vec<_>>();
+                access_spans.sort_unstable();
                 span_lint_hir_and_then(
                     cx,
                     REDUNDANT_REFERENCE,
@@ -206,12 +215,25 @@ impl<'tcx> LateLintPass<'tcx> for RedundantReference {
                         whose type `{subfield_ty}` implements `Copy`"
                     ),
                     |diag| {
-                        for access_span in access_spans {
-                            diag.span_note(*access_span, "read here");
+                        for &(access_span, _) in &access_spans {
+                            diag.span_note(access_span, "read here");
                         }
-                        diag.help(format!(
-                            "consider storing a copy of `.{field}.{subfield}`{lifetime_help}"
-                        ));
+                        // The suggestion does not remove the lifetime parameter, and
+                        // `subfield_ty` may contain elided lifetimes. Hence, the suggestion is not
+                        // machine applicable.
+                        diag.multipart_suggestion(
+                            format!(
+                                "consider storing a copy of `.{field}.{subfield}`{lifetime_help}"
+                            ),
+                            std::iter::once((field_def.ty.span, subfield_ty.clone()))
+                                .chain(
+                                    access_spans
+                                        .iter()
+                                        .map(|&(_, subfield_span)| (subfield_span, String::new())),
+                                )
+                                .collect(),
+                            Applicability::MaybeIncorrect,
+                        );
                     },
                 );
             }
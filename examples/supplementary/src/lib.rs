@@ -17,4 +17,19 @@ pub fn register_lints(sess: &rustc_session::Session, lint_store: &mut rustc_lint
     unnamed_constant::register_lints(sess, lint_store);
     unnecessary_borrow_mut::register_lints(sess, lint_store);
     unnecessary_conversion_for_trait::register_lints(sess, lint_store);
+
+    // Please keep the following lints sorted by crate name.
+    dylint_linting::register_group(
+        lint_store,
+        "supplementary",
+        &[
+            commented_code::COMMENTED_CODE,
+            escaping_doc_link::ESCAPING_DOC_LINK,
+            inconsistent_struct_pattern::INCONSISTENT_STRUCT_PATTERN,
+            redundant_reference::REDUNDANT_REFERENCE,
+            unnamed_constant::UNNAMED_CONSTANT,
+            unnecessary_borrow_mut::UNNECESSARY_BORROW_MUT,
+            unnecessary_conversion_for_trait::UNNECESSARY_CONVERSION_FOR_TRAIT,
+        ],
+    );
 }
@@ -63,7 +63,7 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryBorrowMut {
 
         let mir = cx.tcx.optimized_mir(local_def_id.to_def_id());
 
-        if enabled("DEBUG_MIR") {
+        if dylint_linting::instrumentation_enabled("UNNECESSARY_BORROW_MUT_DEBUG_MIR") {
             let options = PrettyPrintMirOptions::from_cli(cx.tcx);
             write_mir_fn(
                 cx.tcx,
@@ -221,12 +221,6 @@ impl<'tcx> Visitor<'tcx> for V<'tcx> {
     }
 }
 
-#[must_use]
-fn enabled(opt: &str) -> bool {
-    let key = env!("CARGO_PKG_NAME").to_uppercase() + "_" + opt;
-    std::env::var(key).is_ok_and(|value| value != "0")
-}
-
 #[test]
 fn ui() {
     dylint_testing::ui_test(env!("CARGO_PKG_NAME"), "ui");
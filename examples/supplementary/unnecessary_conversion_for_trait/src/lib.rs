@@ -29,6 +29,7 @@ use rustc_middle::ty::{
     self,
     adjustment::{Adjust, Adjustment, AutoBorrow},
     ClauseKind, EarlyBinder, FnDef, FnSig, GenericArgsRef, Param, ParamTy, ProjectionPredicate, Ty,
+    TypeVisitableExt,
 };
 use rustc_span::symbol::{sym, Symbol};
 use rustc_trait_selection::traits::{
@@ -75,6 +76,12 @@ struct UnnecessaryConversionForTrait {
     callee_paths: BTreeSet<Vec<String>>,
 }
 
+// Instrumentation flags. Each can be enabled with an environment variable or in the
+// `[instrumentation]` table of a `dylint.toml` file.
+const CHECK_INHERENTS: &str = "UNNECESSARY_CONVERSION_FOR_TRAIT_CHECK_INHERENTS";
+const COVERAGE: &str = "UNNECESSARY_CONVERSION_FOR_TRAIT_COVERAGE";
+const DEBUG_WATCHLIST: &str = "UNNECESSARY_CONVERSION_FOR_TRAIT_DEBUG_WATCHLIST";
+
 const WATCHED_TRAITS: &[&[&str]] = &[
     &["alloc", "borrow", "ToOwned", "to_owned"],
     &["alloc", "string", "ToString", "to_string"],
@@ -174,10 +181,12 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                 .iter()
                 .position(|arg| arg.hir_id == maybe_arg.hir_id)
             && let Some(input) = outer_fn_sig.inputs().get(i)
-            && let Param(param_ty) = input.kind()
+            && let Some((param_ty, input_mutability)) =
+                input_param_ty_and_mutability(*input, &ancestor_mutabilities)
+            && !has_debug_bound(cx, outer_callee_def_id, param_ty)
         {
             let mut strip_unnecessary_conversions = |mut expr, mut mutabilities| {
-                let mut refs_prefix = None;
+                let mut ty_and_refs_prefix = None;
 
                 loop {
                     if let Some((inner_callee_def_id, _, inner_receiver, inner_args)) =
@@ -193,15 +202,20 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                         && let new_mutabilities = [adjustment_mutabilities, mutabilities].concat()
                         && let (new_ty, new_refs_prefix) =
                             build_ty_and_refs_prefix(cx, inner_arg_ty, &new_mutabilities)
-                        && inner_arg_implements_traits(
-                            cx,
-                            outer_callee_def_id,
-                            outer_fn_sig,
-                            outer_substs,
-                            i,
-                            *param_ty,
-                            new_ty,
-                        )
+                        && let Some((new_ty, new_refs_prefix)) =
+                            substitution_candidates(new_ty, new_refs_prefix, input_mutability)
+                                .into_iter()
+                                .find(|(new_ty, _)| {
+                                    inner_arg_implements_traits(
+                                        cx,
+                                        outer_callee_def_id,
+                                        outer_fn_sig,
+                                        outer_substs,
+                                        i,
+                                        param_ty,
+                                        *new_ty,
+                                    )
+                                })
                     {
                         let inner_callee_path = cx.get_def_path(inner_callee_def_id);
                         if !WATCHED_TRAITS
@@ -209,7 +223,7 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                             .chain(WATCHED_INHERENTS.iter())
                             .any(|path| match_def_path(cx, inner_callee_def_id, path))
                         {
-                            if enabled("DEBUG_WATCHLIST") {
+                            if dylint_linting::instrumentation_enabled(DEBUG_WATCHLIST) {
                                 span_lint(
                                     cx,
                                     UNNECESSARY_CONVERSION_FOR_TRAIT,
@@ -227,18 +241,31 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                         );
                         expr = inner_arg;
                         mutabilities = new_mutabilities;
-                        refs_prefix = Some(new_refs_prefix);
+                        ty_and_refs_prefix = Some((new_ty, new_refs_prefix));
                         continue;
                     }
                     break;
                 }
 
-                Some(expr).zip(refs_prefix)
+                Some(expr).zip(ty_and_refs_prefix)
             };
 
-            if let Some((inner_arg, refs_prefix)) =
+            if let Some((inner_arg, (new_ty, refs_prefix))) =
                 strip_unnecessary_conversions(expr, ancestor_mutabilities)
             {
+                let applicability = if signature_is_preserved(
+                    cx,
+                    outer_callee_def_id,
+                    outer_fn_sig,
+                    outer_substs,
+                    i,
+                    param_ty,
+                    new_ty,
+                ) {
+                    Applicability::MachineApplicable
+                } else {
+                    Applicability::MaybeIncorrect
+                };
                 let (is_bare_method_call, subject) =
                     if matches!(expr.kind, ExprKind::MethodCall(..)) {
                         (maybe_arg.hir_id == expr.hir_id, "receiver")
@@ -255,20 +282,56 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                         msg,
                         "remove this",
                         String::new(),
-                        Applicability::MachineApplicable,
-                    );
-                } else if maybe_arg.span.from_expansion()
-                    && let Some(span) = maybe_arg.span.parent_callsite()
-                {
-                    // smoelius: This message could be more informative.
-                    span_lint_and_help(
-                        cx,
-                        UNNECESSARY_CONVERSION_FOR_TRAIT,
-                        span,
-                        msg,
-                        None,
-                        "use the macro arguments directly",
+                        applicability,
                     );
+                } else if maybe_arg.span.from_expansion() {
+                    // Macros like `format_args!` borrow their arguments. If the borrow is the only
+                    // part of the argument that comes from the macro, the suggestion is made on
+                    // the user's expression, and the borrow is left to the macro.
+                    let user_refs_prefix = input_mutability
+                        .map(|mutability| format!("&{}", mutability.prefix_str()))
+                        .and_then(|macro_borrow| refs_prefix.strip_prefix(&macro_borrow));
+                    if let ExprKind::AddrOf(_, _, borrowed) = maybe_arg.kind
+                        && borrowed.hir_id == expr.hir_id
+                        && !expr.span.from_expansion()
+                        && let Some(user_refs_prefix) = user_refs_prefix
+                    {
+                        if user_refs_prefix.is_empty()
+                            && matches!(expr.kind, ExprKind::MethodCall(..))
+                        {
+                            span_lint_and_sugg(
+                                cx,
+                                UNNECESSARY_CONVERSION_FOR_TRAIT,
+                                expr.span.with_lo(inner_arg.span.hi()),
+                                msg,
+                                "remove this",
+                                String::new(),
+                                applicability,
+                            );
+                        } else if let Some(snippet) = snippet_opt(cx, inner_arg.span) {
+                            span_lint_and_sugg(
+                                cx,
+                                UNNECESSARY_CONVERSION_FOR_TRAIT,
+                                expr.span,
+                                msg,
+                                "use",
+                                format!("{user_refs_prefix}{snippet}"),
+                                applicability,
+                            );
+                        }
+                    } else {
+                        // smoelius: This message could be more informative.
+                        span_lint_and_help(
+                            cx,
+                            UNNECESSARY_CONVERSION_FOR_TRAIT,
+                            // `format!` expands to `format_args!`, so the parent callsite would be
+                            // in the standard library, and the lint would be suppressed there.
+                            maybe_arg.span.source_callsite(),
+                            msg,
+                            None,
+                            "use the macro arguments directly",
+                        );
+                    }
                 } else if let Some(snippet) = snippet_opt(cx, inner_arg.span) {
                     span_lint_and_sugg(
                         cx,
@@ -277,7 +340,7 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                         msg,
                         "use",
                         format!("{refs_prefix}{snippet}"),
-                        Applicability::MachineApplicable,
+                        applicability,
                     );
                 }
             }
@@ -285,7 +348,7 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
     }
 
     fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
-        if enabled("COVERAGE") {
+        if dylint_linting::instrumentation_enabled(COVERAGE) {
             let path = coverage_path(cx.tcx.crate_name(LOCAL_CRATE).as_str());
             // smoelius: Don't overwrite an existing file.
             if path.exists() {
@@ -297,12 +360,12 @@ impl<'tcx> LateLintPass<'tcx> for UnnecessaryConversionForTrait {
                 .truncate(true)
                 .open(&path)
                 .unwrap();
-            for path in &self.callee_paths {
-                writeln!(file, "{path:?}").unwrap();
+            for line in format_coverage(self.callee_paths.iter().map(Vec::as_slice)) {
+                writeln!(file, "{line}").unwrap();
             }
         }
 
-        if enabled("CHECK_INHERENTS") {
+        if dylint_linting::instrumentation_enabled(CHECK_INHERENTS) {
             check_inherents(cx);
         }
     }
@@ -349,6 +412,7 @@ mod sort {
 #[cfg(test)]
 mod ui {
     use super::*;
+    use dylint_linting::instrumentation_enabled;
     use std::{
         env::{remove_var, set_var, var_os},
         ffi::{OsStr, OsString},
@@ -363,30 +427,27 @@ mod ui {
     #[test]
     fn general() {
         let _lock = MUTEX.lock().unwrap();
-        let _var = VarGuard::set("COVERAGE", "1");
+        let _var = VarGuard::set(COVERAGE, "1");
 
-        assert!(!enabled("CHECK_INHERENTS"));
+        assert!(!instrumentation_enabled(CHECK_INHERENTS));
 
         let path = coverage_path("general");
         remove_file(&path).unwrap_or_default();
 
         dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "general");
 
-        let mut combined_watchlist = WATCHED_TRAITS
-            .iter()
-            .chain(WATCHED_INHERENTS.iter())
-            .collect::<Vec<_>>();
-        combined_watchlist.sort();
+        let combined_watchlist = format_coverage(
+            WATCHED_TRAITS
+                .iter()
+                .chain(WATCHED_INHERENTS.iter())
+                .copied(),
+        );
 
         let coverage = read_to_string(path).unwrap();
         let coverage_lines = coverage.lines().collect::<Vec<_>>();
 
-        for (left, right) in combined_watchlist
-            .iter()
-            .map(|path| format!("{path:?}"))
-            .zip(coverage_lines.iter())
-        {
-            assert_eq!(&left, right);
+        for (left, right) in combined_watchlist.iter().zip(coverage_lines.iter()) {
+            assert_eq!(left, right);
         }
 
         assert_eq!(combined_watchlist.len(), coverage_lines.len());
@@ -396,9 +457,9 @@ mod ui {
     #[test]
     fn check_inherents() {
         let _lock = MUTEX.lock().unwrap();
-        let _var = VarGuard::set("CHECK_INHERENTS", "1");
+        let _var = VarGuard::set(CHECK_INHERENTS, "1");
 
-        assert!(!enabled("COVERAGE"));
+        assert!(!instrumentation_enabled(COVERAGE));
 
         let tempdir = tempdir().unwrap();
 
@@ -407,12 +468,22 @@ mod ui {
         dylint_testing::ui_test(env!("CARGO_PKG_NAME"), &tempdir);
     }
 
+    #[test]
+    fn to_string() {
+        let _lock = MUTEX.lock().unwrap();
+
+        assert!(!instrumentation_enabled(COVERAGE));
+        assert!(!instrumentation_enabled(CHECK_INHERENTS));
+
+        dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "to_string");
+    }
+
     #[test]
     fn unnecessary_to_owned() {
         let _lock = MUTEX.lock().unwrap();
 
-        assert!(!enabled("COVERAGE"));
-        assert!(!enabled("CHECK_INHERENTS"));
+        assert!(!instrumentation_enabled(COVERAGE));
+        assert!(!instrumentation_enabled(CHECK_INHERENTS));
 
         dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "unnecessary_to_owned");
     }
@@ -421,13 +492,13 @@ mod ui {
     fn vec() {
         let _lock = MUTEX.lock().unwrap();
 
-        assert!(!enabled("COVERAGE"));
-        assert!(!enabled("CHECK_INHERENTS"));
+        assert!(!instrumentation_enabled(COVERAGE));
+        assert!(!instrumentation_enabled(CHECK_INHERENTS));
 
         dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "vec");
     }
 
-    // smoelius: `VarGuard` is from the following with the use of `option` added:
+    // `VarGuard` is from the following:
     // https://github.com/rust-lang/rust-clippy/blob/9cc8da222b3893bc13bc13c8827e93f8ea246854/tests/compile-test.rs
 
     /// Restores an env var on drop
@@ -440,7 +511,7 @@ mod ui {
     impl VarGuard {
         fn set(key: &'static str, val: impl AsRef<OsStr>) -> Self {
             let value = var_os(key);
-            set_var(option(key), val);
+            set_var(key, val);
             Self { key, value }
         }
     }
@@ -448,8 +519,8 @@ mod ui {
     impl Drop for VarGuard {
         fn drop(&mut self) {
             match self.value.as_deref() {
-                None => remove_var(option(self.key)),
-                Some(value) => set_var(option(self.key), value),
+                None => remove_var(self.key),
+                Some(value) => set_var(self.key, value),
             }
         }
     }
@@ -561,6 +632,71 @@ fn inner_arg_implements_traits<'tcx>(
     })
 }
 
+// `inner_arg_implements_traits` is checked one conversion at a time. As a final check
+// before offering a machine-applicable suggestion, verify that substituting the fully stripped
+// argument's type leaves every other position in the callee's signature unchanged. Otherwise, the
+// suggestion could change type inference at some other position (e.g., the call's result). The
+// types are compared after normalization, so that, e.g., `<std::slice::Iter<'_, u8> as
+// IntoIterator>::Item` and `<&Vec<u8> as IntoIterator>::Item` are considered the same.
+fn signature_is_preserved<'tcx>(
+    cx: &LateContext<'tcx>,
+    callee_def_id: DefId,
+    fn_sig: FnSig<'tcx>,
+    generic_args_with_expr_ty: GenericArgsRef<'tcx>,
+    arg_index: usize,
+    param_ty: ParamTy,
+    new_ty: Ty<'tcx>,
+) -> bool {
+    let projection_predicates = cx
+        .tcx
+        .param_env(callee_def_id)
+        .caller_bounds()
+        .iter()
+        .filter_map(|predicate| {
+            if let ClauseKind::Projection(projection_predicate) = predicate.kind().skip_binder() {
+                Some(projection_predicate)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut substs_with_new_ty = generic_args_with_expr_ty.to_vec();
+
+    if !replace_types(
+        cx,
+        param_ty,
+        new_ty,
+        fn_sig,
+        arg_index,
+        &projection_predicates,
+        &mut substs_with_new_ty,
+    ) {
+        return false;
+    }
+
+    let old_inputs_and_output =
+        EarlyBinder::bind(fn_sig.inputs_and_output).instantiate(cx.tcx, generic_args_with_expr_ty);
+    let new_inputs_and_output = EarlyBinder::bind(fn_sig.inputs_and_output)
+        .instantiate(cx.tcx, substs_with_new_ty.as_slice());
+
+    let normalize = |ty: Ty<'tcx>| {
+        if ty.has_escaping_bound_vars() {
+            ty
+        } else {
+            cx.tcx
+                .try_normalize_erasing_regions(cx.typing_env(), ty)
+                .unwrap_or(ty)
+        }
+    };
+
+    old_inputs_and_output
+        .iter()
+        .zip(new_inputs_and_output.iter())
+        .enumerate()
+        .all(|(i, (old_ty, new_ty))| i == arg_index || normalize(old_ty) == normalize(new_ty))
+}
+
 // smoelius: `replace_types` was copied from:
 // https://github.com/rust-lang/rust-clippy/blob/cf8a67d9ad81547895ec986f8bcb17e912037c38/clippy_lints/src/dereference.rs#L1295-L1349
 
@@ -641,6 +777,71 @@ fn ancestor_addr_of_mutabilities<'tcx>(
     None
 }
 
+// An input is considered if it is a type parameter, or if it is a reference to a type
+// parameter and the argument is explicitly borrowed. The latter case arises with `format_args!`,
+// which passes `&arg` to functions like `core::fmt::rt::Argument::new_display<T: Display>`.
+fn input_param_ty_and_mutability(
+    input: Ty<'_>,
+    ancestor_mutabilities: &[Mutability],
+) -> Option<(ParamTy, Option<Mutability>)> {
+    match input.kind() {
+        Param(param_ty) => Some((*param_ty, None)),
+        ty::Ref(_, referent, mutability) if ancestor_mutabilities.last() == Some(mutability) => {
+            if let Param(param_ty) = referent.kind() {
+                Some((*param_ty, Some(*mutability)))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// Returns the types that could be substituted for the input's type parameter, along with
+// the refs prefix needed for each. If the input is a reference to a type parameter, the new
+// argument's type could already be a suitable reference (e.g., `&s` for `&s.to_string()`), or
+// could require an additional borrow (e.g., `&x` for `&x.to_string()` where `x: &str`).
+fn substitution_candidates(
+    ty: Ty<'_>,
+    refs_prefix: String,
+    input_mutability: Option<Mutability>,
+) -> Vec<(Ty<'_>, String)> {
+    let Some(input_mutability) = input_mutability else {
+        return vec![(ty, refs_prefix)];
+    };
+    let mut candidates = Vec::new();
+    if let ty::Ref(_, referent, mutability) = ty.kind()
+        && *mutability == input_mutability
+    {
+        candidates.push((*referent, refs_prefix.clone()));
+    }
+    candidates.push((
+        ty,
+        "&".to_owned() + input_mutability.prefix_str() + &refs_prefix,
+    ));
+    candidates
+}
+
+// `Debug` output generally reflects the concrete type (e.g., `s.to_string()` is printed
+// with quotes, and `v.iter()` is printed as `Iter([...])`). So no conversion can be assumed to
+// preserve it.
+fn has_debug_bound(cx: &LateContext<'_>, callee_def_id: DefId, param_ty: ParamTy) -> bool {
+    cx.tcx
+        .param_env(callee_def_id)
+        .caller_bounds()
+        .iter()
+        .any(|predicate| {
+            if let ClauseKind::Trait(trait_predicate) = predicate.kind().skip_binder()
+                && trait_predicate.trait_ref.self_ty() == param_ty.to_ty(cx.tcx)
+            {
+                cx.tcx
+                    .is_diagnostic_item(sym::Debug, trait_predicate.trait_ref.def_id)
+            } else {
+                false
+            }
+        })
+}
+
 fn peel_boxes<'tcx>(cx: &LateContext<'tcx>, mut expr: &'tcx Expr<'tcx>) -> &'tcx Expr<'tcx> {
     const BOX_NEW: [&str; 4] = ["alloc", "boxed", "Box", "new"];
 
@@ -703,14 +904,17 @@ fn build_ty_and_refs_prefix<'tcx>(
     (ty, refs_prefix)
 }
 
-#[must_use]
-fn enabled(name: &str) -> bool {
-    let key = option(name);
-    std::env::var(key).is_ok_and(|value| value != "0")
-}
-
-fn option(name: &str) -> String {
-    env!("CARGO_PKG_NAME").to_uppercase() + "_" + name
+// The `general` test compares the coverage file to the combined watchlists line by line. Both
+// sides are produced by `format_coverage` so that they are sorted and formatted the same way.
+fn format_coverage<'a, T: AsRef<str> + 'a>(
+    paths: impl IntoIterator<Item = &'a [T]>,
+) -> Vec<String> {
+    let mut paths = paths
+        .into_iter()
+        .map(|path| path.iter().map(AsRef::as_ref).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    paths.sort_unstable();
+    paths.iter().map(|path| format!("{path:?}")).collect()
 }
 
 fn coverage_path(krate: &str) -> PathBuf {
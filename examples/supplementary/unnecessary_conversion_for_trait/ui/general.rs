@@ -117,3 +117,29 @@ trait OsStringOrBytes {}
 impl OsStringOrBytes for OsString {}
 impl OsStringOrBytes for Vec<u8> {}
 fn os_string_or_bytes(_: impl OsStringOrBytes) {}
+
+mod inference {
+    use std::path::{Path, PathBuf};
+
+    fn first<I: IntoIterator>(iter: I) -> Option<I::Item> {
+        iter.into_iter().next()
+    }
+
+    fn same<T: AsRef<Path>>(path: T) -> T {
+        path
+    }
+
+    // `first`'s return type is a projection on its argument's type. Removing the conversion changes
+    // the projection, but not the type to which it normalizes. So the fix is machine applicable.
+    fn foo() {
+        let v = vec![0u8];
+        let _: Option<&u8> = first(v.iter());
+    }
+
+    // `same`'s return type is its argument's type. Removing the conversion would change the type of
+    // `same(...)`, so the fix is not machine applicable.
+    fn bar() {
+        let path_buf = PathBuf::new();
+        let _: &Path = same(path_buf.as_path());
+    }
+}
@@ -0,0 +1,27 @@
+// run-rustfix
+
+use std::fmt::{Debug, Display};
+
+fn main() {
+    let s = String::new();
+    let x = "";
+
+    display(s.to_string());
+    display(x.to_string());
+
+    as_ref_str(s.to_string());
+    as_ref_str(x.to_string());
+
+    let _ = format!("{}", s.to_string());
+    let _ = format!("{}", x.to_string());
+
+    // `to_string` does not preserve `Debug` output.
+    debug(s.to_string());
+    let _ = format!("{:?}", s.to_string());
+}
+
+fn display(_: impl Display) {}
+
+fn as_ref_str(_: impl AsRef<str>) {}
+
+fn debug(_: impl Debug) {}
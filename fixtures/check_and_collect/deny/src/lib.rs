@@ -0,0 +1,7 @@
+#![deny(question_mark_in_expression)]
+
+pub fn greet() -> Result<(), std::str::Utf8Error> {
+    let unused = 0;
+    println!("{}", std::str::from_utf8(b"Hello, world!")?);
+    Ok(())
+}
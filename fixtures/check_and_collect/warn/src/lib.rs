@@ -0,0 +1,5 @@
+pub fn greet() -> Result<(), std::str::Utf8Error> {
+    let unused = 0;
+    println!("{}", std::str::from_utf8(b"Hello, world!")?);
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use std::ffi::{c_char, CStr, CString};
+
+#[no_mangle]
+pub extern "C" fn dylint_diagnostic_handler(line: *const c_char) -> *mut c_char {
+    let line = unsafe { CStr::from_ptr(line) }.to_string_lossy();
+    CString::new(line.replace("using the `?` operator", "handled"))
+        .unwrap()
+        .into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dylint_diagnostic_handler_free(line: *mut c_char) {
+    drop(unsafe { CString::from_raw(line) });
+}
@@ -0,0 +1,3 @@
+pub mod excluded;
+pub mod included;
+pub mod not_included;
@@ -0,0 +1,14 @@
+use std::{env, fs::write, path::Path};
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    write(
+        Path::new(&out_dir).join("generated.rs"),
+        r#"pub fn greet() -> Result<(), std::str::Utf8Error> {
+    println!("{}", std::str::from_utf8(b"Hello, world!")?);
+    Ok(())
+}
+"#,
+    )
+    .unwrap();
+}
@@ -42,6 +42,9 @@ enum Inner {
     Io(String, std::io::Error),
     #[error("toml error: {0}")]
     Toml(#[from] toml::de::Error),
+    #[cfg(feature = "config-env-expansion")]
+    #[error("environment variable error: {0}: {1}")]
+    Var(String, std::env::VarError),
     #[error("{0}")]
     Other(String),
 }
@@ -55,10 +58,37 @@ pub fn get() -> Option<&'static toml::value::Table> {
 // smoelius: `try_init_with_metadata` returns a string so that `dylint_linting` can record it in
 // `file_depinfo`.
 pub fn try_init_with_metadata(metadata: &cargo_metadata::Metadata) -> Result<Option<String>> {
+    try_init_with_metadata_and_transform(metadata, |value| value)
+}
+
+// `transform` is applied to the file's contents before they are parsed. The string
+// returned is the file's original contents.
+pub fn try_init_with_metadata_and_transform(
+    metadata: &cargo_metadata::Metadata,
+    transform: impl FnOnce(String) -> String,
+) -> Result<Option<String>> {
     if CONFIG_TABLE.get().is_some() {
         return Ok(None);
     }
 
+    let value = read_with_metadata(metadata)?;
+
+    if let Some(s) = &value {
+        init_from_string(&transform(s.clone()))?;
+    }
+
+    Ok(value)
+}
+
+// Unlike `try_init_with_metadata`, `table_with_metadata` does not initialize the global table.
+// So it can be used to read the `dylint.toml` files of several workspaces in one process.
+pub fn table_with_metadata(
+    metadata: &cargo_metadata::Metadata,
+) -> Result<Option<toml::value::Table>> {
+    read_with_metadata(metadata)?.map(|s| parse(&s)).transpose()
+}
+
+fn read_with_metadata(metadata: &cargo_metadata::Metadata) -> Result<Option<String>> {
     let cargo_metadata::Metadata { workspace_root, .. } = metadata;
 
     let dylint_toml = workspace_root.join("dylint.toml");
@@ -78,22 +108,13 @@ pub fn try_init_with_metadata(metadata: &cargo_metadata::Metadata) -> Result<Opt
         None
     };
 
-    if let Some(s) = &value {
-        init_from_string(s)?;
-    }
-
     Ok(value)
 }
 
 pub fn init_from_string(s: &str) -> Result<()> {
     assert!(CONFIG_TABLE.get().is_none());
 
-    let toml: toml::Value = toml::from_str(s)?;
-
-    let table = toml
-        .as_table()
-        .cloned()
-        .ok_or_else(|| Inner::Other("Value is not a table".into()))?;
+    let table = parse(s)?;
 
     // smoelius: Rewrite this function (`init_from_string`) and eliminate the next `expect` once
     // `get_or_try_init` stabilizes: https://github.com/rust-lang/rust/issues/109737
@@ -103,3 +124,103 @@ pub fn init_from_string(s: &str) -> Result<()> {
 
     Ok(())
 }
+
+// Unlike `init_from_string`, `get_or_init_from_string` does not panic if the global table is
+// already initialized, e.g., by another thread that raced with this one. In that case, `s` is
+// still parsed, but the existing table is returned.
+pub fn get_or_init_from_string(s: &str) -> Result<&'static toml::value::Table> {
+    let table = parse(s)?;
+
+    Ok(CONFIG_TABLE.get_or_init(|| table))
+}
+
+fn parse(s: &str) -> Result<toml::value::Table> {
+    let toml: toml::Value = toml::from_str(s)?;
+
+    #[cfg(feature = "config-env-expansion")]
+    let toml = expand_env_vars(toml)?;
+
+    toml.as_table()
+        .cloned()
+        .ok_or_else(|| Inner::Other("Value is not a table".into()).into())
+}
+
+// Only string values are expanded, not keys.
+#[cfg(feature = "config-env-expansion")]
+fn expand_env_vars(value: toml::Value) -> Result<toml::Value> {
+    match value {
+        toml::Value::String(s) => expand_env_vars_in_str(&s).map(toml::Value::String),
+        toml::Value::Array(array) => array
+            .into_iter()
+            .map(expand_env_vars)
+            .collect::<Result<_>>()
+            .map(toml::Value::Array),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(key, value)| Ok((key, expand_env_vars(value)?)))
+            .collect::<Result<_>>()
+            .map(toml::Value::Table),
+        _ => Ok(value),
+    }
+}
+
+#[cfg(feature = "config-env-expansion")]
+fn expand_env_vars_in_str(s: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| Inner::Other(format!("Unterminated `${{` in `{s}`")))?;
+        let key = &after[..end];
+        let value = std::env::var(key)
+            .map_err(|error| Inner::Var(format!("`{key}` referenced in `{s}`"), error))?;
+        expanded.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn get_or_init_from_string_concurrently() {
+        let tables = thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|i| scope.spawn(move || get_or_init_from_string(&format!("x = {i}")).unwrap()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert!(tables.iter().all(|table| std::ptr::eq(*table, tables[0])));
+        assert!(std::ptr::eq(tables[0], get().unwrap()));
+    }
+
+    #[cfg(feature = "config-env-expansion")]
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn expand_env_vars() {
+        std::env::set_var("DYLINT_CONFIG_TEST_VAR", "value");
+        assert_eq!(
+            expand_env_vars_in_str("${DYLINT_CONFIG_TEST_VAR}/x/${DYLINT_CONFIG_TEST_VAR}")
+                .unwrap(),
+            "value/x/value"
+        );
+        assert_eq!(expand_env_vars_in_str("$HOME").unwrap(), "$HOME");
+        let error = expand_env_vars_in_str("${DYLINT_CONFIG_TEST_UNSET_VAR}").unwrap_err();
+        assert!(
+            error.to_string().contains("`DYLINT_CONFIG_TEST_UNSET_VAR`"),
+            "{error}"
+        );
+        assert!(expand_env_vars_in_str("${DYLINT_CONFIG_TEST_VAR").is_err());
+    }
+}
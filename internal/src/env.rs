@@ -19,14 +19,31 @@ declare_const!(CI);
 declare_const!(CLIPPY_DISABLE_DOCS_LINKS);
 declare_const!(CLIPPY_DRIVER_PATH);
 declare_const!(DOCS_RS);
+declare_const!(DYLINT_CATCH_PANICS);
+declare_const!(DYLINT_CFGS);
+declare_const!(DYLINT_DIAGNOSTIC_HANDLER);
 declare_const!(DYLINT_DRIVER_PATH);
+declare_const!(DYLINT_DRIVER_TIMEOUT);
+declare_const!(DYLINT_EXCLUDE_LINTS);
 declare_const!(DYLINT_LIBRARY_PATH);
+declare_const!(DYLINT_LEVELS);
 declare_const!(DYLINT_LIBS);
+declare_const!(DYLINT_LINT_COVERAGE);
 declare_const!(DYLINT_LIST);
+declare_const!(DYLINT_LIST_CONFIGS);
+declare_const!(DYLINT_LIST_JSON);
+declare_const!(DYLINT_LIST_WITH_DOCS);
 declare_const!(DYLINT_METADATA);
 declare_const!(DYLINT_NO_DEPS);
+declare_const!(DYLINT_NORMALIZE_STDERR);
 declare_const!(DYLINT_RUSTFLAGS);
+declare_const!(DYLINT_SKIP_GENERATED);
+declare_const!(DYLINT_TARGET_DIR);
+declare_const!(DYLINT_TESTING_NO_BUILD);
+declare_const!(DYLINT_TESTNAME);
 declare_const!(DYLINT_TOML);
+declare_const!(DYLINT_VALIDATE_CONFIG);
+declare_const!(DYLINT_VERBOSE);
 declare_const!(OUT_DIR);
 declare_const!(PATH);
 declare_const!(RUSTC);
@@ -35,6 +52,7 @@ declare_const!(RUSTFLAGS);
 declare_const!(RUSTUP_HOME);
 declare_const!(RUSTUP_TOOLCHAIN);
 declare_const!(RUST_BACKTRACE);
+declare_const!(RUST_LOG);
 declare_const!(TARGET);
 
 /// Returns true if the environment variable `key` is set to a non-zero value.
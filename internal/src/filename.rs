@@ -41,6 +41,9 @@ pub fn library_filename(lib_name: &str, toolchain: &str) -> String {
 
 /// Parses the filename of a Dylint library path into a tuple of (name, toolchain).
 ///
+/// A hash or version appended to the library's name (e.g., `libfoo-1.2.3@...`) is not included in
+/// the returned name.
+///
 /// # Examples
 ///
 /// ```
@@ -91,5 +94,99 @@ pub fn parse_filename(filename: &str) -> Option<(String, String)> {
 
 fn parse_target_name(target_name: &str) -> Option<(String, String)> {
     let (lib_name, toolchain) = target_name.split_once('@')?;
-    Some((lib_name.to_owned(), toolchain.to_owned()))
+    Some((
+        strip_hash_or_version(lib_name).to_owned(),
+        toolchain.to_owned(),
+    ))
+}
+
+// Library names are snake case (see `library_filename`), so a `-` cannot be part of one.
+// Libraries built from crates.io or git can have a hash or version appended to their names, e.g.,
+// `foo-0123456789abcdef` or `foo-1.2.3`. Such a suffix is stripped. Any other suffix is left alone.
+fn strip_hash_or_version(lib_name: &str) -> &str {
+    let Some((name, suffix)) = lib_name.split_once('-') else {
+        return lib_name;
+    };
+    if is_hash(suffix) || is_version(suffix) {
+        name
+    } else {
+        lib_name
+    }
+}
+
+fn is_hash(s: &str) -> bool {
+    s.len() >= 8 && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_version(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit())
+        && s.contains('.')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            parse_target_name("foo@stable-x86_64-unknown-linux-gnu"),
+            Some((
+                String::from("foo"),
+                String::from("stable-x86_64-unknown-linux-gnu")
+            ))
+        );
+    }
+
+    #[test]
+    fn hashed() {
+        assert_eq!(
+            parse_target_name("foo-0123456789abcdef@stable-x86_64-unknown-linux-gnu"),
+            Some((
+                String::from("foo"),
+                String::from("stable-x86_64-unknown-linux-gnu")
+            ))
+        );
+    }
+
+    #[test]
+    fn versioned() {
+        for version in ["1.2.3", "0.1.0-alpha.1", "1.0.0+build"] {
+            assert_eq!(
+                parse_target_name(&format!("foo-{version}@stable-x86_64-unknown-linux-gnu")),
+                Some((
+                    String::from("foo"),
+                    String::from("stable-x86_64-unknown-linux-gnu")
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn other_suffix() {
+        assert_eq!(
+            parse_target_name("foo-bar@stable-x86_64-unknown-linux-gnu"),
+            Some((
+                String::from("foo-bar"),
+                String::from("stable-x86_64-unknown-linux-gnu")
+            ))
+        );
+    }
+
+    #[test]
+    fn platform_prefix_and_suffix() {
+        assert_eq!(
+            parse_filename(&format!(
+                "{}foo-1.2.3@nightly-2024-11-28-x86_64-unknown-linux-gnu{}",
+                consts::DLL_PREFIX,
+                consts::DLL_SUFFIX
+            )),
+            Some((
+                String::from("foo"),
+                String::from("nightly-2024-11-28-x86_64-unknown-linux-gnu")
+            ))
+        );
+    }
 }
@@ -98,17 +98,62 @@
 //! [`supplementary` library]. The `constituent` feature is the underlying mechanism that makes this
 //! work.
 //!
+//! A larger library can also register a lint group containing all of its lints with
+//! [`register_group`], so that users can, e.g., allow all of the library's lints at once:
+//!
+//! ```rust,ignore
+//! dylint_linting::register_group(lint_store, "general", &[abs_home_path::ABS_HOME_PATH, ...]);
+//! ```
+//!
+//! The `general` and `supplementary` libraries register such groups, named `general` and
+//! `supplementary`, respectively.
+//!
 //! # Configurable libraries
 //!
 //! Libraries can be configured by including a `dylint.toml` file in the target workspace's root
-//! directory. This crate provides the following functions for reading and parsing `dylint.toml`
+//! directory. (If the target is not part of a workspace, e.g., because `rustc` was invoked
+//! directly, a `dylint.toml` file in the same directory as the crate's root source file is used
+//! instead.) This crate provides the following functions for reading and parsing `dylint.toml`
 //! files:
 //!
 //! - [`config_or_default`]
 //! - [`config`]
+//! - [`config_path`]
 //! - [`config_toml`]
+//! - [`config_with_presence`]
+//! - [`config_keys`]
+//! - [`config_profile`]
+//! - [`instrumentation_enabled`]
 //! - [`init_config`]
 //! - [`try_init_config`]
+//! - [`try_init_config_with_transform`]
+//!
+//! If the `config-env-expansion` feature is enabled, then each occurrence of `${VAR}` in a
+//! `dylint.toml` string value is replaced with the value of the environment variable `VAR`, e.g.,
+//! `path = "${HOME}/warnings.json"`. Referring to a variable that is not set is an error.
+//!
+//! A `dylint.toml` file can also contain a `[profile]` table that trades speed for thoroughness
+//! across all of the libraries that consult it, e.g.:
+//!
+//! ```toml
+//! [profile]
+//! name = "fast" # or "thorough"
+//! ```
+//!
+//! A library reads the selected profile with [`config_profile`] and maps it to its own defaults. A
+//! library's own configuration should take precedence over the profile. The
+//! [`non_local_effect_before_error_return`] library, for example, lowers its work limit under
+//! `fast` and raises it under `thorough`, unless its `work_limit` is set explicitly.
+//!
+//! Some libraries have instrumentation (e.g., debug output or coverage files) that is enabled by an
+//! environment variable such as `UNNECESSARY_CONVERSION_FOR_TRAIT_COVERAGE=1`. A library checks
+//! such a flag with [`instrumentation_enabled`], which also consults the `dylint.toml` file's
+//! `[instrumentation]` table. So the above flag can alternatively be enabled as follows:
+//!
+//! ```toml
+//! [instrumentation]
+//! unnecessary_conversion_for_trait_coverage = true
+//! ```
 //!
 //! A configurable library containing just one lint will typically have a `lib.rs` file of the
 //! following form:
@@ -145,6 +190,31 @@
 //! For a concrete example of a `lib.rs` file with this form, see the
 //! [`non_local_effect_before_error_return`] library in this repository.
 //!
+//! `impl_late_lint!` can generate the call to `config_or_default` itself. If the lint's
+//! [`LintPass`] structure is preceded by `@config = Config,`, then the structure is given as a
+//! function that takes the deserialized configuration:
+//!
+//! ```rust,ignore
+//! dylint_linting::impl_late_lint! {
+//!     ...,
+//!     @config = Config,
+//!     |config| LintName { config }
+//! }
+//! ```
+//!
+//! The [`ref_aware_redundant_closure_for_method_calls`] library in this repository is written this
+//! way.
+//!
+//! A configurable library can advertise the keys it accepts by invoking [`dylint_config_keys!`],
+//! e.g., `dylint_linting::dylint_config_keys!("boolean", "strings");`. The keys are then shown by
+//! `cargo dylint list --configs`. Libraries that do not invoke the macro are shown as having no
+//! configuration.
+//!
+//! Similarly, a configurable library can let users check their `dylint.toml` file before a full
+//! run by invoking [`dylint_validate_config!`], e.g.,
+//! `dylint_linting::dylint_validate_config!(env!("CARGO_PKG_NAME") => Config);`. Then
+//! `cargo dylint check-config` reports each key whose value cannot be deserialized.
+//!
 //! A library containing more than one lint must implement the `register_lints` function without
 //! relying on the above macros. If the library is configurable, then its `register_lints` function
 //! should include a call to `dylint_linting::init_config`, as in the following example:
@@ -162,27 +232,45 @@
 //! }
 //! ```
 //!
+//! If `config_or_default`, `config`, or `config_toml` is called before the config is initialized,
+//! the config is initialized from the `DYLINT_TOML` environment variable, if it is set. Otherwise,
+//! the call fails with a "Config is not initialized" error. Only `init_config` and
+//! `try_init_config` can find the target workspace's `dylint.toml` file, though, so a configurable
+//! library should still call one of them. Also, only `init_config` and `try_init_config` record
+//! `DYLINT_TOML` in the target crate's dependency information. If the config is initialized lazily,
+//! changing `DYLINT_TOML` does not cause the target crate to be rechecked.
+//!
 //! Additional documentation on `config_or_default`, etc. can be found on [docs.rs].
 //!
 //! [Configurable libraries]: #configurable-libraries
 //! [Dylint]: https://github.com/trailofbits/dylint/tree/master
 //! [`LintPass`]: https://doc.rust-lang.org/nightly/nightly-rustc/rustc_lint/trait.LintPass.html
+//! [`config_keys`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_keys.html
 //! [`config_or_default`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_or_default.html
+//! [`config_path`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_path.html
+//! [`config_profile`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_profile.html
 //! [`config_toml`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_toml.html
+//! [`config_with_presence`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_with_presence.html
 //! [`config`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config.html
 //! [`constituent` feature]: #constituent-feature
 //! [`declare_late_lint!`, `declare_early_lint!`, `declare_pre_expansion_lint!`]: #declare_late_lint-etc
 //! [`declare_lint!`]: https://doc.rust-lang.org/nightly/nightly-rustc/rustc_session/macro.declare_lint.html
 //! [`declare_lint_pass!`]: https://doc.rust-lang.org/nightly/nightly-rustc/rustc_session/macro.declare_lint_pass.html
 //! [`dylint-link`]: https://github.com/trailofbits/dylint/tree/master/dylint-link
+//! [`dylint_config_keys!`]: https://docs.rs/dylint_linting/latest/dylint_linting/macro.dylint_config_keys.html
 //! [`dylint_library!`]: #dylint_library
+//! [`dylint_validate_config!`]: https://docs.rs/dylint_linting/latest/dylint_linting/macro.dylint_validate_config.html
 //! [`general` library]: https://github.com/trailofbits/dylint/tree/master/examples/general/src/lib.rs
 //! [`impl_late_lint!`, `impl_early_lint!`, `impl_pre_expansion_lint!`]: #impl_late_lint-etc
 //! [`impl_lint_pass!`]: https://doc.rust-lang.org/nightly/nightly-rustc/rustc_session/macro.impl_lint_pass.html
 //! [`init_config`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.init_config.html
+//! [`instrumentation_enabled`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.instrumentation_enabled.html
 //! [`non_local_effect_before_error_return`]: https://github.com/trailofbits/dylint/tree/master/examples/general/non_local_effect_before_error_return/src/lib.rs
+//! [`ref_aware_redundant_closure_for_method_calls`]: https://github.com/trailofbits/dylint/tree/master/examples/restriction/ref_aware_redundant_closure_for_method_calls/src/lib.rs
+//! [`register_group`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.register_group.html
 //! [`register_lints`]: https://doc.rust-lang.org/nightly/nightly-rustc/rustc_interface/interface/struct.Config.html#structfield.register_lints
 //! [`supplementary` library]: https://github.com/trailofbits/dylint/tree/master/examples/supplementary/src/lib.rs
+//! [`try_init_config_with_transform`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.try_init_config_with_transform.html
 //! [`try_init_config`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.try_init_config.html
 //! [docs.rs documentation]: https://docs.rs/dylint_linting/latest/dylint_linting/
 //! [docs.rs]: https://docs.rs/dylint_linting/latest/dylint_linting/
@@ -196,6 +284,7 @@
 #[allow(unused_extern_crates)]
 extern crate rustc_driver;
 
+extern crate rustc_lint;
 extern crate rustc_session;
 extern crate rustc_span;
 
@@ -203,11 +292,16 @@ use dylint_internal::{config, env};
 use rustc_span::Symbol;
 use std::{
     any::type_name,
+    collections::BTreeMap,
+    fs::read_to_string,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 pub use config::{Error as ConfigError, Result as ConfigResult};
 
+static NO_CONFIG_FILE: AtomicBool = AtomicBool::new(false);
+
 pub const DYLINT_VERSION: &str = "0.1.0";
 
 pub use paste;
@@ -231,6 +325,88 @@ macro_rules! dylint_library {
     };
 }
 
+/// Exports the configuration keys a library accepts, so that they can be listed with
+/// `cargo dylint list --configs`.
+///
+/// Example:
+///
+/// ```rust,ignore
+/// dylint_linting::dylint_config_keys!("boolean", "strings");
+/// ```
+#[macro_export]
+macro_rules! dylint_config_keys {
+    ($($key:expr),* $(,)?) => {
+        $crate::__maybe_exclude! {
+            #[doc(hidden)]
+            #[no_mangle]
+            pub extern "C" fn dylint_config_keys() -> *mut std::os::raw::c_char {
+                let keys: &[&str] = &[$($key),*];
+                std::ffi::CString::new(keys.join("\n"))
+                    .unwrap()
+                    .into_raw()
+            }
+        }
+
+        $crate::__maybe_exclude! {
+            /// Frees a string returned by `dylint_config_keys`.
+            ///
+            /// # Safety
+            ///
+            /// `keys` must have been returned by `dylint_config_keys` and not already freed.
+            #[doc(hidden)]
+            #[no_mangle]
+            pub unsafe extern "C" fn dylint_config_keys_free(keys: *mut std::os::raw::c_char) {
+                drop(unsafe { std::ffi::CString::from_raw(keys) });
+            }
+        }
+    };
+}
+
+/// Exports a function that checks whether the workspace's `dylint.toml` file can be deserialized
+/// into a library's configuration, so that the file can be checked with `cargo dylint
+/// check-config`.
+///
+/// Each argument pairs a `dylint.toml` key with the type its value should deserialize as.
+///
+/// Example:
+///
+/// ```rust,ignore
+/// dylint_linting::dylint_validate_config!(env!("CARGO_PKG_NAME") => Config);
+/// ```
+#[macro_export]
+macro_rules! dylint_validate_config {
+    ($($name:expr => $ty:ty),* $(,)?) => {
+        $crate::__maybe_exclude! {
+            #[doc(hidden)]
+            #[no_mangle]
+            pub extern "C" fn dylint_validate_config() -> *mut std::os::raw::c_char {
+                let mut errors = Vec::<String>::new();
+                $(
+                    errors.extend($crate::__config_error::<$ty>($name));
+                )*
+                std::ffi::CString::new(errors.join("\n"))
+                    .unwrap()
+                    .into_raw()
+            }
+        }
+
+        $crate::__maybe_exclude! {
+            /// Frees a string returned by `dylint_validate_config`.
+            ///
+            /// # Safety
+            ///
+            /// `errors` must have been returned by `dylint_validate_config` and not already freed.
+            #[doc(hidden)]
+            #[no_mangle]
+            pub unsafe extern "C" fn dylint_validate_config_free(
+                errors: *mut std::os::raw::c_char,
+            ) {
+                drop(unsafe { std::ffi::CString::from_raw(errors) });
+            }
+        }
+    };
+}
+
 #[cfg(not(feature = "constituent"))]
 #[doc(hidden)]
 #[macro_export]
@@ -345,6 +521,14 @@ macro_rules! impl_early_lint {
 
 #[macro_export]
 macro_rules! impl_late_lint {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, @config = $Config:ty, $pass:expr) => {
+        $crate::impl_late_lint!(
+            $(#[$attr])* $vis $NAME,
+            $Level,
+            $desc,
+            ($pass)($crate::config_or_default::<$Config>(env!("CARGO_PKG_NAME")))
+        );
+    };
     ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, $pass:expr) => {
         $crate::__declare_and_register_lint!(
             $(#[$attr])* $vis $NAME,
@@ -407,6 +591,29 @@ macro_rules! declare_late_lint {
     };
 }
 
+/// Registers a lint group named `name` containing `lints`.
+///
+/// A library containing more than one lint can use this function to let users refer to all of its
+/// lints at once, e.g., `#[allow(name)]`.
+///
+/// Example:
+///
+/// ```rust,ignore
+/// dylint_linting::register_group(lint_store, "library", &[FIRST_LINT_NAME, SECOND_LINT_NAME]);
+/// ```
+pub fn register_group(
+    lint_store: &mut rustc_lint::LintStore,
+    name: &'static str,
+    lints: &[&'static rustc_lint::Lint],
+) {
+    lint_store.register_group(
+        true,
+        name,
+        None,
+        lints.iter().copied().map(rustc_lint::LintId::of).collect(),
+    );
+}
+
 /// Reads and deserializes an entry from the workspace's `dylint.toml` file, and returns the default
 /// value if the entry is not present.
 ///
@@ -415,24 +622,90 @@ macro_rules! declare_late_lint {
 /// - If the target workspace's `dylint.toml` file does not exist or does not contain key `name`,
 ///   `config_or_default` returns `T::default()`.
 /// - If an error occurs (e.g., the value cannot be deserialized as `T`), `config_or_default`
-///   panics.
+///   panics. When possible, the panic message identifies the key whose value could not be
+///   deserialized.
 ///
 /// Note: `init_config` or `try_init_config` must be called before `config_or_default` is called.
 /// However, the `register_lints` function generated by `impl_late_lint`, etc. includes a call to
 /// `init_config`.
 pub fn config_or_default<T: Default + serde::de::DeserializeOwned>(name: &str) -> T {
     config::<T>(name).map_or_else(
-        |error| {
-            panic!(
-                "Could not parse config as `{}`: {}",
-                type_name::<T>(),
-                error
-            )
-        },
+        |error| panic!("{}", error_message::<T>(name, &error)),
         Option::unwrap_or_default,
     )
 }
 
+/// Returns a message describing why key `name` of the workspace's `dylint.toml` file could not be
+/// deserialized as `T`, or `None` if it could be. Used by [`dylint_validate_config!`].
+#[doc(hidden)]
+pub fn __config_error<T: serde::de::DeserializeOwned>(name: &str) -> Option<String> {
+    config::<T>(name)
+        .err()
+        .map(|error| error_message::<T>(name, &error))
+}
+
+fn error_message<T: serde::de::DeserializeOwned>(name: &str, error: &ConfigError) -> String {
+    let detail = config_toml(name)
+        .ok()
+        .flatten()
+        .and_then(|value| error_detail::<T>(&value));
+    format!(
+        "Could not parse `{name}` config as `{}`: {}",
+        type_name::<T>(),
+        detail.unwrap_or_else(|| error.to_string())
+    )
+}
+
+// An error from `toml::Value::try_into` does not say where in the value the problem is.
+// Deserializing the value from TOML text instead produces an error with a span. But the span is
+// into the re-serialized text, not into `dylint.toml`, so it is mapped back to a key path rather
+// than reported as a line and column.
+fn error_detail<T: serde::de::DeserializeOwned>(value: &toml::Value) -> Option<String> {
+    let text = toml::to_string(value).ok()?;
+    let error = toml::from_str::<T>(&text).err()?;
+    let message = error.message();
+    Some(
+        match error.span().and_then(|span| key_path(&text, span.start)) {
+            Some(path) => format!("{message} at key `{path}`"),
+            None => message.to_owned(),
+        },
+    )
+}
+
+// `text` is the output of `toml::to_string`, which puts each table under a `[header]` or
+// `[[header]]` line, and each other value on a `key = value` line. A path is returned only if
+// `offset` is the start of such a value. Errors about a table as a whole (e.g., a missing field)
+// have a span that covers the table, and the span could be confused with that of an enclosing
+// table.
+fn key_path(text: &str, offset: usize) -> Option<String> {
+    let mut header = None;
+    let mut counts = BTreeMap::<&str, usize>::new();
+    let mut line_start = 0;
+    for line in text.lines() {
+        if let Some(name) = line
+            .strip_prefix("[[")
+            .and_then(|rest| rest.strip_suffix("]]"))
+        {
+            let count = counts.entry(name).or_default();
+            header = Some(format!("{name}[{count}]"));
+            *count += 1;
+        } else if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            header = Some(name.to_owned());
+        } else if let Some((key, _)) = line.split_once(" = ") {
+            if line_start + key.len() + " = ".len() == offset {
+                return Some(
+                    header.map_or_else(|| key.to_owned(), |header| format!("{header}.{key}")),
+                );
+            }
+        }
+        line_start += line.len() + 1;
+    }
+    None
+}
+
 /// Reads and deserializes an entry from the workspace's `dylint.toml` file.
 ///
 /// Returns:
@@ -451,6 +724,83 @@ pub fn config<T: serde::de::DeserializeOwned>(name: &str) -> ConfigResult<Option
         .map_err(Into::into)
 }
 
+/// Reads and deserializes a nested entry from the workspace's `dylint.toml` file. `dotted` is a
+/// `.`-separated sequence of keys, e.g., `"my_lint.advanced.threshold"`.
+///
+/// Returns:
+/// - `Ok(Some(...))` if the target workspace's `dylint.toml` file contains the entry at `dotted`
+///   and its value can be deserialized as `T`
+/// - `Ok(None)` if the target workspace's `dylint.toml` file does not exist or any key in `dotted`
+///   is missing
+/// - `Err(...)` if a key in `dotted` other than the last has a value that is not a table, or if
+///   another error occurs (e.g., the value cannot be deserialized as `T`)
+///
+/// Note: `init_config` or `try_init_config` must be called before `config_path` is called. However,
+/// the `register_lints` function generated by `impl_late_lint`, etc. includes a call to
+/// `init_config`.
+pub fn config_path<T: serde::de::DeserializeOwned>(dotted: &str) -> ConfigResult<Option<T>> {
+    let mut table = config_table()?;
+    let mut keys = dotted.split('.');
+    let last = keys
+        .next_back()
+        .expect("`split` should return at least one item");
+    let mut prefix = Vec::new();
+    for key in keys {
+        prefix.push(key);
+        let Some(value) = table.get(key) else {
+            return Ok(None);
+        };
+        table = value
+            .as_table()
+            .ok_or_else(|| ConfigError::other(format!("`{}` is not a table", prefix.join("."))))?;
+    }
+    table
+        .get(last)
+        .cloned()
+        .map(toml::Value::try_into::<T>)
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Whether the workspace's `dylint.toml` file exists and contains a given key
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Presence {
+    /// The target workspace has no `dylint.toml` file.
+    NoFile,
+    /// The target workspace's `dylint.toml` file exists but does not contain the key.
+    FilePresentKeyAbsent,
+    /// The target workspace's `dylint.toml` file contains the key.
+    KeyPresent,
+}
+
+/// Like [`config`], but also reports whether the workspace's `dylint.toml` file exists and contains
+/// key `name`.
+///
+/// [`config`] returns `Ok(None)` both when the `dylint.toml` file does not exist and when it does
+/// not contain key `name`. `config_with_presence` allows a library to distinguish the two, e.g., to
+/// warn a user who has a `dylint.toml` file but has not configured the library.
+///
+/// A `dylint.toml` file provided through the `DYLINT_TOML` environment variable is considered to
+/// exist.
+///
+/// Note: `init_config` or `try_init_config` must be called before `config_with_presence` is called.
+/// However, the `register_lints` function generated by `impl_late_lint`, etc. includes a call to
+/// `init_config`.
+pub fn config_with_presence<T: serde::de::DeserializeOwned>(
+    name: &str,
+) -> ConfigResult<(Option<T>, Presence)> {
+    let config_table = config_table()?;
+    let presence = if NO_CONFIG_FILE.load(Ordering::SeqCst) {
+        Presence::NoFile
+    } else if config_table.contains_key(name) {
+        Presence::KeyPresent
+    } else {
+        Presence::FilePresentKeyAbsent
+    };
+    let value = config::<T>(name)?;
+    Ok((value, presence))
+}
+
 /// Reads an entry from the workspace's `dylint.toml` file as a raw `toml::Value`.
 ///
 /// Returns:
@@ -461,18 +811,117 @@ pub fn config<T: serde::de::DeserializeOwned>(name: &str) -> ConfigResult<Option
 ///
 /// Note: `init_config` or `try_init_config` must be called before `config_toml` is called. However,
 /// the `register_lints` function generated by `impl_late_lint`, etc. includes a call to
-/// `init_config`.
+/// `init_config`. If neither was called but the `DYLINT_TOML` environment variable is set,
+/// `config_toml` initializes the config from that variable. In that case, `DYLINT_TOML` is not
+/// recorded in the target crate's dependency information, so changing it does not cause the target
+/// crate to be rechecked.
 pub fn config_toml(name: &str) -> ConfigResult<Option<toml::Value>> {
-    let Some(config_table) = config::get() else {
-        return Err(ConfigError::other(
-            "Config is not initialized; `init_config` should have been called from \
-             `register_lints`"
-                .into(),
-        ));
-    };
+    let config_table = config_table()?;
     Ok(config_table.get(name).cloned())
 }
 
+/// Returns the top-level keys in the workspace's `dylint.toml` file.
+///
+/// Returns:
+/// - `Ok(...)` if the config was initialized (the result is empty if the target workspace's
+///   `dylint.toml` file does not exist)
+/// - `Err(...)` if an error occurs (e.g., `init_config` was not called)
+///
+/// Note: `init_config` or `try_init_config` must be called before `config_keys` is called. However,
+/// the `register_lints` function generated by `impl_late_lint`, etc. includes a call to
+/// `init_config`.
+pub fn config_keys() -> ConfigResult<Vec<String>> {
+    let config_table = config_table()?;
+    Ok(config_table.keys().cloned().collect())
+}
+
+/// A trade-off between speed and thoroughness, selected by the `name` key of the `[profile]` table
+/// in the workspace's `dylint.toml` file
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    Fast,
+    Thorough,
+}
+
+#[derive(Default, serde::Deserialize)]
+struct ProfileTable {
+    name: Option<Profile>,
+}
+
+/// Returns the profile selected in the workspace's `dylint.toml` file, if any.
+///
+/// A library can use the profile to choose defaults for its own performance-related settings,
+/// e.g.:
+///
+/// ```rust,ignore
+/// let work_limit = config.work_limit.unwrap_or_else(|| {
+///     match dylint_linting::config_profile() {
+///         Some(dylint_linting::Profile::Fast) => 50_000,
+///         Some(dylint_linting::Profile::Thorough) => 5_000_000,
+///         None => 500_000,
+///     }
+/// });
+/// ```
+///
+/// Like `config_or_default`, `config_profile` panics if the `[profile]` table cannot be parsed.
+///
+/// Note: `init_config` or `try_init_config` must be called before `config_profile` is called.
+/// However, the `register_lints` function generated by `impl_late_lint`, etc. includes a call to
+/// `init_config`.
+pub fn config_profile() -> Option<Profile> {
+    config_or_default::<ProfileTable>("profile").name
+}
+
+/// Returns true if instrumentation flag `flag` is enabled.
+///
+/// `flag` is enabled if:
+/// - the environment variable named `flag` is set to a value other than `0`, or
+/// - the environment variable is not set, and the `[instrumentation]` table in the workspace's
+///   `dylint.toml` file maps `flag` converted to lowercase to `true`.
+///
+/// So, for example, setting `MY_LINT_DEBUG=0` disables `MY_LINT_DEBUG` even if the `dylint.toml`
+/// file enables it.
+///
+/// Unlike most functions in this crate, `instrumentation_enabled` can be called when the config is
+/// uninitialized, e.g., from a library's tests. In that case, only the environment is consulted.
+///
+/// Like `config_or_default`, `instrumentation_enabled` panics if the `[instrumentation]` table
+/// cannot be parsed.
+#[must_use]
+pub fn instrumentation_enabled(flag: &str) -> bool {
+    if let Ok(value) = std::env::var(flag) {
+        return value != "0";
+    }
+    if config_table().is_err() {
+        return false;
+    }
+    let key = format!("instrumentation.{}", flag.to_lowercase());
+    config_path::<bool>(&key)
+        .unwrap_or_else(|err| panic!("Could not parse `{key}`: {err}"))
+        .unwrap_or_default()
+}
+
+// If the config is uninitialized but `DYLINT_TOML` is set, initialize the config from
+// `DYLINT_TOML`. Unlike `try_init_config_guarded`, this cannot record `DYLINT_TOML` in
+// `env_depinfo`, because no session is available here. Lints may call this function from several
+// threads at once, so the config is initialized with `get_or_init_from_string`, which does not
+// panic if another thread initialized the config first.
+fn config_table() -> ConfigResult<&'static toml::value::Table> {
+    if let Some(config_table) = config::get() {
+        return Ok(config_table);
+    }
+
+    if let Ok(value) = std::env::var(env::DYLINT_TOML) {
+        return config::get_or_init_from_string(&value);
+    }
+
+    Err(ConfigError::other(
+        "Config is not initialized; `init_config` should have been called from `register_lints`"
+            .into(),
+    ))
+}
+
 /// A wrapper around `try_init_config`. Calls `rustc_session::early_error` if `try_init_config`
 /// returns an error.
 ///
@@ -508,12 +957,29 @@ impl ParseSess for rustc_session::Session {
 /// `config_toml` is called. However, the `register_lints` function generated by `impl_late_lint`,
 /// etc. includes a call to `init_config`.
 pub fn try_init_config(sess: &rustc_session::Session) -> ConfigResult<()> {
-    let result = try_init_config_guarded(sess);
+    try_init_config_with_transform(sess, |value| value)
+}
+
+/// Like [`try_init_config`], but applies `transform` to the contents of the `dylint.toml` file (or
+/// of the `DYLINT_TOML` environment variable) before they are parsed. This allows the contents to
+/// be preprocessed, e.g., to fill in values from a CI environment.
+///
+/// Note that the dependency information recorded for incremental compilation reflects the
+/// original contents, not the transformed ones. So if `transform`'s output depends on something
+/// other than its input (e.g., an environment variable), changing that thing will not cause the
+/// target crate to be rechecked.
+pub fn try_init_config_with_transform(
+    sess: &rustc_session::Session,
+    transform: impl FnOnce(String) -> String,
+) -> ConfigResult<()> {
+    let result = try_init_config_guarded(sess, transform);
 
     // smoelius: If we're returning `Ok(())`, ensure that `config::get()` will later return
-    // `Some(..)`.
+    // `Some(..)`. Reaching here with the config uninitialized means no `dylint.toml` file was
+    // found, which `config_with_presence` reports.
     if result.is_ok() && config::get().is_none() {
         config::init_from_string("").unwrap();
+        NO_CONFIG_FILE.store(true, Ordering::SeqCst);
     }
 
     result
@@ -521,13 +987,16 @@ pub fn try_init_config(sess: &rustc_session::Session) -> ConfigResult<()> {
 
 #[allow(clippy::empty_line_after_outer_attr)]
 #[cfg_attr(dylint_lib = "supplementary", allow(commented_code))]
-fn try_init_config_guarded(sess: &rustc_session::Session) -> ConfigResult<()> {
+fn try_init_config_guarded(
+    sess: &rustc_session::Session,
+    transform: impl FnOnce(String) -> String,
+) -> ConfigResult<()> {
     if config::get().is_some() {
         return Ok(());
     }
 
     if let Ok(value) = std::env::var(env::DYLINT_TOML) {
-        config::init_from_string(&value)?;
+        config::init_from_string(&transform(value.clone()))?;
         sess.parse_sess().env_depinfo.lock().insert((
             Symbol::intern(env::DYLINT_TOML),
             Some(Symbol::intern(&value)),
@@ -585,11 +1054,28 @@ fn try_init_config_guarded(sess: &rustc_session::Session) -> ConfigResult<()> {
 
     match result {
         Err(cargo_metadata::Error::CargoMetadata { stderr })
-            if stderr.contains("could not find `Cargo.toml`") => {}
+            if stderr.contains("could not find `Cargo.toml`") =>
+        {
+            // There is no workspace (e.g., `rustc` was invoked directly on a single
+            // file). Look for a `dylint.toml` file alongside the source file.
+            let dylint_toml = parent.join("dylint.toml");
+            if dylint_toml.is_file() {
+                let value = read_to_string(&dylint_toml).map_err(|error| {
+                    ConfigError::other(format!(
+                        "`read_to_string` failed for {dylint_toml:?}: {error}"
+                    ))
+                })?;
+                config::init_from_string(&transform(value))?;
+                sess.parse_sess()
+                    .file_depinfo
+                    .lock()
+                    .insert(Symbol::intern(&dylint_toml.to_string_lossy()));
+            }
+        }
         _ => {
             let metadata = result?;
 
-            let value = config::try_init_with_metadata(&metadata)?;
+            let value = config::try_init_with_metadata_and_transform(&metadata, transform)?;
 
             if let Some(s) = &value {
                 sess.parse_sess()
@@ -664,3 +1150,136 @@ fn early_error(msg: impl Into<rustc_errors::DiagMessage>) -> ! {
         rustc_session::EarlyDiagCtxt::new(rustc_session::config::ErrorOutputType::default());
     handler.early_fatal(msg)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+    use std::sync::{Mutex, MutexGuard};
+
+    const DYLINT_TOML: &str = r#"
+[my_lint]
+work_limit = 1
+
+[my_lint.advanced]
+threshold = 3
+"#;
+
+    static MUTEX: Mutex<()> = Mutex::new(());
+
+    // Nothing in these tests calls `init_config`. So the first call to `config_table` initializes
+    // the config from `DYLINT_TOML`. The lock prevents two tests from setting `DYLINT_TOML` at
+    // once.
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    fn lock_and_set_dylint_toml() -> MutexGuard<'static, ()> {
+        let lock = MUTEX.lock().unwrap();
+        std::env::set_var(env::DYLINT_TOML, DYLINT_TOML);
+        lock
+    }
+
+    #[test]
+    fn config_toml_lazy_init() {
+        let _lock = lock_and_set_dylint_toml();
+
+        let value = config_toml("my_lint").unwrap().unwrap();
+        assert_eq!(
+            Some(1),
+            value.get("work_limit").and_then(toml::Value::as_integer)
+        );
+        assert!(config_toml("other_lint").unwrap().is_none());
+    }
+
+    // Whichever thread initializes the config first, the others must see the same config rather
+    // than panic.
+    #[test]
+    fn config_toml_concurrent_lazy_init() {
+        let _lock = lock_and_set_dylint_toml();
+
+        std::thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|_| scope.spawn(|| config_toml("my_lint").unwrap()))
+                .collect::<Vec<_>>();
+            for handle in handles {
+                assert!(handle.join().unwrap().is_some());
+            }
+        });
+    }
+
+    #[test]
+    fn config_path_nested() {
+        let _lock = lock_and_set_dylint_toml();
+
+        assert_eq!(
+            Some(3),
+            config_path::<u64>("my_lint.advanced.threshold").unwrap()
+        );
+    }
+
+    #[test]
+    fn config_path_missing_segment() {
+        let _lock = lock_and_set_dylint_toml();
+
+        assert_eq!(
+            None,
+            config_path::<u64>("my_lint.missing.threshold").unwrap()
+        );
+        assert_eq!(
+            None,
+            config_path::<u64>("other_lint.advanced.threshold").unwrap()
+        );
+    }
+
+    #[test]
+    fn config_path_non_table() {
+        let _lock = lock_and_set_dylint_toml();
+
+        let error = config_path::<u64>("my_lint.work_limit.threshold").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("`my_lint.work_limit` is not a table"),
+            "{error}"
+        );
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        work_limit: Option<u64>,
+        advanced: Option<Advanced>,
+        list: Option<Vec<Advanced>>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize)]
+    struct Advanced {
+        threshold: u64,
+    }
+
+    #[test]
+    fn error_detail_names_key() {
+        let cases = [
+            (
+                "work_limit = \"x\"",
+                "invalid type: string \"x\", expected u64 at key `work_limit`",
+            ),
+            (
+                "[advanced]\nthreshold = -1",
+                "invalid value: integer `-1`, expected u64 at key `advanced.threshold`",
+            ),
+            (
+                "[[list]]\nthreshold = 1\n[[list]]\nthreshold = true",
+                "invalid type: boolean `true`, expected u64 at key `list[1].threshold`",
+            ),
+            ("[advanced]", "missing field `threshold`"),
+        ];
+        for (toml, expected) in cases {
+            let value = toml::from_str::<toml::Value>(toml).unwrap();
+            assert_eq!(
+                Some(expected),
+                error_detail::<Config>(&value).as_deref(),
+                "{toml:?}"
+            );
+        }
+    }
+}
@@ -38,10 +38,59 @@
 //!
 //! A `Test` instance has the following methods:
 //!
+//! - `allow_empty` - do not fail if there are no example targets (with `ui::Test::examples`)
+//! - `check_fixed_idempotent` - fail if running the library on a `.fixed` file produces
+//!   machine-applicable suggestions
+//! - `driver` - run the test with a given driver rather than one built by Dylint
 //! - `dylint_toml` - set the `dylint.toml` file's contents (for testing [configurable libraries])
+//! - `expect_no_diagnostics` - fail if any source file produces diagnostics, rather than comparing
+//!   to `.stderr` files
+//! - `filter` - run only source files whose names match a pattern
+//! - `manifest_path` - link source files against the dependencies of a given package (with
+//!   `ui::Test::src_base`)
+//! - `normalize` - replace matches of a regular expression in the test's standard error
+//! - `only_on` - run the test only on hosts with a given architecture, operating system, or family
 //! - `rustc_flags` - pass flags to the compiler when running the test
+//! - `timeout` - abort the whole test binary, along with every other test it is running, if the
+//!   test does not finish within a given duration
+//! - `toolchain` - build the library and driver for, and run the test with, a specific toolchain
+//! - `ui_testing` - control whether `-Zui-testing` is passed to the compiler (default `true`)
 //! - `run` - run the test
 //!
+//! In addition, [`assert_span_snippet`] runs a library on a single source file and checks that the
+//! primary span of one of its diagnostics covers exactly some expected text. This allows a
+//! suggestion's span to be tested without depending on the wording of the diagnostic's message. For
+//! example:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn span() {
+//!     dylint_testing::assert_span_snippet(
+//!         env!("CARGO_PKG_NAME"),
+//!         "#![allow(dead_code)]\n",
+//!         "crate_wide_allow",
+//!         "#![allow(dead_code)]",
+//!     );
+//! }
+//! ```
+//!
+//! To iterate over a package's example targets, e.g., to run the same check on each of them within
+//! a single test, use [`example_names`], as follows:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn examples_have_stderr_files() {
+//!     for example in dylint_testing::example_names(env!("CARGO_PKG_NAME")).unwrap() {
+//!         assert!(std::path::Path::new(&example).join("main.stderr").exists());
+//!     }
+//! }
+//! ```
+//!
+//! By default, the library under test is built before its tests are run. If the library has already
+//! been built, e.g., when running the tests repeatedly without changing the library's source,
+//! setting the `DYLINT_TESTING_NO_BUILD` environment variable to `1` skips the build, and the
+//! library already in `target/debug` is used. The tests fail if no such library exists.
+//!
 //! # Updating `.stderr` files
 //!
 //! If the standard error that results from running your `.rs` file differs from the contents of
@@ -94,7 +143,9 @@
 //! Additional documentation on `compiletest_rs` can be found in [its repository].
 //!
 //! [Dylint]: https://github.com/trailofbits/dylint/tree/master
+//! [`assert_span_snippet`]: https://docs.rs/dylint_testing/latest/dylint_testing/fn.assert_span_snippet.html
 //! [`compiletest_rs`]: https://github.com/Manishearth/compiletest-rs
+//! [`example_names`]: https://docs.rs/dylint_testing/latest/dylint_testing/fn.example_names.html
 //! [`non_thread_safe_call_in_test`]: https://github.com/trailofbits/dylint/tree/master/examples/general/non_thread_safe_call_in_test/src/lib.rs
 //! [`question_mark_in_expression`]: https://github.com/trailofbits/dylint/tree/master/examples/restriction/question_mark_in_expression/Cargo.toml
 //! [`ui::Test::example`]: https://docs.rs/dylint_testing/latest/dylint_testing/ui/struct.Test.html#method.example
@@ -110,23 +161,31 @@
 //! [its repository]: https://github.com/Manishearth/compiletest-rs
 
 use anyhow::{anyhow, ensure, Context, Result};
-use cargo_metadata::{Metadata, Package, Target, TargetKind};
+use cargo_metadata::{
+    diagnostic::{Applicability, Diagnostic},
+    DependencyKind, Message, Metadata, MetadataCommand, Package, Target, TargetKind,
+};
 use compiletest_rs as compiletest;
 use dylint_internal::{env, library_filename, rustup::is_rustc, CommandExt};
 use once_cell::sync::{Lazy, OnceCell};
 use regex::Regex;
 use std::{
-    env::{consts, remove_var, set_var, var_os},
+    collections::BTreeMap,
+    env::{consts, remove_var, set_var, var, var_os},
     ffi::{OsStr, OsString},
-    fs::{copy, read_dir, remove_file},
-    io::BufRead,
+    fs::{copy, read_dir, remove_file, write},
+    io::{BufRead, BufReader},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
-    sync::Mutex,
+    process::Command,
+    sync::{mpsc::channel, Mutex},
+    thread::{self, spawn},
+    time::Duration,
 };
 
 pub mod ui;
 
-static DRIVER: OnceCell<PathBuf> = OnceCell::new();
+static DRIVER: OnceCell<(String, PathBuf)> = OnceCell::new();
 static LINKING_FLAGS: OnceCell<Vec<String>> = OnceCell::new();
 
 /// Test a library on all source files in a directory.
@@ -155,7 +214,89 @@ pub fn ui_test_examples(name: &str) {
     ui::Test::examples(name).run();
 }
 
-fn initialize(name: &str) -> Result<&Path> {
+/// Run a library on a single source file and check the span of one of its diagnostics.
+///
+/// - `name` is the name of a Dylint library to be tested.
+/// - `source` is the contents of the source file. The file is compiled as a library crate.
+/// - `lint_name` is the name of the lint whose diagnostic should be checked.
+/// - `expected_snippet` is the text that the diagnostic's primary span should cover.
+///
+/// # Panics
+///
+/// Panics if the library does not emit a diagnostic for `lint_name`, or if the first such
+/// diagnostic's primary span does not cover exactly `expected_snippet`.
+pub fn assert_span_snippet(name: &str, source: &str, lint_name: &str, expected_snippet: &str) {
+    let driver = initialize(name, None, None).unwrap();
+    let snippet = span_snippet(driver, source, lint_name).unwrap();
+    assert_eq!(
+        expected_snippet, snippet,
+        "the primary span of the `{lint_name}` diagnostic does not cover the expected text"
+    );
+}
+
+/// Return the names of a package's example targets.
+///
+/// - `name` is the name of a package in the current workspace.
+///
+/// # Errors
+///
+/// Returns an error if the workspace's metadata cannot be read, or if the workspace has no package
+/// named `name`.
+pub fn example_names(name: &str) -> Result<Vec<String>> {
+    let metadata = dylint_internal::cargo::current_metadata()?;
+    let package = metadata
+        .workspace_packages()
+        .into_iter()
+        .find(|package| package.name == name)
+        .ok_or_else(|| anyhow!("Could not find package `{}`", name))?;
+    let targets = example_targets(package)?;
+    Ok(targets.into_iter().map(|target| target.name).collect())
+}
+
+fn span_snippet(driver: &Path, source: &str, lint_name: &str) -> Result<String> {
+    let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+    let path = tempdir.path().join("lib.rs");
+    write(&path, source).with_context(|| format!("Could not write to {path:?}"))?;
+
+    let output = Command::new(driver)
+        .arg("rustc")
+        .arg(&path)
+        .args([
+            "--crate-type=lib",
+            "--edition=2021",
+            "--emit=metadata",
+            "--error-format=json",
+            &format!("--warn={lint_name}"),
+            "--out-dir",
+        ])
+        .arg(tempdir.path())
+        .logged_output(false)?;
+
+    let stderr = String::from_utf8(output.stderr)?;
+    let diagnostic = stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+        .find(|diagnostic| {
+            diagnostic
+                .code
+                .as_ref()
+                .is_some_and(|code| code.code == lint_name)
+        })
+        .ok_or_else(|| anyhow!("Found no diagnostic for `{lint_name}`; stderr:\n{stderr}"))?;
+    let span = diagnostic
+        .spans
+        .iter()
+        .find(|span| span.is_primary)
+        .ok_or_else(|| anyhow!("Diagnostic for `{lint_name}` has no primary span"))?;
+    source
+        .get(span.byte_start as usize..span.byte_end as usize)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| anyhow!("Primary span is out of bounds: {span:?}"))
+}
+
+fn initialize(name: &str, toolchain: Option<&str>, driver: Option<&Path>) -> Result<&'static Path> {
+    let toolchain = toolchain.unwrap_or(env!("RUSTUP_TOOLCHAIN"));
+
     DRIVER
         .get_or_try_init(|| {
             let _ = env_logger::try_init();
@@ -163,9 +304,12 @@ fn initialize(name: &str) -> Result<&Path> {
             // smoelius: Try to order failures by how informative they are: failure to build the
             // library, failure to find the library, failure to build/find the driver.
 
-            dylint_internal::cargo::build(&format!("library `{name}`"))
-                .build()
-                .success()?;
+            if !env::enabled(env::DYLINT_TESTING_NO_BUILD) {
+                dylint_internal::cargo::build(&format!("library `{name}`"))
+                    .build()
+                    .envs([(env::RUSTUP_TOOLCHAIN, toolchain)])
+                    .success()?;
+            }
 
             // smoelius: `DYLINT_LIBRARY_PATH` must be set before `dylint_libs` is called.
             // smoelius: This was true when `dylint_libs` called `name_toolchain_map`, but that is
@@ -175,26 +319,81 @@ fn initialize(name: &str) -> Result<&Path> {
             let dylint_library_path = metadata.target_directory.join("debug");
             set_var(env::DYLINT_LIBRARY_PATH, dylint_library_path);
 
-            let dylint_libs = dylint_libs(name)?;
-            let driver = dylint::driver_builder::get(
-                &dylint::opts::Dylint::default(),
-                env!("RUSTUP_TOOLCHAIN"),
-            )?;
+            let dylint_libs = dylint_libs_for_toolchain(name, toolchain)?;
+            let driver = if let Some(driver) = driver {
+                check_driver_version(toolchain, driver)?;
+                driver.to_owned()
+            } else {
+                dylint::driver_builder::get(&dylint::opts::Dylint::default(), toolchain)?
+            };
 
             set_var(env::CLIPPY_DISABLE_DOCS_LINKS, "true");
             set_var(env::DYLINT_LIBS, dylint_libs);
 
-            Ok(driver)
+            Ok((toolchain.to_owned(), driver))
+        })
+        .and_then(|(initialized_toolchain, initialized_driver)| {
+            // The library and driver are built once per process. So every test in a
+            // process must use the same toolchain and driver.
+            ensure!(
+                initialized_toolchain == toolchain,
+                "Tests were initialized with toolchain `{initialized_toolchain}`, but toolchain \
+                 `{toolchain}` was requested"
+            );
+            if let Some(driver) = driver {
+                ensure!(
+                    initialized_driver == driver,
+                    "Tests were initialized with driver `{}`, but driver `{}` was requested",
+                    initialized_driver.display(),
+                    driver.display()
+                );
+            }
+            Ok(initialized_driver.as_path())
         })
-        .map(PathBuf::as_path)
+}
+
+// A driver passed to `ui::Test::driver` is not built by Dylint, so there is no guarantee
+// that it understands the environment variables that `dylint_testing` sets. Check its version
+// before running any tests with it.
+fn check_driver_version(toolchain: &str, driver: &Path) -> Result<()> {
+    let output = dylint_internal::driver(toolchain, driver)?
+        .arg("-V")
+        .logged_output(true)?;
+    let stdout = std::str::from_utf8(&output.stdout)?;
+    let version = stdout
+        .trim_end()
+        .rsplit_once(' ')
+        .map(|(_, version)| version)
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not determine version of driver `{}`",
+                driver.display()
+            )
+        })?;
+    ensure!(
+        version == env!("CARGO_PKG_VERSION"),
+        "Driver `{}` has version `{version}`, but `{}` was expected",
+        driver.display(),
+        env!("CARGO_PKG_VERSION")
+    );
+    Ok(())
 }
 
 #[doc(hidden)]
 pub fn dylint_libs(name: &str) -> Result<String> {
-    let metadata = dylint_internal::cargo::current_metadata().unwrap();
     let rustup_toolchain = env::var(env::RUSTUP_TOOLCHAIN)?;
-    let filename = library_filename(name, &rustup_toolchain);
+    dylint_libs_for_toolchain(name, &rustup_toolchain)
+}
+
+fn dylint_libs_for_toolchain(name: &str, toolchain: &str) -> Result<String> {
+    let metadata = dylint_internal::cargo::current_metadata().unwrap();
+    let filename = library_filename(name, toolchain);
     let path = metadata.target_directory.join("debug").join(filename);
+    ensure!(
+        !env::enabled(env::DYLINT_TESTING_NO_BUILD) || path.exists(),
+        "`{}` is set, but `{path}` does not exist; build the library first",
+        env::DYLINT_TESTING_NO_BUILD
+    );
     let paths = vec![path];
     serde_json::to_string(&paths).map_err(Into::into)
 }
@@ -279,6 +478,88 @@ fn linking_flags(
         .map(Vec::as_slice)
 }
 
+// Unlike `linking_flags`, `manifest_linking_flags` does not need to observe a `rustc`
+// invocation. Cargo reports each artifact's filenames in its JSON messages, even for artifacts
+// that are fresh, so the `--extern` flags can be built from those.
+//
+// The dependencies are built with `toolchain`, if provided, because the driver can load only
+// crates built by its own compiler. Otherwise, they are built with the toolchain the test is run
+// with, just as the driver is.
+fn manifest_linking_flags(manifest_path: &Path, toolchain: Option<&str>) -> Result<Vec<String>> {
+    let metadata = MetadataCommand::new().manifest_path(manifest_path).exec()?;
+    let package = metadata.root_package().ok_or_else(|| {
+        anyhow!(
+            "`{}` does not have a root package",
+            manifest_path.to_string_lossy()
+        )
+    })?;
+    let node = metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| resolve.nodes.iter().find(|node| node.id == package.id))
+        .ok_or_else(|| anyhow!("Could not find `{}` in resolve graph", package.name))?;
+    let deps = node
+        .deps
+        .iter()
+        .filter(|dep| {
+            dep.dep_kinds
+                .iter()
+                .any(|info| info.kind == DependencyKind::Normal)
+        })
+        .map(|dep| (&dep.pkg, &dep.name))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut command =
+        dylint_internal::cargo::build(&format!("`{}` dependencies", package.name)).build();
+    if let Some(toolchain) = toolchain {
+        command.env(env::RUSTUP_TOOLCHAIN, toolchain);
+    }
+    let output = command
+        .envs([(env::CARGO_TERM_COLOR, "never")])
+        .args([
+            "--manifest-path",
+            package.manifest_path.as_ref(),
+            "--message-format=json",
+        ])
+        .logged_output(true)?;
+
+    let mut linking_flags = vec![
+        format!("--edition={}", package.edition.as_str()),
+        "-L".to_owned(),
+        format!(
+            "dependency={}",
+            metadata.target_directory.join("debug/deps")
+        ),
+    ];
+
+    for message in Message::parse_stream(BufReader::new(output.stdout.as_slice())) {
+        let Message::CompilerArtifact(artifact) = message? else {
+            continue;
+        };
+        let Some(name) = deps.get(&artifact.package_id) else {
+            continue;
+        };
+        if !artifact.target.kind.iter().any(|kind| {
+            matches!(
+                kind,
+                TargetKind::Lib | TargetKind::RLib | TargetKind::ProcMacro
+            )
+        }) {
+            continue;
+        }
+        // Prefer an `.rlib` to an `.rmeta`. A proc macro has only a dynamic library.
+        let path = artifact
+            .filenames
+            .iter()
+            .find(|path| path.extension() == Some("rlib"))
+            .or_else(|| artifact.filenames.first())
+            .ok_or_else(|| anyhow!("Found no filenames for `{}`", artifact.package_id))?;
+        linking_flags.extend(["--extern".to_owned(), format!("{name}={path}")]);
+    }
+
+    Ok(linking_flags)
+}
+
 // smoelius: We need to recover the `rustc` flags used to build a target. I can see four options:
 //
 // * Use `cargo build --build-plan`
@@ -422,6 +703,41 @@ fn run_tests(driver: &Path, src_base: &Path, config: &ui::Config) {
         .as_ref()
         .map(|value| VarGuard::set(env::DYLINT_TOML, value));
 
+    // The driver uses `RUSTUP_TOOLCHAIN` to find the sysroot.
+    let _toolchain_var = config
+        .toolchain
+        .as_ref()
+        .map(|value| VarGuard::set(env::RUSTUP_TOOLCHAIN, value));
+
+    // `compiletest` normalizes standard error only according to per-file headers, so the
+    // driver does the normalizing.
+    let _normalize_var = if config.normalizations.is_empty() {
+        None
+    } else {
+        let normalizations = serde_json::to_string(&config.normalizations).unwrap();
+        Some(VarGuard::set(env::DYLINT_NORMALIZE_STDERR, normalizations))
+    };
+
+    let _timeout_var = config
+        .timeout
+        .map(|timeout| VarGuard::set(env::DYLINT_DRIVER_TIMEOUT, timeout.as_millis().to_string()));
+
+    let timeout = config.timeout;
+
+    let filters = config
+        .filters
+        .iter()
+        .cloned()
+        .chain(var(env::DYLINT_TESTNAME).ok())
+        .collect::<Vec<_>>();
+
+    if config.expect_no_diagnostics {
+        run_tests_expecting_no_diagnostics(driver, src_base, config, &filters).unwrap();
+        return;
+    }
+
+    let ui_config = config;
+
     let config = compiletest::Config {
         mode: compiletest::common::Mode::Ui,
         rustc_path: driver.to_path_buf(),
@@ -434,12 +750,191 @@ fn run_tests(driver: &Path, src_base: &Path, config: &ui::Config) {
                 } else {
                     ""
                 }
-                + " -Zui-testing",
+                + if config.ui_testing {
+                    " -Zui-testing"
+                } else {
+                    ""
+                },
         ),
+        filters: filters.clone(),
         ..compiletest::Config::default()
     };
 
-    compiletest::run_tests(&config);
+    // `compiletest` can panic with an opaque message, e.g., when the driver path is wrong
+    // or the toolchain is mismatched. So catch the panic, add context, and resume unwinding.
+    let result = if let Some(timeout) = timeout {
+        run_tests_with_timeout(config.clone(), timeout)
+    } else {
+        catch_unwind(AssertUnwindSafe(|| compiletest::run_tests(&config)))
+    };
+    if let Err(payload) = result {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("<non-string panic payload>"));
+        resume_unwind(Box::new(format!(
+            "{message}\ndriver: {}\nsrc_base: {}\ntarget_rustcflags: {}",
+            config.rustc_path.to_string_lossy(),
+            config.src_base.to_string_lossy(),
+            config.target_rustcflags.as_deref().unwrap_or_default()
+        )));
+    }
+
+    if ui_config.check_fixed_idempotent {
+        check_fixed_idempotent(driver, src_base, ui_config, &filters).unwrap();
+    }
+}
+
+// Running the driver directly, rather than through `compiletest`, means the source files
+// need no `.stderr` files. The flags are those `run_tests` passes to `compiletest`. Standard error
+// that is only whitespace (e.g., the usual trailing blank line) is considered empty.
+fn run_tests_expecting_no_diagnostics(
+    driver: &Path,
+    src_base: &Path,
+    config: &ui::Config,
+    filters: &[String],
+) -> Result<()> {
+    let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+
+    let mut failures = Vec::new();
+
+    for path in source_files(src_base, filters)? {
+        let path_str = path.to_string_lossy();
+
+        let mut command = Command::new(driver);
+        command
+            .arg("rustc")
+            .arg(&path)
+            .args(&config.rustc_flags)
+            .args(["--emit=metadata", "--out-dir"])
+            .arg(tempdir.path());
+        if cfg!(feature = "deny_warnings") {
+            command.arg("-Dwarnings");
+        }
+        if config.ui_testing {
+            command.arg("-Zui-testing");
+        }
+        let output = command.logged_output(false)?;
+
+        let stderr = String::from_utf8(output.stderr)?;
+        if !stderr.trim().is_empty() {
+            failures.push(format!("{path_str}:\n{stderr}"));
+        }
+    }
+
+    ensure!(
+        failures.is_empty(),
+        "Expected no diagnostics, but found the following:\n\n{}",
+        failures.join("\n")
+    );
+
+    Ok(())
+}
+
+// If a lint's suggestions were applied correctly, running the lint on the `.fixed` file
+// should produce no further machine-applicable suggestions. The `.fixed` file is copied to a `.rs`
+// file with the same name as the source file so that the crate name is the same.
+fn check_fixed_idempotent(
+    driver: &Path,
+    src_base: &Path,
+    config: &ui::Config,
+    filters: &[String],
+) -> Result<()> {
+    let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+
+    let mut failures = Vec::new();
+
+    for path in source_files(src_base, filters)? {
+        let fixed = path.with_extension("fixed");
+        if !fixed.exists() {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Could not get file name"))?;
+        let to = tempdir.path().join(file_name);
+        copy(&fixed, &to).with_context(|| format!("Could not copy {fixed:?} to {to:?}"))?;
+
+        let output = Command::new(driver)
+            .arg("rustc")
+            .arg(&to)
+            .args(&config.rustc_flags)
+            .args(["--emit=metadata", "--error-format=json", "--out-dir"])
+            .arg(tempdir.path())
+            .logged_output(false)?;
+
+        let stderr = String::from_utf8(output.stderr)?;
+        let rendered = stderr
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Diagnostic>(line).ok())
+            .filter(has_machine_applicable_suggestion)
+            .filter_map(|diagnostic| diagnostic.rendered)
+            .collect::<String>();
+        if !rendered.is_empty() {
+            failures.push(format!("{}:\n{rendered}", fixed.to_string_lossy()));
+        }
+    }
+
+    ensure!(
+        failures.is_empty(),
+        "Expected `.fixed` files to produce no machine-applicable suggestions, but found the \
+         following:\n\n{}",
+        failures.join("\n")
+    );
+
+    Ok(())
+}
+
+fn has_machine_applicable_suggestion(diagnostic: &Diagnostic) -> bool {
+    diagnostic
+        .spans
+        .iter()
+        .any(|span| span.suggestion_applicability == Some(Applicability::MachineApplicable))
+        || diagnostic
+            .children
+            .iter()
+            .any(has_machine_applicable_suggestion)
+}
+
+// Returns the `.rs` files in `src_base` whose paths match one of `filters` (or all of
+// them, if `filters` is empty), sorted.
+fn source_files(src_base: &Path, filters: &[String]) -> Result<Vec<PathBuf>> {
+    let mut paths = read_dir(src_base)
+        .with_context(|| format!("`read_dir` failed for {src_base:?}"))?
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .with_context(|| format!("`read_dir` failed for {src_base:?}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    paths.retain(|path| {
+        let path_str = path.to_string_lossy();
+        path.extension() == Some(OsStr::new("rs"))
+            && (filters.is_empty() || filters.iter().any(|filter| path_str.contains(filter)))
+    });
+    paths.sort();
+    Ok(paths)
+}
+
+// `compiletest` offers no way to bound a test's running time. So run the tests on a
+// worker thread and wait for them for at most `timeout`. A thread cannot be stopped from outside,
+// and a panic here would leave the worker running (along with any driver it is waiting on). So
+// on timeout, the whole process is aborted.
+fn run_tests_with_timeout(config: compiletest::Config, timeout: Duration) -> thread::Result<()> {
+    let src_base = config.src_base.clone();
+    let (sender, receiver) = channel();
+    spawn(move || {
+        let result = catch_unwind(AssertUnwindSafe(|| compiletest::run_tests(&config)));
+        let _ = sender.send(result);
+    });
+    receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+        eprintln!(
+            "test timed out after {timeout:?}\nsrc_base: {}",
+            src_base.to_string_lossy()
+        );
+        std::process::abort()
+    })
 }
 
 // smoelius: `VarGuard` was copied from:
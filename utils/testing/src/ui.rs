@@ -1,7 +1,12 @@
-use crate::{example_target, example_targets, initialize, run_example_test, run_tests};
+use crate::{
+    example_target, example_targets, initialize, manifest_linking_flags, run_example_test,
+    run_tests,
+};
+use regex::Regex;
 use std::{
-    env::current_dir,
+    env::{consts, current_dir},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 enum Target {
@@ -10,10 +15,41 @@ enum Target {
     Examples,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub(super) struct Config {
     pub(super) rustc_flags: Vec<String>,
     pub(super) dylint_toml: Option<String>,
+    pub(super) filters: Vec<String>,
+    pub(super) normalizations: Vec<(String, String)>,
+    pub(super) toolchain: Option<String>,
+    pub(super) allow_empty: bool,
+    pub(super) check_fixed_idempotent: bool,
+    pub(super) timeout: Option<Duration>,
+    pub(super) expect_no_diagnostics: bool,
+    pub(super) only_on: Vec<String>,
+    pub(super) manifest_path: Option<PathBuf>,
+    pub(super) ui_testing: bool,
+    pub(super) driver: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rustc_flags: Vec::new(),
+            dylint_toml: None,
+            filters: Vec::new(),
+            normalizations: Vec::new(),
+            toolchain: None,
+            allow_empty: false,
+            check_fixed_idempotent: false,
+            timeout: None,
+            expect_no_diagnostics: false,
+            only_on: Vec::new(),
+            manifest_path: None,
+            ui_testing: true,
+            driver: None,
+        }
+    }
 }
 
 /// Test builder
@@ -65,6 +101,118 @@ impl Test {
         self
     }
 
+    /// Run only source files whose names match `pattern`.
+    ///
+    /// The environment variable `DYLINT_TESTNAME`, if set, is used as an additional filter.
+    pub fn filter(&mut self, pattern: &str) -> &mut Self {
+        self.config.filters.push(pattern.to_owned());
+        self
+    }
+
+    /// Replace matches of `regex` with `replacement` in the test's standard error before it is
+    /// compared to the `.stderr` file.
+    ///
+    /// On Windows, backslashes in the paths of spans are rewritten to forward slashes before any
+    /// replacements are made.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `regex` is not a valid regular expression.
+    pub fn normalize(&mut self, regex: &str, replacement: &str) -> &mut Self {
+        Regex::new(regex).unwrap();
+        self.config
+            .normalizations
+            .push((regex.to_owned(), replacement.to_owned()));
+        self
+    }
+
+    /// Build the library and driver for, and run the test with, `toolchain` rather than the
+    /// toolchain the test was compiled with.
+    ///
+    /// All tests run by one process must use the same toolchain.
+    pub fn toolchain(&mut self, toolchain: &str) -> &mut Self {
+        self.config.toolchain = Some(toolchain.to_owned());
+        self
+    }
+
+    /// Do not fail if the package has no example targets. Without this, a test constructed with
+    /// [`Test::examples`] fails if there are no example targets to run it on.
+    pub fn allow_empty(&mut self) -> &mut Self {
+        self.config.allow_empty = true;
+        self
+    }
+
+    /// After the test passes, run the library on each source file's `.fixed` file (if any), and
+    /// fail if doing so produces any machine-applicable suggestions. This catches lints whose fixes
+    /// re-trigger themselves.
+    pub fn check_fixed_idempotent(&mut self) -> &mut Self {
+        self.config.check_fixed_idempotent = true;
+        self
+    }
+
+    /// Abort the test binary if the test does not finish within `timeout`.
+    ///
+    /// **Note:** The whole process is aborted, so every other test in the same test binary is
+    /// killed too, and none of them reports a result. Aborting, rather than failing the test,
+    /// ensures that a hung test does not keep running in the background.
+    ///
+    /// Each driver process the test spawns also exits with an error if it runs longer than
+    /// `timeout`.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Expect the source files to produce no diagnostics. Rather than comparing each file's
+    /// standard error to a `.stderr` file, the test fails if any file's standard error is
+    /// non-empty, and the failure lists the unexpected diagnostics. This is useful for
+    /// false-positive regression tests, which otherwise need empty `.stderr` files.
+    pub fn expect_no_diagnostics(&mut self) -> &mut Self {
+        self.config.expect_no_diagnostics = true;
+        self
+    }
+
+    /// Link the source files against the dependencies of the package at `manifest_path`, as they
+    /// are resolved in that package's workspace. The dependencies are built as needed. This lets a
+    /// test constructed with [`Test::src_base`] use crates that the library under test does not
+    /// depend on, without resorting to example targets.
+    pub fn manifest_path(&mut self, manifest_path: impl AsRef<Path>) -> &mut Self {
+        self.config.manifest_path = Some(manifest_path.as_ref().to_owned());
+        self
+    }
+
+    /// Run the test only on hosts matching one of `requirements`, and skip it (without failing)
+    /// on all others. Each requirement is compared to the host's architecture, operating system,
+    /// and family, i.e., to [`std::env::consts::ARCH`], [`std::env::consts::OS`], and
+    /// [`std::env::consts::FAMILY`]. For example, `only_on(&["x86_64"])` skips the test on
+    /// non-x86-64 hosts, and `only_on(&["linux", "macos"])` skips the test on Windows.
+    pub fn only_on(&mut self, requirements: &[&str]) -> &mut Self {
+        self.config
+            .only_on
+            .extend(requirements.iter().map(|&s| s.to_owned()));
+        self
+    }
+
+    /// Control whether `-Zui-testing` is passed to the compiler (the default is `true`).
+    /// `-Zui-testing` normalizes some parts of the compiler's output, e.g., it omits line numbers
+    /// from source excerpts. Turning it off may make `.stderr` files less portable, but it allows
+    /// testing lints whose output is sensitive to that normalization.
+    pub fn ui_testing(&mut self, ui_testing: bool) -> &mut Self {
+        self.config.ui_testing = ui_testing;
+        self
+    }
+
+    /// Run the test with the driver at `driver` rather than one built by Dylint. This is useful
+    /// when developing the driver, as it allows testing a locally built driver without installing
+    /// it. The driver must have been built for the toolchain the test uses, and its version must
+    /// match `dylint_testing`'s.
+    ///
+    /// All tests run by one process must use the same driver.
+    pub fn driver(&mut self, driver: impl AsRef<Path>) -> &mut Self {
+        self.config.driver = Some(driver.as_ref().to_owned());
+        self
+    }
+
     /// Run the test.
     #[allow(clippy::needless_pass_by_ref_mut)]
     pub fn run(&mut self) {
@@ -80,11 +228,37 @@ impl Test {
     }
 
     fn run_immutable(&self) {
-        let driver = initialize(&self.name).unwrap();
+        if !self.config.only_on.is_empty()
+            && !self.config.only_on.iter().any(|requirement| {
+                [consts::ARCH, consts::OS, consts::FAMILY].contains(&requirement.as_str())
+            })
+        {
+            eprintln!(
+                "Skipping test of `{}`: host does not match any of {:?}",
+                self.name, self.config.only_on
+            );
+            return;
+        }
+
+        let driver = initialize(
+            &self.name,
+            self.config.toolchain.as_deref(),
+            self.config.driver.as_deref(),
+        )
+        .unwrap();
 
         match &self.target {
             Target::SrcBase(src_base) => {
-                run_tests(driver, src_base, &self.config);
+                if let Some(manifest_path) = &self.config.manifest_path {
+                    let mut config = self.config.clone();
+                    config.rustc_flags.extend(
+                        manifest_linking_flags(manifest_path, self.config.toolchain.as_deref())
+                            .unwrap(),
+                    );
+                    run_tests(driver, src_base, &config);
+                } else {
+                    run_tests(driver, src_base, &self.config);
+                }
             }
             Target::Example(example) => {
                 let metadata = dylint_internal::cargo::current_metadata().unwrap();
@@ -102,6 +276,12 @@ impl Test {
                     dylint_internal::cargo::package_with_root(&metadata, &current_dir).unwrap();
                 let targets = example_targets(&package).unwrap();
 
+                assert!(
+                    self.config.allow_empty || !targets.is_empty(),
+                    "package `{}` has no example targets (use `allow_empty` if this is intended)",
+                    package.name
+                );
+
                 for target in targets {
                     run_example_test(driver, &metadata, &package, &target, &self.config).unwrap();
                 }
@@ -114,9 +294,75 @@ impl Test {
 mod test {
     use super::*;
 
+    // Verify that `only_on` compiles when used as intended.
+    #[allow(dead_code)]
+    fn only_on() {
+        let _ = Test::src_base("name", PathBuf::new()).only_on(&["x86_64"]);
+    }
+
     // smoelius: Verify that `rustc_flags` compiles when used as intended.
     #[allow(dead_code)]
     fn rustc_flags() {
         let _ = Test::src_base("name", PathBuf::new()).rustc_flags(["--test"]);
     }
+
+    // Verify that `allow_empty` compiles when used as intended.
+    #[allow(dead_code)]
+    fn allow_empty() {
+        let _ = Test::examples("name").allow_empty();
+    }
+
+    // Verify that `check_fixed_idempotent` compiles when used as intended.
+    #[allow(dead_code)]
+    fn check_fixed_idempotent() {
+        let _ = Test::example("name", "example").check_fixed_idempotent();
+    }
+
+    // Verify that `driver` compiles when used as intended.
+    #[allow(dead_code)]
+    fn driver() {
+        let _ = Test::src_base("name", PathBuf::new()).driver("target/debug/dylint-driver");
+    }
+
+    // Verify that `expect_no_diagnostics` compiles when used as intended.
+    #[allow(dead_code)]
+    fn expect_no_diagnostics() {
+        let _ = Test::src_base("name", PathBuf::new()).expect_no_diagnostics();
+    }
+
+    // Verify that `filter` compiles when used as intended.
+    #[allow(dead_code)]
+    fn filter() {
+        let _ = Test::src_base("name", PathBuf::new()).filter("main");
+    }
+
+    // Verify that `manifest_path` compiles when used as intended.
+    #[allow(dead_code)]
+    fn manifest_path() {
+        let _ = Test::src_base("name", PathBuf::new()).manifest_path("fixture/Cargo.toml");
+    }
+
+    // Verify that `normalize` compiles when used as intended.
+    #[allow(dead_code)]
+    fn normalize() {
+        let _ = Test::src_base("name", PathBuf::new()).normalize(r"\d+ms", "Nms");
+    }
+
+    // Verify that `ui_testing` compiles when used as intended.
+    #[allow(dead_code)]
+    fn ui_testing() {
+        let _ = Test::src_base("name", PathBuf::new()).ui_testing(false);
+    }
+
+    // Verify that `timeout` compiles when used as intended.
+    #[allow(dead_code)]
+    fn timeout() {
+        let _ = Test::src_base("name", PathBuf::new()).timeout(Duration::from_secs(60));
+    }
+
+    // Verify that `toolchain` compiles when used as intended.
+    #[allow(dead_code)]
+    fn toolchain() {
+        let _ = Test::src_base("name", PathBuf::new()).toolchain("nightly");
+    }
 }